@@ -0,0 +1,112 @@
+//! Pixel color notations shown by the pixel inspector
+
+/// `#RRGGBB`, or `#RRGGBBAA` for translucent pixels
+pub fn hex(rgba: [u8; 4]) -> String {
+    let [r, g, b, a] = rgba;
+    if a == 255 {
+        format!("#{r:02X}{g:02X}{b:02X}")
+    } else {
+        format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+    }
+}
+
+/// `#RRGGBB` or `#RGB`, with or without the `#`
+pub fn parse_hex(text: &str) -> Option<[u8; 3]> {
+    let digits = text.trim().trim_start_matches('#');
+    if !digits.is_ascii() {
+        return None;
+    }
+
+    match digits.len() {
+        6 => {
+            let channel = |idx: usize| u8::from_str_radix(&digits[idx..idx + 2], 16).ok();
+            Some([channel(0)?, channel(2)?, channel(4)?])
+        }
+        3 => {
+            let channel = |idx: usize| {
+                u8::from_str_radix(&digits[idx..idx + 1], 16)
+                    .ok()
+                    .map(|v| v * 17)
+            };
+            Some([channel(0)?, channel(1)?, channel(2)?])
+        }
+        _ => None,
+    }
+}
+
+/// Hue in degrees, saturation and lightness from 0 to 1
+pub fn hsl(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let [r, g, b] = [r, g, b].map(|v| v as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    (hue, saturation, lightness)
+}
+
+/// `hsl(210, 50%, 40%)`
+pub fn hsl_label(r: u8, g: u8, b: u8) -> String {
+    let (hue, saturation, lightness) = hsl(r, g, b);
+    format!(
+        "hsl({}, {}%, {}%)",
+        hue.round() as u32 % 360,
+        (saturation * 100.0).round() as u32,
+        (lightness * 100.0).round() as u32
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_opaque_and_translucent() {
+        assert_eq!(hex([255, 128, 0, 255]), "#FF8000");
+        assert_eq!(hex([1, 2, 3, 128]), "#01020380");
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_hex("#FF8000"), Some([255, 128, 0]));
+        assert_eq!(parse_hex(" 1a2b3c "), Some([0x1a, 0x2b, 0x3c]));
+        assert_eq!(parse_hex("#fff"), Some([255, 255, 255]));
+        assert_eq!(parse_hex("#12345"), None);
+        assert_eq!(parse_hex("#GG0000"), None);
+        assert_eq!(parse_hex("#ééé"), None);
+    }
+
+    #[test]
+    fn test_hsl_primaries() {
+        assert_eq!(hsl(255, 0, 0), (0.0, 1.0, 0.5));
+        assert_eq!(hsl(0, 255, 0), (120.0, 1.0, 0.5));
+        assert_eq!(hsl(0, 0, 255), (240.0, 1.0, 0.5));
+    }
+
+    #[test]
+    fn test_hsl_grays_have_no_hue() {
+        assert_eq!(hsl(0, 0, 0), (0.0, 0.0, 0.0));
+        assert_eq!(hsl(255, 255, 255), (0.0, 0.0, 1.0));
+        let (hue, saturation, _) = hsl(128, 128, 128);
+        assert_eq!((hue, saturation), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_hsl_label() {
+        assert_eq!(hsl_label(255, 0, 255), "hsl(300, 100%, 50%)");
+        assert_eq!(hsl_label(51, 102, 153), "hsl(210, 50%, 40%)");
+    }
+}
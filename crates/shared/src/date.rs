@@ -0,0 +1,116 @@
+//! Calendar date helpers that avoid pulling in a full date/time crate
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date (proleptic Gregorian, UTC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CalendarDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl CalendarDate {
+    pub fn new(year: i32, month: u32, day: u32) -> Self {
+        Self { year, month, day }
+    }
+
+    /// Date of a unix timestamp in seconds
+    pub fn from_unix_secs(secs: i64) -> Self {
+        let days = secs.div_euclid(86_400);
+        civil_from_days(days)
+    }
+
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let secs = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i64,
+            Err(err) => -(err.duration().as_secs() as i64),
+        };
+        Self::from_unix_secs(secs)
+    }
+
+    /// Parse the date part of an EXIF `DateTime` value ("YYYY:MM:DD HH:MM:SS")
+    pub fn from_exif(value: &str) -> Option<Self> {
+        let date = value.trim().split(' ').next()?;
+        let mut parts = date.split([':', '-']);
+
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+
+        // Cameras without a clock set write all zeroes
+        if year == 0 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+
+        Some(Self { year, month, day })
+    }
+}
+
+impl std::fmt::Display for CalendarDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Convert days since 1970-01-01 to a calendar date.
+///
+/// Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> CalendarDate {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400 + (month <= 2) as i64) as i32;
+
+    CalendarDate { year, month, day }
+}
+
+/// Format a unix timestamp in seconds as "YYYY-MM-DD"
+pub fn format_unix_date(secs: i64) -> String {
+    CalendarDate::from_unix_secs(secs).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_unix_secs() {
+        assert_eq!(
+            CalendarDate::from_unix_secs(0),
+            CalendarDate::new(1970, 1, 1)
+        );
+        assert_eq!(
+            CalendarDate::from_unix_secs(951_782_400),
+            CalendarDate::new(2000, 2, 29)
+        );
+        assert_eq!(
+            CalendarDate::from_unix_secs(1_700_000_000),
+            CalendarDate::new(2023, 11, 14)
+        );
+        assert_eq!(
+            CalendarDate::from_unix_secs(-1),
+            CalendarDate::new(1969, 12, 31)
+        );
+    }
+
+    #[test]
+    fn test_from_exif() {
+        assert_eq!(
+            CalendarDate::from_exif("2021:07:04 12:30:00"),
+            Some(CalendarDate::new(2021, 7, 4))
+        );
+        assert_eq!(CalendarDate::from_exif("0000:00:00 00:00:00"), None);
+        assert_eq!(CalendarDate::from_exif("garbage"), None);
+    }
+
+    #[test]
+    fn test_format_unix_date() {
+        assert_eq!(format_unix_date(1_700_000_000), "2023-11-14");
+    }
+}
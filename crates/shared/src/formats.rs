@@ -0,0 +1,427 @@
+//! Registry of the image formats the viewer opens
+//!
+//! Folder scanning, the loader and the supported format listing all go through
+//! this table, so a format is added in one place. Files are recognised by the
+//! magic bytes at the start of the file, so a PNG saved as `.jpg` still opens,
+//! and by extension when their content has no signature.
+
+use std::path::Path;
+
+/// Bytes of the file header [`ImageFormat::sniff`] needs to tell every format apart
+pub const SNIFF_LEN: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    WebP,
+    Bmp,
+    Tiff,
+    Ico,
+    Avif,
+    Heif,
+    JpegXl,
+    Qoi,
+    Hdr,
+    OpenExr,
+    Farbfeld,
+    Pnm,
+    /// SVG documents, plain or gzip compressed
+    Svg,
+    /// Photoshop documents, shown by their merged image
+    Psd,
+    /// GIMP documents, shown by compositing their layers
+    Xcf,
+    /// Krita documents, shown by their merged image
+    Kra,
+    /// Camera raw files, most of them TIFF based
+    Raw,
+}
+
+impl ImageFormat {
+    pub const ALL: &[ImageFormat] = &[
+        Self::Png,
+        Self::Jpeg,
+        Self::Gif,
+        Self::WebP,
+        Self::Bmp,
+        Self::Tiff,
+        Self::Ico,
+        Self::Avif,
+        Self::Heif,
+        Self::JpegXl,
+        Self::Qoi,
+        Self::Hdr,
+        Self::OpenExr,
+        Self::Farbfeld,
+        Self::Pnm,
+        Self::Svg,
+        Self::Psd,
+        Self::Xcf,
+        Self::Kra,
+        Self::Raw,
+    ];
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Png => "PNG",
+            Self::Jpeg => "JPEG",
+            Self::Gif => "GIF",
+            Self::WebP => "WebP",
+            Self::Bmp => "BMP",
+            Self::Tiff => "TIFF",
+            Self::Ico => "ICO",
+            Self::Avif => "AVIF",
+            Self::Heif => "HEIF",
+            Self::JpegXl => "JPEG XL",
+            Self::Qoi => "QOI",
+            Self::Hdr => "Radiance HDR",
+            Self::OpenExr => "OpenEXR",
+            Self::Farbfeld => "Farbfeld",
+            Self::Pnm => "PNM",
+            Self::Svg => "SVG",
+            Self::Psd => "Photoshop",
+            Self::Xcf => "GIMP XCF",
+            Self::Kra => "Krita",
+            Self::Raw => "Camera RAW",
+        }
+    }
+
+    /// Lowercase file extensions, the preferred one first
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Png => &["png"],
+            Self::Jpeg => &["jpg", "jpeg"],
+            Self::Gif => &["gif"],
+            Self::WebP => &["webp"],
+            Self::Bmp => &["bmp"],
+            Self::Tiff => &["tiff", "tif"],
+            Self::Ico => &["ico"],
+            Self::Avif => &["avif"],
+            Self::Heif => &["heic", "heif"],
+            Self::JpegXl => &["jxl"],
+            Self::Qoi => &["qoi"],
+            Self::Hdr => &["hdr"],
+            Self::OpenExr => &["exr"],
+            Self::Farbfeld => &["ff", "farbfeld"],
+            Self::Pnm => &["ppm", "pgm", "pbm", "pnm"],
+            Self::Svg => &["svg", "svgz"],
+            Self::Psd => &["psd", "psb"],
+            Self::Xcf => &["xcf"],
+            Self::Kra => &["kra"],
+            Self::Raw => &["raw", "cr2", "cr3", "nef", "arw", "dng", "orf", "rw2"],
+        }
+    }
+
+    /// Whether this build can decode the format; HEIF needs the `heif` feature
+    pub fn is_enabled(self) -> bool {
+        match self {
+            Self::Heif => cfg!(feature = "heif"),
+            _ => true,
+        }
+    }
+
+    /// Whether the format has no fixed resolution and is rendered at the zoom it is shown at
+    pub fn is_vector(self) -> bool {
+        self == Self::Svg
+    }
+
+    /// Whether the format is an editor document with layers, shown flattened
+    pub fn is_layered(self) -> bool {
+        matches!(self, Self::Psd | Self::Xcf | Self::Kra)
+    }
+
+    /// Enabled format with the extension, case-insensitive
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let extension = extension.to_lowercase();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|format| format.is_enabled() && format.extensions().contains(&extension.as_str()))
+    }
+
+    /// Enabled format named by the path's extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    /// Format of a file from its first [`SNIFF_LEN`] bytes, falling back to the extension
+    pub fn detect(header: &[u8], path: &Path) -> Option<Self> {
+        let by_extension = Self::from_path(path);
+
+        match Self::sniff(header) {
+            // Most camera raws are TIFF files inside, keep them on the raw path
+            Some(Self::Tiff) if by_extension == Some(Self::Raw) => by_extension,
+            Some(format) => Some(format),
+            None => by_extension,
+        }
+    }
+
+    /// Whether the extension of `path` names another format than its content
+    pub fn extension_mismatch(path: &Path, detected: Self) -> bool {
+        Self::from_path(path).is_some_and(|format| format != detected)
+    }
+
+    /// Recognise a format from the first [`SNIFF_LEN`] bytes of a file
+    pub fn sniff(header: &[u8]) -> Option<Self> {
+        let format = if header.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Self::Png
+        } else if header.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Self::Jpeg
+        } else if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+            Self::Gif
+        } else if header.starts_with(b"RIFF") && header.get(8..12) == Some(b"WEBP") {
+            Self::WebP
+        } else if header.starts_with(b"II*\0") || header.starts_with(b"MM\0*") {
+            Self::Tiff
+        } else if header.starts_with(b"IIRO") || header.starts_with(b"IIU\0") {
+            // Olympus and Panasonic raws use their own TIFF magic
+            Self::Raw
+        } else if header.starts_with(&[0, 0, 1, 0]) {
+            Self::Ico
+        } else if header.starts_with(&[0xFF, 0x0A])
+            || header.starts_with(b"\0\0\0\x0cJXL \r\n\x87\n")
+        {
+            Self::JpegXl
+        } else if header.starts_with(b"qoif") {
+            Self::Qoi
+        } else if header.starts_with(b"#?RADIANCE") || header.starts_with(b"#?RGBE") {
+            Self::Hdr
+        } else if header.starts_with(&[0x76, 0x2F, 0x31, 0x01]) {
+            Self::OpenExr
+        } else if header.starts_with(b"farbfeld") {
+            Self::Farbfeld
+        } else if header.starts_with(b"8BPS") {
+            Self::Psd
+        } else if header.starts_with(b"gimp xcf ") {
+            Self::Xcf
+        } else if is_krita_document(header) {
+            Self::Kra
+        } else if header.starts_with(b"BM") {
+            Self::Bmp
+        } else if header.first() == Some(&b'P')
+            && header
+                .get(1)
+                .is_some_and(|kind| (b'1'..=b'7').contains(kind))
+            && header.get(2).is_some_and(u8::is_ascii_whitespace)
+        {
+            Self::Pnm
+        } else if is_svg_document(header) {
+            Self::Svg
+        } else {
+            Self::sniff_iso_media(header)?
+        };
+
+        format.is_enabled().then_some(format)
+    }
+
+    /// AVIF, HEIF and CR3 share the ISO base media container and differ in their brand
+    fn sniff_iso_media(header: &[u8]) -> Option<Self> {
+        if header.get(4..8) != Some(b"ftyp") {
+            return None;
+        }
+
+        match header.get(8..12)? {
+            b"avif" | b"avis" => Some(Self::Avif),
+            b"heic" | b"heix" | b"hevc" | b"hevx" | b"heim" | b"heis" | b"mif1" | b"msf1" => {
+                Some(Self::Heif)
+            }
+            b"crx " => Some(Self::Raw),
+            _ => None,
+        }
+    }
+}
+
+/// SVG documents may start with an XML declaration, comments or a doctype
+/// before the root element, so look for it in the whole header
+fn is_svg_document(header: &[u8]) -> bool {
+    let text = header.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(header);
+    let text = &text[text.len() - text.trim_ascii_start().len()..];
+
+    text.starts_with(b"<")
+        && text
+            .windows(4)
+            .any(|window| window.eq_ignore_ascii_case(b"<svg"))
+}
+
+/// Krita documents are zip archives that start with a stored `mimetype` entry
+fn is_krita_document(header: &[u8]) -> bool {
+    header.starts_with(b"PK\x03\x04")
+        && header.get(30..38) == Some(b"mimetype")
+        && header
+            .windows(19)
+            .any(|window| window == b"application/x-krita")
+}
+
+/// Whether the extension belongs to an enabled format, case-insensitive
+pub fn is_supported_extension(extension: &str) -> bool {
+    ImageFormat::from_extension(extension).is_some()
+}
+
+/// Extensions of all enabled formats
+pub fn supported_extensions() -> impl Iterator<Item = &'static str> {
+    ImageFormat::ALL
+        .iter()
+        .filter(|format| format.is_enabled())
+        .flat_map(|format| format.extensions().iter().copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_extension() {
+        assert_eq!(ImageFormat::from_extension("JPG"), Some(ImageFormat::Jpeg));
+        assert_eq!(
+            ImageFormat::from_extension("jxl"),
+            Some(ImageFormat::JpegXl)
+        );
+        assert_eq!(
+            ImageFormat::from_extension("exr"),
+            Some(ImageFormat::OpenExr)
+        );
+        assert_eq!(ImageFormat::from_extension("txt"), None);
+        assert_eq!(
+            ImageFormat::from_path(Path::new("/photos/scan.TIF")),
+            Some(ImageFormat::Tiff)
+        );
+        assert_eq!(ImageFormat::from_path(Path::new("/photos/README")), None);
+    }
+
+    #[test]
+    fn test_extensions_are_unique() {
+        let mut extensions: Vec<&str> = ImageFormat::ALL
+            .iter()
+            .flat_map(|format| format.extensions().iter().copied())
+            .collect();
+        let total = extensions.len();
+        extensions.sort_unstable();
+        extensions.dedup();
+        assert_eq!(extensions.len(), total);
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(
+            ImageFormat::sniff(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::sniff(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(
+            ImageFormat::sniff(b"RIFF\x24\0\0\0WEBPVP8 "),
+            Some(ImageFormat::WebP)
+        );
+        assert_eq!(
+            ImageFormat::sniff(b"II*\0\x08\0\0\0"),
+            Some(ImageFormat::Tiff)
+        );
+        assert_eq!(
+            ImageFormat::sniff(&[0xFF, 0x0A, 0xFA]),
+            Some(ImageFormat::JpegXl)
+        );
+        assert_eq!(
+            ImageFormat::sniff(b"\0\0\0\x0cJXL \r\n\x87\n\0\0\0\x14ftypjxl "),
+            Some(ImageFormat::JpegXl)
+        );
+        assert_eq!(
+            ImageFormat::sniff(b"qoif\0\0\0\x10"),
+            Some(ImageFormat::Qoi)
+        );
+        assert_eq!(
+            ImageFormat::sniff(b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe"),
+            Some(ImageFormat::Hdr)
+        );
+        assert_eq!(
+            ImageFormat::sniff(&[0x76, 0x2F, 0x31, 0x01, 2, 0, 0, 0]),
+            Some(ImageFormat::OpenExr)
+        );
+        assert_eq!(
+            ImageFormat::sniff(b"\0\0\0\x1cftypavif\0\0\0\0"),
+            Some(ImageFormat::Avif)
+        );
+        assert_eq!(
+            ImageFormat::sniff(b"P6\n640 480\n255\n"),
+            Some(ImageFormat::Pnm)
+        );
+        assert_eq!(
+            ImageFormat::sniff(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg xmlns="),
+            Some(ImageFormat::Svg)
+        );
+        assert_eq!(
+            ImageFormat::sniff(b"  <svg width=\"16\""),
+            Some(ImageFormat::Svg)
+        );
+        assert_eq!(
+            ImageFormat::sniff(b"8BPS\0\x01\0\0\0\0\0\0\0\x03"),
+            Some(ImageFormat::Psd)
+        );
+        assert_eq!(
+            ImageFormat::sniff(b"gimp xcf v011\0"),
+            Some(ImageFormat::Xcf)
+        );
+        let mut krita = b"PK\x03\x04".to_vec();
+        krita.resize(30, 0);
+        krita.extend_from_slice(b"mimetypeapplication/x-krita");
+        assert_eq!(ImageFormat::sniff(&krita), Some(ImageFormat::Kra));
+        assert_eq!(ImageFormat::sniff(b"PK\x03\x04\x14\0\0\0"), None);
+        assert_eq!(ImageFormat::sniff(b"<?xml version=\"1.0\"?><feed>"), None);
+        assert_eq!(ImageFormat::sniff(b"Plain text"), None);
+        assert_eq!(ImageFormat::sniff(b""), None);
+    }
+
+    #[test]
+    fn test_detect_prefers_content() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        let tiff = b"II*\0\x08\0\0\0";
+
+        assert_eq!(
+            ImageFormat::detect(png, Path::new("download.jpg")),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::detect(png, Path::new("download")),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::detect(tiff, Path::new("camera.NEF")),
+            Some(ImageFormat::Raw)
+        );
+        assert_eq!(
+            ImageFormat::detect(b"\x1f\x8b\x08", Path::new("icon.svgz")),
+            Some(ImageFormat::Svg)
+        );
+        assert_eq!(ImageFormat::detect(b"text", Path::new("notes")), None);
+
+        assert!(ImageFormat::extension_mismatch(
+            Path::new("download.jpg"),
+            ImageFormat::Png
+        ));
+        assert!(!ImageFormat::extension_mismatch(
+            Path::new("download.JPEG"),
+            ImageFormat::Jpeg
+        ));
+        assert!(!ImageFormat::extension_mismatch(
+            Path::new("download"),
+            ImageFormat::Png
+        ));
+    }
+
+    #[test]
+    fn test_supported_extensions() {
+        assert!(is_supported_extension("qoi"));
+        assert!(is_supported_extension("CR2"));
+        assert!(!is_supported_extension("mp4"));
+        assert!(supported_extensions().any(|ext| ext == "hdr"));
+        assert!(is_supported_extension("PSD"));
+        assert!(ImageFormat::Kra.is_layered());
+        assert!(!ImageFormat::Png.is_layered());
+    }
+}
@@ -0,0 +1,225 @@
+//! Channel histograms and clipping statistics of 8 bit RGBA images
+
+/// Share of pixels at either end of the range above which clipping is reported
+pub const CLIPPING_THRESHOLD: f32 = 0.005;
+
+/// Mask color of pixels with a channel at full intensity
+const HIGHLIGHT_MASK: [u8; 4] = [255, 0, 0, 255];
+/// Mask color of pixels with every channel at zero
+const SHADOW_MASK: [u8; 4] = [0, 96, 255, 255];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    pub red: [u32; 256],
+    pub green: [u32; 256],
+    pub blue: [u32; 256],
+    pub luma: [u32; 256],
+    /// Pixels counted; fully transparent ones are left out
+    pub total: u64,
+    /// Pixels with a channel at full intensity
+    pub highlights: u64,
+    /// Pixels with every channel at zero
+    pub shadows: u64,
+}
+
+impl Histogram {
+    pub fn from_rgba(pixels: &[u8]) -> Self {
+        let mut histogram = Self {
+            red: [0; 256],
+            green: [0; 256],
+            blue: [0; 256],
+            luma: [0; 256],
+            total: 0,
+            highlights: 0,
+            shadows: 0,
+        };
+
+        for pixel in pixels.chunks_exact(4) {
+            let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+            if a == 0 {
+                continue;
+            }
+
+            histogram.red[r as usize] += 1;
+            histogram.green[g as usize] += 1;
+            histogram.blue[b as usize] += 1;
+            histogram.luma[luma(r, g, b) as usize] += 1;
+            histogram.total += 1;
+
+            if is_highlight_clipped(r, g, b) {
+                histogram.highlights += 1;
+            } else if is_shadow_clipped(r, g, b) {
+                histogram.shadows += 1;
+            }
+        }
+
+        histogram
+    }
+
+    /// Height the bars are scaled to. The end bins are left out, clipped images
+    /// pile up there and would flatten everything else.
+    pub fn peak(&self) -> u32 {
+        [&self.red, &self.green, &self.blue, &self.luma]
+            .iter()
+            .flat_map(|channel| channel[1..255].iter())
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(1)
+    }
+
+    /// Share of pixels with a channel at full intensity
+    pub fn highlight_fraction(&self) -> f32 {
+        fraction(self.highlights, self.total)
+    }
+
+    /// Share of pixels with every channel at zero
+    pub fn shadow_fraction(&self) -> f32 {
+        fraction(self.shadows, self.total)
+    }
+
+    pub fn highlights_clipped(&self) -> bool {
+        self.highlight_fraction() > CLIPPING_THRESHOLD
+    }
+
+    pub fn shadows_clipped(&self) -> bool {
+        self.shadow_fraction() > CLIPPING_THRESHOLD
+    }
+}
+
+fn fraction(count: u64, total: u64) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f32 / total as f32
+    }
+}
+
+/// Luminance with Rec. 709 weights
+pub fn luma(r: u8, g: u8, b: u8) -> u8 {
+    ((54 * r as u32 + 183 * g as u32 + 19 * b as u32) >> 8) as u8
+}
+
+pub fn is_highlight_clipped(r: u8, g: u8, b: u8) -> bool {
+    r == 255 || g == 255 || b == 255
+}
+
+pub fn is_shadow_clipped(r: u8, g: u8, b: u8) -> bool {
+    r == 0 && g == 0 && b == 0
+}
+
+/// RGBA overlay marking clipped pixels, highlights in red and shadows in blue.
+/// Everything else, transparent pixels included, stays transparent.
+pub fn clipping_mask(pixels: &[u8]) -> Vec<u8> {
+    let mut mask = vec![0; pixels.len() / 4 * 4];
+
+    for (pixel, out) in pixels.chunks_exact(4).zip(mask.chunks_exact_mut(4)) {
+        let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+        if a == 0 {
+            continue;
+        }
+
+        if is_highlight_clipped(r, g, b) {
+            out.copy_from_slice(&HIGHLIGHT_MASK);
+        } else if is_shadow_clipped(r, g, b) {
+            out.copy_from_slice(&SHADOW_MASK);
+        }
+    }
+
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_counts_channels() {
+        let pixels = [10, 20, 30, 255, 10, 200, 30, 255];
+        let histogram = Histogram::from_rgba(&pixels);
+
+        assert_eq!(histogram.total, 2);
+        assert_eq!(histogram.red[10], 2);
+        assert_eq!(histogram.green[20], 1);
+        assert_eq!(histogram.green[200], 1);
+        assert_eq!(histogram.blue[30], 2);
+        assert_eq!(histogram.luma.iter().sum::<u32>(), 2);
+    }
+
+    #[test]
+    fn test_histogram_skips_transparent_pixels() {
+        let pixels = [255, 255, 255, 0, 0, 0, 0, 0, 128, 128, 128, 255];
+        let histogram = Histogram::from_rgba(&pixels);
+
+        assert_eq!(histogram.total, 1);
+        assert_eq!(histogram.highlights, 0);
+        assert_eq!(histogram.shadows, 0);
+    }
+
+    #[test]
+    fn test_clipping_fractions() {
+        let mut pixels = Vec::new();
+        pixels.extend_from_slice(&[255, 100, 100, 255]);
+        pixels.extend_from_slice(&[0, 0, 0, 255]);
+        pixels.extend_from_slice(&[0, 0, 0, 255]);
+        pixels.extend_from_slice(&[100, 100, 100, 255]);
+        let histogram = Histogram::from_rgba(&pixels);
+
+        assert_eq!(histogram.highlight_fraction(), 0.25);
+        assert_eq!(histogram.shadow_fraction(), 0.5);
+        assert!(histogram.highlights_clipped());
+        assert!(histogram.shadows_clipped());
+    }
+
+    #[test]
+    fn test_no_clipping_in_midtones() {
+        let pixels: Vec<u8> = (1..=254).flat_map(|v| [v, v, v, 255]).collect();
+        let histogram = Histogram::from_rgba(&pixels);
+
+        assert!(!histogram.highlights_clipped());
+        assert!(!histogram.shadows_clipped());
+        assert_eq!(histogram.peak(), 1);
+    }
+
+    #[test]
+    fn test_peak_ignores_end_bins() {
+        let mut pixels: Vec<u8> = [255, 255, 255, 255].repeat(100);
+        pixels.extend_from_slice(&[50, 60, 70, 255, 50, 60, 70, 255]);
+        let histogram = Histogram::from_rgba(&pixels);
+
+        assert_eq!(histogram.peak(), 2);
+    }
+
+    #[test]
+    fn test_empty_histogram() {
+        let histogram = Histogram::from_rgba(&[]);
+
+        assert_eq!(histogram.total, 0);
+        assert_eq!(histogram.highlight_fraction(), 0.0);
+        assert_eq!(histogram.peak(), 1);
+    }
+
+    #[test]
+    fn test_luma_weights() {
+        assert_eq!(luma(0, 0, 0), 0);
+        assert_eq!(luma(255, 255, 255), 255);
+        assert!(luma(0, 255, 0) > luma(255, 0, 0));
+        assert!(luma(255, 0, 0) > luma(0, 0, 255));
+    }
+
+    #[test]
+    fn test_clipping_mask() {
+        let pixels = [
+            255, 10, 10, 255, // highlight
+            0, 0, 0, 255, // shadow
+            90, 90, 90, 255, // midtone
+            255, 255, 255, 0, // transparent
+        ];
+        let mask = clipping_mask(&pixels);
+
+        assert_eq!(&mask[0..4], &HIGHLIGHT_MASK);
+        assert_eq!(&mask[4..8], &SHADOW_MASK);
+        assert_eq!(&mask[8..12], &[0, 0, 0, 0]);
+        assert_eq!(&mask[12..16], &[0, 0, 0, 0]);
+    }
+}
@@ -4,6 +4,8 @@ use image::{DynamicImage, RgbaImage};
 use std::path::Path;
 use thiserror::Error;
 
+use crate::formats::{self, ImageFormat};
+
 #[derive(Error, Debug)]
 pub enum ImageProcessingError {
     #[error("Failed to load image: {0}")]
@@ -194,16 +196,15 @@ pub fn resize_with_letterbox(
 }
 
 pub fn get_supported_formats() -> Vec<&'static str> {
-    vec![
-        "PNG", "JPEG", "JPG", "GIF", "WebP", "BMP", "TIFF", "TIF", "ICO", "AVIF",
-    ]
+    ImageFormat::ALL
+        .iter()
+        .filter(|format| format.is_enabled())
+        .map(|format| format.name())
+        .collect()
 }
 
 pub fn is_supported_extension(ext: &str) -> bool {
-    matches!(
-        ext.to_lowercase().as_str(),
-        "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" | "tiff" | "tif" | "ico" | "avif"
-    )
+    formats::is_supported_extension(ext)
 }
 
 pub fn format_image_size(size_bytes: u64) -> String {
@@ -0,0 +1,357 @@
+//! Justified-rows gallery layout
+//!
+//! Items keep their aspect ratio and are packed into rows at a target height.
+//! Each full row is then scaled so it fills the available width exactly; the
+//! last row stays at the target height and is left aligned. All coordinates
+//! are relative to the top left of the content area, without padding.
+//!
+//! Layouts can be stacked with `append`, e.g. one per timeline section; items
+//! of collapsed sections then have no cell and are skipped by navigation.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JustifiedCell {
+    pub x: f32,
+    pub width: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JustifiedRow {
+    /// Index of the first item in the row
+    pub start: usize,
+    pub y: f32,
+    pub height: f32,
+    pub cells: Vec<JustifiedCell>,
+}
+
+impl JustifiedRow {
+    pub fn end(&self) -> usize {
+        self.start + self.cells.len()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JustifiedLayout {
+    pub rows: Vec<JustifiedRow>,
+    /// Total height of all rows and the spacing between them
+    pub height: f32,
+}
+
+impl JustifiedLayout {
+    /// Lay out items with the given width / height ratios
+    pub fn new(
+        aspects: impl IntoIterator<Item = f32>,
+        available_width: f32,
+        target_height: f32,
+        spacing: f32,
+    ) -> Self {
+        let mut layout = Self::default();
+        if available_width <= 0.0 || target_height <= 0.0 {
+            return layout;
+        }
+
+        let mut pending: Vec<f32> = Vec::new();
+        let mut start = 0;
+        let mut y = 0.0;
+
+        for aspect in aspects {
+            // Unknown or broken dimensions are treated as square
+            let aspect = if aspect.is_finite() && aspect > 0.0 {
+                aspect
+            } else {
+                1.0
+            };
+            pending.push(aspect);
+
+            let gaps = spacing * (pending.len() - 1) as f32;
+            let natural_width = pending.iter().sum::<f32>() * target_height + gaps;
+
+            if natural_width >= available_width {
+                // Shrink the row so it exactly fills the width
+                let height = (available_width - gaps) / pending.iter().sum::<f32>();
+                layout.push_row(start, y, height, &pending, spacing);
+                y += height + spacing;
+                start += pending.len();
+                pending.clear();
+            }
+        }
+
+        if !pending.is_empty() {
+            layout.push_row(start, y, target_height, &pending, spacing);
+        }
+
+        layout.height = layout.rows.last().map_or(0.0, |row| row.y + row.height);
+        layout
+    }
+
+    /// Fixed size square cells, as many per row as fit, left aligned
+    pub fn uniform(count: usize, available_width: f32, cell_size: f32, spacing: f32) -> Self {
+        let cols = ((available_width + spacing) / (cell_size + spacing))
+            .floor()
+            .max(1.0) as usize;
+
+        let mut layout = Self::default();
+        let mut y = 0.0;
+        for start in (0..count).step_by(cols) {
+            let cells = (0..cols.min(count - start))
+                .map(|col| JustifiedCell {
+                    x: col as f32 * (cell_size + spacing),
+                    width: cell_size,
+                })
+                .collect();
+            layout.rows.push(JustifiedRow {
+                start,
+                y,
+                height: cell_size,
+                cells,
+            });
+            y += cell_size + spacing;
+        }
+
+        layout.height = layout.rows.last().map_or(0.0, |row| row.y + row.height);
+        layout
+    }
+
+    /// Stack `other` below at `y`, its item indices shifted by `start`
+    pub fn append(&mut self, other: JustifiedLayout, start: usize, y: f32) {
+        self.rows.extend(other.rows.into_iter().map(|row| JustifiedRow {
+            start: row.start + start,
+            y: row.y + y,
+            ..row
+        }));
+        self.height = self.height.max(other.height + y);
+    }
+
+    fn push_row(&mut self, start: usize, y: f32, height: f32, aspects: &[f32], spacing: f32) {
+        let mut x = 0.0;
+        let cells = aspects
+            .iter()
+            .map(|aspect| {
+                let cell = JustifiedCell {
+                    x,
+                    width: aspect * height,
+                };
+                x += cell.width + spacing;
+                cell
+            })
+            .collect();
+
+        self.rows.push(JustifiedRow {
+            start,
+            y,
+            height,
+            cells,
+        });
+    }
+
+    /// Number of items in the layout
+    pub fn len(&self) -> usize {
+        self.rows.last().map_or(0, JustifiedRow::end)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Row containing the item at `index`, `None` if the item has no cell
+    pub fn row_of(&self, index: usize) -> Option<usize> {
+        let row = self
+            .rows
+            .partition_point(|row| row.start <= index)
+            .checked_sub(1)?;
+
+        (index < self.rows[row].end()).then_some(row)
+    }
+
+    /// Next item that has a cell
+    pub fn next_item(&self, index: usize) -> Option<usize> {
+        let next = index + 1;
+        let row = self.rows.get(self.rows.partition_point(|row| row.end() <= next))?;
+        Some(next.max(row.start))
+    }
+
+    /// Previous item that has a cell
+    pub fn prev_item(&self, index: usize) -> Option<usize> {
+        let prev = index.checked_sub(1)?;
+        let row = &self.rows[self.rows.partition_point(|row| row.start <= prev).checked_sub(1)?];
+        Some(prev.min(row.end() - 1))
+    }
+
+    pub fn first_item(&self) -> Option<usize> {
+        self.rows.first().map(|row| row.start)
+    }
+
+    pub fn last_item(&self) -> Option<usize> {
+        self.rows.last().map(|row| row.end() - 1)
+    }
+
+    /// `(x, y, width, height)` of the item at `index`
+    pub fn cell_rect(&self, index: usize) -> Option<(f32, f32, f32, f32)> {
+        let row = &self.rows[self.row_of(index)?];
+        let cell = row.cells[index - row.start];
+
+        Some((cell.x, row.y, cell.width, row.height))
+    }
+
+    /// Item under a point, `None` when it falls in the spacing
+    pub fn item_at(&self, x: f32, y: f32) -> Option<usize> {
+        let row_idx = self.rows.partition_point(|row| row.y <= y).checked_sub(1)?;
+        let row = &self.rows[row_idx];
+        if y > row.y + row.height {
+            return None;
+        }
+
+        row.cells
+            .iter()
+            .position(|cell| x >= cell.x && x <= cell.x + cell.width)
+            .map(|col| row.start + col)
+    }
+
+    /// Items in rows intersecting `top..top + height`, plus `overscan` rows on each side
+    pub fn visible_range(&self, top: f32, height: f32, overscan: usize) -> Range<usize> {
+        if self.rows.is_empty() {
+            return 0..0;
+        }
+
+        let first = self
+            .rows
+            .partition_point(|row| row.y + row.height < top)
+            .saturating_sub(overscan);
+        let last = (self.rows.partition_point(|row| row.y <= top + height) + overscan)
+            .min(self.rows.len());
+
+        if first >= last {
+            return 0..0;
+        }
+
+        self.rows[first].start..self.rows[last - 1].end()
+    }
+
+    /// Item in the row above whose center is closest to the current item's center
+    pub fn move_focus_up(&self, index: usize) -> Option<usize> {
+        let row = self.row_of(index)?;
+        self.nearest_in_row(index, row.checked_sub(1)?)
+    }
+
+    /// Item in the row below whose center is closest to the current item's center
+    pub fn move_focus_down(&self, index: usize) -> Option<usize> {
+        let row = self.row_of(index)?;
+        self.nearest_in_row(index, row + 1)
+    }
+
+    fn nearest_in_row(&self, index: usize, row_idx: usize) -> Option<usize> {
+        let (x, _, width, _) = self.cell_rect(index)?;
+        let center = x + width / 2.0;
+        let row = self.rows.get(row_idx)?;
+
+        row.cells
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                let a = (a.x + a.width / 2.0 - center).abs();
+                let b = (b.x + b.width / 2.0 - center).abs();
+                a.total_cmp(&b)
+            })
+            .map(|(col, _)| row.start + col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> JustifiedLayout {
+        // Width 100, target height 20, no spacing:
+        // row 0 = 2:1 + 2:1 + 2:1 (natural 120) scaled to height 100 / 6
+        // row 1 = 4:1 + 1:1 (natural 100) at height 20
+        // row 2 = 1:1 left at the target height
+        JustifiedLayout::new([2.0, 2.0, 2.0, 4.0, 1.0, 1.0], 100.0, 20.0, 0.0)
+    }
+
+    #[test]
+    fn test_rows_fill_width() {
+        let layout = layout();
+        assert_eq!(layout.rows.len(), 3);
+        assert_eq!(layout.len(), 6);
+
+        let row = &layout.rows[0];
+        let width: f32 = row.cells.iter().map(|cell| cell.width).sum();
+        assert!((width - 100.0).abs() < 0.01);
+        assert!(row.height < 20.0);
+
+        assert_eq!(layout.rows[2].height, 20.0);
+        assert_eq!(layout.rows[2].cells[0].width, 20.0);
+    }
+
+    #[test]
+    fn test_spacing_between_cells() {
+        let layout = JustifiedLayout::new([1.0, 1.0], 42.0, 20.0, 2.0);
+        let row = &layout.rows[0];
+        assert_eq!(row.cells[1].x, 22.0);
+        assert_eq!(layout.height, 20.0);
+    }
+
+    #[test]
+    fn test_invalid_aspect_is_square() {
+        let layout = JustifiedLayout::new([f32::NAN, 0.0], 1000.0, 10.0, 0.0);
+        assert_eq!(layout.cell_rect(1), Some((10.0, 0.0, 10.0, 10.0)));
+    }
+
+    #[test]
+    fn test_item_at() {
+        let layout = layout();
+        let row_height = layout.rows[0].height;
+        assert_eq!(layout.item_at(50.0, 1.0), Some(1));
+        assert_eq!(layout.item_at(90.0, row_height + 5.0), Some(4));
+        assert_eq!(layout.item_at(50.0, layout.height + 5.0), None);
+    }
+
+    #[test]
+    fn test_focus_moves_to_nearest_center() {
+        let layout = layout();
+        // The 4:1 panorama spans x 0..80, its center is closest to the middle item above
+        assert_eq!(layout.move_focus_up(3), Some(1));
+        assert_eq!(layout.move_focus_down(0), Some(3));
+        assert_eq!(layout.move_focus_down(2), Some(4));
+        assert_eq!(layout.move_focus_down(4), Some(5));
+        assert_eq!(layout.move_focus_up(0), None);
+        assert_eq!(layout.move_focus_down(5), None);
+    }
+
+    #[test]
+    fn test_uniform_rows() {
+        let layout = JustifiedLayout::uniform(5, 100.0, 30.0, 5.0);
+        assert_eq!(layout.rows.len(), 2);
+        assert_eq!(layout.cell_rect(4), Some((35.0, 35.0, 30.0, 30.0)));
+        assert_eq!(layout.height, 65.0);
+    }
+
+    #[test]
+    fn test_append_skips_hidden_items() {
+        // Items 0..2 visible, 2..5 hidden (collapsed), 5..7 visible
+        let mut layout = JustifiedLayout::uniform(2, 100.0, 10.0, 0.0);
+        layout.append(JustifiedLayout::uniform(2, 100.0, 10.0, 0.0), 5, 50.0);
+
+        assert_eq!(layout.row_of(3), None);
+        assert_eq!(layout.cell_rect(6), Some((10.0, 50.0, 10.0, 10.0)));
+        assert_eq!(layout.height, 60.0);
+
+        assert_eq!(layout.next_item(0), Some(1));
+        assert_eq!(layout.next_item(1), Some(5));
+        assert_eq!(layout.next_item(6), None);
+        assert_eq!(layout.prev_item(5), Some(1));
+        assert_eq!(layout.prev_item(0), None);
+        assert_eq!(layout.last_item(), Some(6));
+    }
+
+    #[test]
+    fn test_visible_range() {
+        let layout = layout();
+        let row_height = layout.rows[0].height;
+        assert_eq!(layout.visible_range(0.0, 1.0, 0), 0..3);
+        assert_eq!(layout.visible_range(row_height + 1.0, 1.0, 0), 3..5);
+        assert_eq!(layout.visible_range(row_height + 1.0, 1.0, 1), 0..6);
+        assert_eq!(JustifiedLayout::default().visible_range(0.0, 10.0, 2), 0..0);
+    }
+}
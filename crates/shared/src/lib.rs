@@ -1,7 +1,22 @@
 pub mod accessibility;
 pub mod cache;
+pub mod color;
+pub mod date;
+pub mod formats;
 pub mod grid_config;
+pub mod histogram;
 pub mod image;
+pub mod justified;
 pub mod loading_state;
+pub mod overlay;
+pub mod perceptual_hash;
+pub mod places;
+pub mod prefetch;
+pub mod reader;
 pub mod screen_reader_labels;
 pub mod thumbnail_metadata;
+pub mod thumbnail_zoom;
+pub mod tiling;
+pub mod timeline;
+pub mod tone_map;
+pub mod viewport;
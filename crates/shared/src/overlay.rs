@@ -0,0 +1,45 @@
+//! Fading of the controls drawn over the fullscreen image
+
+/// Seconds the controls stay after the pointer stops moving
+pub const HIDE_AFTER_SECS: f32 = 2.0;
+/// Seconds a fade in or out takes
+pub const FADE_SECS: f32 = 0.25;
+
+/// Opacity of the controls `shown_for` seconds after they appeared and
+/// `idle_for` seconds after the pointer last moved
+pub fn opacity(shown_for: f32, idle_for: f32) -> f32 {
+    let fade_in = (shown_for / FADE_SECS).clamp(0.0, 1.0);
+    let fade_out = 1.0 - ((idle_for - HIDE_AFTER_SECS) / FADE_SECS).clamp(0.0, 1.0);
+    fade_in.min(fade_out)
+}
+
+/// Whether the controls have faded out completely
+pub fn is_hidden(idle_for: f32) -> bool {
+    idle_for >= HIDE_AFTER_SECS + FADE_SECS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fades_in() {
+        assert_eq!(opacity(0.0, 0.0), 0.0);
+        assert_eq!(opacity(FADE_SECS / 2.0, 0.0), 0.5);
+        assert_eq!(opacity(FADE_SECS, 0.0), 1.0);
+    }
+
+    #[test]
+    fn test_stays_while_active() {
+        assert_eq!(opacity(10.0, 0.0), 1.0);
+        assert_eq!(opacity(10.0, HIDE_AFTER_SECS), 1.0);
+        assert!(!is_hidden(HIDE_AFTER_SECS));
+    }
+
+    #[test]
+    fn test_fades_out_after_inactivity() {
+        assert_eq!(opacity(10.0, HIDE_AFTER_SECS + FADE_SECS / 2.0), 0.5);
+        assert_eq!(opacity(10.0, HIDE_AFTER_SECS + FADE_SECS), 0.0);
+        assert!(is_hidden(HIDE_AFTER_SECS + FADE_SECS));
+    }
+}
@@ -0,0 +1,324 @@
+//! Content and perceptual hashing used for duplicate detection
+//!
+//! All functions operate on raw RGBA8 buffers so they can be fed directly
+//! from the thumbnail decoder without another decode pass.
+
+use std::f32::consts::PI;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Side length of the grayscale image the pHash DCT runs on
+const PHASH_SIZE: usize = 32;
+/// Side length of the low-frequency block kept from the DCT
+const PHASH_LOW: usize = 8;
+
+/// Incremental FNV-1a hasher for exact content matching.
+///
+/// Unlike `DefaultHasher`, the output is stable across Rust releases, so it
+/// can be persisted in the on-disk hash cache.
+#[derive(Debug, Clone, Copy)]
+pub struct ContentHasher {
+    state: u64,
+}
+
+impl ContentHasher {
+    pub fn new() -> Self {
+        Self { state: FNV_OFFSET }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+impl Default for ContentHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hash a complete byte slice
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = ContentHasher::new();
+    hasher.update(bytes);
+    hasher.finish()
+}
+
+/// Difference hash: compares horizontally adjacent pixels of a 9x8 grayscale image
+pub fn dhash(rgba: &[u8], width: u32, height: u32) -> u64 {
+    let gray = to_grayscale(rgba, width, height);
+    let small = resize_gray(&gray, width as usize, height as usize, 9, 8);
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small[y * 9 + x] > small[y * 9 + x + 1] {
+                hash |= 1;
+            }
+        }
+    }
+
+    hash
+}
+
+/// Perceptual hash: low-frequency DCT coefficients of a 32x32 grayscale image
+/// compared against their median
+pub fn phash(rgba: &[u8], width: u32, height: u32) -> u64 {
+    let gray = to_grayscale(rgba, width, height);
+    let small = resize_gray(
+        &gray,
+        width as usize,
+        height as usize,
+        PHASH_SIZE,
+        PHASH_SIZE,
+    );
+    let dct = dct_2d(&small, PHASH_SIZE);
+
+    let mut low = Vec::with_capacity(PHASH_LOW * PHASH_LOW);
+    for v in 0..PHASH_LOW {
+        for u in 0..PHASH_LOW {
+            low.push(dct[v * PHASH_SIZE + u]);
+        }
+    }
+
+    // Skip the DC term, it only encodes average brightness
+    let mut sorted: Vec<f32> = low[1..].to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = sorted[sorted.len() / 2];
+
+    low.iter()
+        .fold(0u64, |hash, &coef| (hash << 1) | (coef > median) as u64)
+}
+
+/// Number of differing bits between two hashes
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Hashes of a single image used for grouping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageHashes {
+    pub content: u64,
+    pub file_size: u64,
+    pub dhash: u64,
+    pub phash: u64,
+}
+
+/// Group images whose content matches exactly or whose perceptual hashes are
+/// within `threshold` bits of each other.
+///
+/// Returns groups of indices into `hashes`, each with at least two members,
+/// in order of their first member.
+pub fn group_similar(hashes: &[ImageHashes], threshold: u32) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..hashes.len()).collect();
+
+    fn find(parent: &mut [usize], mut idx: usize) -> usize {
+        while parent[idx] != idx {
+            parent[idx] = parent[parent[idx]];
+            idx = parent[idx];
+        }
+        idx
+    }
+
+    for i in 0..hashes.len() {
+        for j in (i + 1)..hashes.len() {
+            let a = &hashes[i];
+            let b = &hashes[j];
+
+            let exact = a.content == b.content && a.file_size == b.file_size;
+            let similar = hamming_distance(a.dhash, b.dhash) <= threshold
+                && hamming_distance(a.phash, b.phash) <= threshold;
+
+            if exact || similar {
+                let root_a = find(&mut parent, i);
+                let root_b = find(&mut parent, j);
+                if root_a != root_b {
+                    parent[root_b.max(root_a)] = root_a.min(root_b);
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root: Vec<Option<usize>> = vec![None; hashes.len()];
+
+    for idx in 0..hashes.len() {
+        let root = find(&mut parent, idx);
+        match group_of_root[root] {
+            Some(group) => groups[group].push(idx),
+            None => {
+                group_of_root[root] = Some(groups.len());
+                groups.push(vec![idx]);
+            }
+        }
+    }
+
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+fn to_grayscale(rgba: &[u8], width: u32, height: u32) -> Vec<f32> {
+    let pixel_count = (width as usize) * (height as usize);
+
+    rgba.chunks_exact(4)
+        .take(pixel_count)
+        .map(|px| 0.299 * px[0] as f32 + 0.587 * px[1] as f32 + 0.114 * px[2] as f32)
+        .collect()
+}
+
+/// Area-average downscale of a grayscale buffer
+fn resize_gray(gray: &[f32], src_w: usize, src_h: usize, dst_w: usize, dst_h: usize) -> Vec<f32> {
+    let mut out = vec![0.0; dst_w * dst_h];
+
+    if src_w == 0 || src_h == 0 || gray.len() < src_w * src_h {
+        return out;
+    }
+
+    for dy in 0..dst_h {
+        let y0 = dy * src_h / dst_h;
+        let y1 = ((dy + 1) * src_h / dst_h).max(y0 + 1).min(src_h);
+
+        for dx in 0..dst_w {
+            let x0 = dx * src_w / dst_w;
+            let x1 = ((dx + 1) * src_w / dst_w).max(x0 + 1).min(src_w);
+
+            let mut sum = 0.0;
+            for y in y0..y1 {
+                let row = &gray[y * src_w..];
+                sum += row[x0..x1].iter().sum::<f32>();
+            }
+
+            out[dy * dst_w + dx] = sum / ((x1 - x0) * (y1 - y0)) as f32;
+        }
+    }
+
+    out
+}
+
+/// Separable 2D DCT-II of a square buffer
+fn dct_2d(input: &[f32], size: usize) -> Vec<f32> {
+    let mut cos_table = vec![0.0f32; size * size];
+    for k in 0..size {
+        for n in 0..size {
+            cos_table[k * size + n] =
+                ((2 * n + 1) as f32 * k as f32 * PI / (2 * size) as f32).cos();
+        }
+    }
+
+    // Rows
+    let mut rows = vec![0.0f32; size * size];
+    for y in 0..size {
+        for k in 0..size {
+            rows[y * size + k] = (0..size)
+                .map(|n| input[y * size + n] * cos_table[k * size + n])
+                .sum();
+        }
+    }
+
+    // Columns
+    let mut out = vec![0.0f32; size * size];
+    for x in 0..size {
+        for k in 0..size {
+            out[k * size + x] = (0..size)
+                .map(|n| rows[n * size + x] * cos_table[k * size + n])
+                .sum();
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(width: u32, height: u32, invert: bool) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let fx = x as f32 / width as f32;
+                let fy = y as f32 / height as f32;
+                let mut v = ((fx * 7.0).sin() * (fy * 5.0).cos() * 127.0 + 128.0) as u8;
+                if invert {
+                    v = 255 - v;
+                }
+                pixels.extend_from_slice(&[v, v, v, 255]);
+            }
+        }
+        pixels
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(b""), FNV_OFFSET);
+        assert_eq!(content_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+
+        let mut hasher = ContentHasher::new();
+        hasher.update(b"hello ");
+        hasher.update(b"world");
+        assert_eq!(hasher.finish(), content_hash(b"hello world"));
+    }
+
+    #[test]
+    fn test_hashes_survive_rescaling() {
+        let large = pattern(128, 96, false);
+        let small = pattern(64, 48, false);
+
+        assert!(hamming_distance(dhash(&large, 128, 96), dhash(&small, 64, 48)) <= 4);
+        assert!(hamming_distance(phash(&large, 128, 96), phash(&small, 64, 48)) <= 4);
+    }
+
+    #[test]
+    fn test_hashes_differ_for_different_images() {
+        let normal = pattern(64, 64, false);
+        let inverted = pattern(64, 64, true);
+
+        assert!(hamming_distance(dhash(&normal, 64, 64), dhash(&inverted, 64, 64)) > 16);
+        assert!(hamming_distance(phash(&normal, 64, 64), phash(&inverted, 64, 64)) > 16);
+    }
+
+    #[test]
+    fn test_group_similar() {
+        let hashes = [
+            ImageHashes {
+                content: 1,
+                file_size: 10,
+                dhash: 0,
+                phash: 0,
+            },
+            ImageHashes {
+                content: 2,
+                file_size: 10,
+                dhash: u64::MAX,
+                phash: u64::MAX,
+            },
+            ImageHashes {
+                content: 3,
+                file_size: 12,
+                dhash: 0b11,
+                phash: 0b1,
+            },
+            ImageHashes {
+                content: 2,
+                file_size: 10,
+                dhash: 0xFF,
+                phash: 0xFF,
+            },
+        ];
+
+        let groups = group_similar(&hashes, 4);
+        assert_eq!(groups, vec![vec![0, 2], vec![1, 3]]);
+
+        let groups = group_similar(&hashes, 0);
+        assert_eq!(groups, vec![vec![1, 3]]);
+    }
+}
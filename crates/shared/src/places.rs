@@ -0,0 +1,213 @@
+//! Folder tree and places for the navigation sidebar
+//!
+//! The tree is kept flat in display order. Expanding a node inserts its
+//! children right after it, collapsing drops all of its descendants again, so
+//! folders are only listed when they are opened and always show fresh counts.
+
+use std::path::{Path, PathBuf};
+
+/// A listed folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FolderEntry {
+    pub path: PathBuf,
+    pub name: String,
+    /// Supported images directly inside the folder
+    pub image_count: usize,
+    pub has_children: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FolderNode {
+    pub entry: FolderEntry,
+    pub depth: usize,
+    pub expanded: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FolderTree {
+    nodes: Vec<FolderNode>,
+}
+
+impl FolderTree {
+    /// Tree with collapsed top level folders
+    pub fn new(roots: Vec<FolderEntry>) -> Self {
+        Self {
+            nodes: roots
+                .into_iter()
+                .map(|entry| FolderNode {
+                    entry,
+                    depth: 0,
+                    expanded: false,
+                })
+                .collect(),
+        }
+    }
+
+    /// Visible nodes in display order
+    pub fn nodes(&self) -> &[FolderNode] {
+        &self.nodes
+    }
+
+    /// Whether `index` still refers to the folder at `path`, used to drop stale listings
+    pub fn is_node(&self, index: usize, path: &Path) -> bool {
+        self.nodes
+            .get(index)
+            .is_some_and(|node| node.entry.path == path)
+    }
+
+    /// Show `children` below the node at `index`
+    pub fn expand(&mut self, index: usize, children: Vec<FolderEntry>) {
+        self.collapse(index);
+        let Some(node) = self.nodes.get_mut(index) else {
+            return;
+        };
+
+        node.expanded = true;
+        node.entry.has_children = !children.is_empty();
+        let depth = node.depth + 1;
+
+        self.nodes.splice(
+            index + 1..index + 1,
+            children.into_iter().map(|entry| FolderNode {
+                entry,
+                depth,
+                expanded: false,
+            }),
+        );
+    }
+
+    /// Hide all descendants of the node at `index`
+    pub fn collapse(&mut self, index: usize) {
+        let Some(node) = self.nodes.get_mut(index) else {
+            return;
+        };
+        node.expanded = false;
+
+        let depth = node.depth;
+        let end = self.nodes[index + 1..]
+            .iter()
+            .position(|node| node.depth <= depth)
+            .map_or(self.nodes.len(), |offset| index + 1 + offset);
+        self.nodes.drain(index + 1..end);
+    }
+}
+
+/// Removable and user mounted volumes from a `/proc/self/mounts` listing
+pub fn parse_mounts(mounts: &str) -> Vec<PathBuf> {
+    const VOLUME_ROOTS: &[&str] = &["/media/", "/run/media/", "/mnt/"];
+
+    let mut volumes: Vec<PathBuf> = mounts
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(unescape_mount_path)
+        .filter(|mount| VOLUME_ROOTS.iter().any(|root| mount.starts_with(root)))
+        .map(PathBuf::from)
+        .collect();
+
+    volumes.sort();
+    volumes.dedup();
+    volumes
+}
+
+/// Mount points escape whitespace and backslashes as three digit octal codes
+fn unescape_mount_path(raw: &str) -> String {
+    let mut path = String::with_capacity(raw.len());
+    let mut rest = raw;
+
+    while let Some(pos) = rest.find('\\') {
+        path.push_str(&rest[..pos]);
+        let code = rest.get(pos + 1..pos + 4);
+
+        match code.and_then(|code| u8::from_str_radix(code, 8).ok()) {
+            Some(byte) => {
+                path.push(byte as char);
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                path.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+
+    path.push_str(rest);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str) -> FolderEntry {
+        FolderEntry {
+            path: PathBuf::from(path),
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            image_count: 0,
+            has_children: true,
+        }
+    }
+
+    fn paths(tree: &FolderTree) -> Vec<(&str, usize)> {
+        tree.nodes()
+            .iter()
+            .map(|node| (node.entry.path.to_str().unwrap(), node.depth))
+            .collect()
+    }
+
+    #[test]
+    fn test_expand_inserts_children_after_node() {
+        let mut tree = FolderTree::new(vec![entry("/a"), entry("/b")]);
+        tree.expand(0, vec![entry("/a/x"), entry("/a/y")]);
+        tree.expand(2, vec![entry("/a/y/z")]);
+
+        assert_eq!(
+            paths(&tree),
+            vec![
+                ("/a", 0),
+                ("/a/x", 1),
+                ("/a/y", 1),
+                ("/a/y/z", 2),
+                ("/b", 0)
+            ]
+        );
+        assert!(tree.nodes()[0].expanded);
+    }
+
+    #[test]
+    fn test_collapse_removes_descendants() {
+        let mut tree = FolderTree::new(vec![entry("/a"), entry("/b")]);
+        tree.expand(0, vec![entry("/a/x")]);
+        tree.expand(1, vec![entry("/a/x/y")]);
+        tree.collapse(0);
+
+        assert_eq!(paths(&tree), vec![("/a", 0), ("/b", 0)]);
+        assert!(!tree.nodes()[0].expanded);
+    }
+
+    #[test]
+    fn test_expand_without_children() {
+        let mut tree = FolderTree::new(vec![entry("/a")]);
+        tree.expand(0, Vec::new());
+        assert!(!tree.nodes()[0].entry.has_children);
+        assert!(tree.is_node(0, Path::new("/a")));
+        assert!(!tree.is_node(1, Path::new("/a")));
+    }
+
+    #[test]
+    fn test_parse_mounts() {
+        let mounts = "\
+/dev/nvme0n1p2 / ext4 rw 0 0
+proc /proc proc rw 0 0
+/dev/sda1 /run/media/user/USB\\040Stick vfat rw 0 0
+/dev/sdb1 /mnt/backup ext4 rw 0 0
+/dev/sdb1 /mnt/backup ext4 rw 0 0
+";
+        assert_eq!(
+            parse_mounts(mounts),
+            vec![
+                PathBuf::from("/mnt/backup"),
+                PathBuf::from("/run/media/user/USB Stick"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Which neighbouring images to decode ahead of time
+//!
+//! Navigation wraps around at both ends, so the window does too.
+
+/// Images decoded in the direction of travel
+const AHEAD: usize = 3;
+/// Images kept behind the direction of travel
+const BEHIND: usize = 1;
+/// Images on either side when the direction is unknown
+const AROUND: usize = 2;
+/// Images decoded ahead while a slideshow runs
+const SLIDESHOW_AHEAD: usize = 5;
+
+/// Total size of decoded RGBA images the prefetch window may hold
+pub const PREFETCH_BUDGET_BYTES: u64 = 512 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavDirection {
+    Forward,
+    Backward,
+}
+
+/// Indices to prefetch around `current`, nearest and most likely first.
+/// `current` itself is not included.
+pub fn prefetch_window(
+    current: usize,
+    total: usize,
+    direction: Option<NavDirection>,
+    slideshow: bool,
+) -> Vec<usize> {
+    if total <= 1 {
+        return Vec::new();
+    }
+
+    let (forward, backward) = match (direction, slideshow) {
+        (_, true) => (SLIDESHOW_AHEAD, 0),
+        (Some(NavDirection::Forward), false) => (AHEAD, BEHIND),
+        (Some(NavDirection::Backward), false) => (BEHIND, AHEAD),
+        (None, false) => (AROUND, AROUND),
+    };
+
+    let mut window = Vec::with_capacity(forward + backward);
+    for step in 1..=forward.max(backward) {
+        // The direction of travel wins ties
+        let mut offsets = [
+            (step <= forward, step as isize),
+            (step <= backward, -(step as isize)),
+        ];
+        if direction == Some(NavDirection::Backward) {
+            offsets.swap(0, 1);
+        }
+
+        for (included, offset) in offsets {
+            let idx = (current as isize + offset).rem_euclid(total as isize) as usize;
+            if included && idx != current && !window.contains(&idx) {
+                window.push(idx);
+            }
+        }
+    }
+
+    window
+}
+
+/// Largest decoded image allowed for each slot of a window of `window_len` images
+pub fn per_image_budget(window_len: usize) -> u64 {
+    PREFETCH_BUDGET_BYTES / window_len.max(1) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_without_direction() {
+        assert_eq!(prefetch_window(5, 10, None, false), vec![6, 4, 7, 3]);
+    }
+
+    #[test]
+    fn test_window_biased_by_direction() {
+        assert_eq!(
+            prefetch_window(5, 10, Some(NavDirection::Forward), false),
+            vec![6, 4, 7, 8]
+        );
+        assert_eq!(
+            prefetch_window(5, 10, Some(NavDirection::Backward), false),
+            vec![4, 6, 3, 2]
+        );
+    }
+
+    #[test]
+    fn test_window_slideshow_only_looks_ahead() {
+        assert_eq!(
+            prefetch_window(8, 10, Some(NavDirection::Forward), true),
+            vec![9, 0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_window_small_folders() {
+        assert!(prefetch_window(0, 1, None, false).is_empty());
+        assert_eq!(prefetch_window(0, 3, None, false), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_per_image_budget() {
+        assert_eq!(per_image_budget(4), PREFETCH_BUDGET_BYTES / 4);
+        assert_eq!(per_image_budget(0), PREFETCH_BUDGET_BYTES);
+    }
+}
@@ -0,0 +1,136 @@
+//! Page layout for reading comics
+//!
+//! Spreads pair pages two by two, optionally keeping the cover on its own so
+//! that left and right pages of the printed book stay together. The continuous
+//! strip stacks pages scaled to a common width.
+
+use std::ops::Range;
+
+/// Height to width ratio assumed for pages whose size isn't known yet
+pub const DEFAULT_PAGE_ASPECT: f32 = 1.5;
+
+/// Pages shown together with `page`, in reading order
+pub fn spread(page: usize, total: usize, cover_alone: bool) -> Range<usize> {
+    if total == 0 {
+        return 0..0;
+    }
+
+    let page = page.min(total - 1);
+    let start = match (cover_alone, page) {
+        (true, 0) => return 0..1,
+        (true, page) => page - (page - 1) % 2,
+        (false, page) => page - page % 2,
+    };
+
+    start..(start + 2).min(total)
+}
+
+/// First page of the spread after the one showing `page`
+pub fn next_spread(page: usize, total: usize, cover_alone: bool) -> Option<usize> {
+    let next = spread(page, total, cover_alone).end;
+    (next < total).then_some(next)
+}
+
+/// First page of the spread before the one showing `page`
+pub fn prev_spread(page: usize, total: usize, cover_alone: bool) -> Option<usize> {
+    let start = spread(page, total, cover_alone).start;
+    (start > 0).then(|| spread(start - 1, total, cover_alone).start)
+}
+
+/// Scale that fits pages side by side at a common height into `available`
+///
+/// `sizes` are the page dimensions in pixels.
+pub fn spread_scale(sizes: &[(f32, f32)], available: (f32, f32)) -> f32 {
+    let (available_width, available_height) = available;
+    let tallest = sizes.iter().map(|&(_, height)| height).fold(0.0, f32::max);
+    if tallest <= 0.0 || available_width <= 0.0 || available_height <= 0.0 {
+        return 1.0;
+    }
+
+    // Width of the spread once every page is scaled to the tallest one's height
+    let width: f32 = sizes
+        .iter()
+        .filter(|&&(_, height)| height > 0.0)
+        .map(|&(width, height)| width * tallest / height)
+        .sum();
+
+    (available_width / width).min(available_height / tallest)
+}
+
+/// Top of each page in a strip of pages scaled to `width`, followed by the strip height
+///
+/// `aspects` are height to width ratios.
+pub fn strip_offsets(aspects: &[f32], width: f32, gap: f32) -> Vec<f32> {
+    let mut offsets = Vec::with_capacity(aspects.len() + 1);
+    let mut y = 0.0;
+
+    for aspect in aspects {
+        offsets.push(y);
+        y += aspect * width + gap;
+    }
+
+    offsets.push((y - gap).max(0.0));
+    offsets
+}
+
+/// Page of the strip at vertical position `y`
+pub fn page_at(offsets: &[f32], y: f32) -> usize {
+    let pages = offsets.len().saturating_sub(1);
+    offsets[..pages]
+        .partition_point(|&top| top <= y)
+        .saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spread_pairs_pages() {
+        assert_eq!(spread(0, 5, false), 0..2);
+        assert_eq!(spread(3, 5, false), 2..4);
+        assert_eq!(spread(4, 5, false), 4..5);
+        assert_eq!(spread(0, 0, false), 0..0);
+    }
+
+    #[test]
+    fn test_spread_with_cover_alone() {
+        assert_eq!(spread(0, 5, true), 0..1);
+        assert_eq!(spread(1, 5, true), 1..3);
+        assert_eq!(spread(2, 5, true), 1..3);
+        assert_eq!(spread(4, 5, true), 3..5);
+        assert_eq!(spread(9, 5, true), 3..5);
+    }
+
+    #[test]
+    fn test_turning_spreads() {
+        assert_eq!(next_spread(0, 5, true), Some(1));
+        assert_eq!(next_spread(2, 5, true), Some(3));
+        assert_eq!(next_spread(3, 5, true), None);
+        assert_eq!(prev_spread(3, 5, true), Some(1));
+        assert_eq!(prev_spread(2, 5, true), Some(0));
+        assert_eq!(prev_spread(1, 4, false), None);
+        assert_eq!(prev_spread(3, 4, false), Some(0));
+    }
+
+    #[test]
+    fn test_spread_scale() {
+        // Two portrait pages side by side are twice as wide as one
+        let pages = [(100.0, 150.0), (100.0, 150.0)];
+        assert_eq!(spread_scale(&pages, (400.0, 600.0)), 2.0);
+        assert_eq!(spread_scale(&pages, (1000.0, 300.0)), 2.0);
+        assert_eq!(spread_scale(&[], (400.0, 600.0)), 1.0);
+    }
+
+    #[test]
+    fn test_strip_offsets_and_page_at() {
+        let offsets = strip_offsets(&[1.5, 1.0, 1.5], 100.0, 10.0);
+        assert_eq!(offsets, vec![0.0, 160.0, 270.0, 420.0]);
+
+        assert_eq!(page_at(&offsets, 0.0), 0);
+        assert_eq!(page_at(&offsets, 159.0), 0);
+        assert_eq!(page_at(&offsets, 160.0), 1);
+        assert_eq!(page_at(&offsets, 1000.0), 2);
+        assert_eq!(page_at(&strip_offsets(&[], 100.0, 10.0), 50.0), 0);
+    }
+}
@@ -0,0 +1,75 @@
+//! Continuous gallery thumbnail sizing
+//!
+//! Tiles can be any size between the limits below. Thumbnails are only decoded
+//! at a few fixed resolutions and scaled to the tile, so zooming reuses what is
+//! cached and only re-decodes once a tile outgrows its thumbnail's resolution.
+
+pub const MIN_THUMBNAIL_SIZE: u32 = 48;
+pub const MAX_THUMBNAIL_SIZE: u32 = 512;
+
+/// Size factor for one wheel notch or key press
+const ZOOM_STEP: f32 = 1.1;
+
+/// Resolutions thumbnails are decoded at, smallest first
+const DECODE_SIZES: [u32; 4] = [64, 128, 256, 512];
+
+/// Tile size after zooming `steps` notches, positive steps make tiles larger
+pub fn zoom_thumbnail_size(size: u32, steps: f32) -> u32 {
+    let mut zoomed = (size as f32 * ZOOM_STEP.powf(steps)).round() as u32;
+
+    // Small touchpad deltas must still move, or they would round away forever
+    if zoomed == size && steps != 0.0 {
+        zoomed = if steps > 0.0 {
+            size + 1
+        } else {
+            size.saturating_sub(1)
+        };
+    }
+
+    zoomed.clamp(MIN_THUMBNAIL_SIZE, MAX_THUMBNAIL_SIZE)
+}
+
+/// Resolution to decode thumbnails at for tiles of `size` pixels
+pub fn decode_size(size: u32) -> u32 {
+    DECODE_SIZES
+        .into_iter()
+        .find(|&decode| decode >= size)
+        .unwrap_or(MAX_THUMBNAIL_SIZE)
+}
+
+/// Whether a thumbnail decoded at `decoded_at` looks soft in a tile of `size` pixels
+pub fn needs_redecode(decoded_at: u32, size: u32) -> bool {
+    decoded_at < decode_size(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_is_clamped() {
+        assert_eq!(zoom_thumbnail_size(128, 0.0), 128);
+        assert!(zoom_thumbnail_size(128, 1.0) > 128);
+        assert!(zoom_thumbnail_size(128, -1.0) < 128);
+        assert_eq!(zoom_thumbnail_size(500, 10.0), MAX_THUMBNAIL_SIZE);
+        assert_eq!(zoom_thumbnail_size(50, -10.0), MIN_THUMBNAIL_SIZE);
+        assert_eq!(zoom_thumbnail_size(100, 0.01), 101);
+        assert_eq!(zoom_thumbnail_size(100, -0.01), 99);
+    }
+
+    #[test]
+    fn test_decode_size_buckets() {
+        assert_eq!(decode_size(48), 64);
+        assert_eq!(decode_size(128), 128);
+        assert_eq!(decode_size(129), 256);
+        assert_eq!(decode_size(512), 512);
+        assert_eq!(decode_size(2000), 512);
+    }
+
+    #[test]
+    fn test_needs_redecode_only_when_outgrown() {
+        assert!(!needs_redecode(256, 200));
+        assert!(!needs_redecode(256, 100));
+        assert!(needs_redecode(256, 300));
+    }
+}
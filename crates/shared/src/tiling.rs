@@ -0,0 +1,172 @@
+//! Tile pyramid math for very large images
+//!
+//! Level 0 is the full resolution image; every following level halves both
+//! dimensions until the whole image fits in a single tile. Tiles are square
+//! and addressed by column and row within their level.
+
+/// Edge length of a tile in pixels
+pub const TILE_SIZE: u32 = 512;
+
+/// Images above this many pixels are shown through a tile pyramid
+pub const TILING_THRESHOLD: u64 = 40_000_000;
+
+/// Longest edge of the preview decoded for a tiled image before the pyramid is ready
+pub const TILED_PREVIEW_SIZE: u32 = 2048;
+
+/// Whether an image is large enough to be rendered in tiles
+pub fn needs_tiling(width: u32, height: u32) -> bool {
+    width as u64 * height as u64 > TILING_THRESHOLD
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileId {
+    pub level: usize,
+    pub col: u32,
+    pub row: u32,
+}
+
+/// Number of pyramid levels needed until the image fits in one tile
+pub fn level_count(width: u32, height: u32) -> usize {
+    let mut count = 1;
+    let (mut width, mut height) = (width, height);
+
+    while width > TILE_SIZE || height > TILE_SIZE {
+        width = width.div_ceil(2);
+        height = height.div_ceil(2);
+        count += 1;
+    }
+
+    count
+}
+
+/// Dimensions of a pyramid level
+pub fn level_size(width: u32, height: u32, level: usize) -> (u32, u32) {
+    (0..level).fold((width, height), |(width, height), _| {
+        (width.div_ceil(2).max(1), height.div_ceil(2).max(1))
+    })
+}
+
+/// Scale of a level relative to the full resolution image
+pub fn level_scale(level: usize) -> f32 {
+    1.0 / (1u32 << level.min(31)) as f32
+}
+
+/// Coarsest level that still has at least one image pixel per screen pixel at `zoom`
+pub fn level_for_zoom(zoom: f32, levels: usize) -> usize {
+    if zoom <= 0.0 || levels == 0 {
+        return levels.saturating_sub(1);
+    }
+
+    let mut level = 0;
+    while level + 1 < levels && level_scale(level + 1) >= zoom {
+        level += 1;
+    }
+
+    level
+}
+
+/// Pixel rectangle `(x, y, width, height)` covered by a tile within its level
+pub fn tile_rect(level_width: u32, level_height: u32, col: u32, row: u32) -> (u32, u32, u32, u32) {
+    let x = col * TILE_SIZE;
+    let y = row * TILE_SIZE;
+
+    (
+        x,
+        y,
+        TILE_SIZE.min(level_width.saturating_sub(x)),
+        TILE_SIZE.min(level_height.saturating_sub(y)),
+    )
+}
+
+/// Tiles of a level that intersect the visible region
+///
+/// `visible` is `(x, y, width, height)` in full resolution image pixels.
+pub fn visible_tiles(
+    width: u32,
+    height: u32,
+    level: usize,
+    visible: (f32, f32, f32, f32),
+) -> Vec<TileId> {
+    let (level_width, level_height) = level_size(width, height, level);
+    let scale = level_scale(level);
+    let (x, y, w, h) = visible;
+
+    if w <= 0.0 || h <= 0.0 {
+        return Vec::new();
+    }
+
+    let cols = level_width.div_ceil(TILE_SIZE);
+    let rows = level_height.div_ceil(TILE_SIZE);
+    let tile = TILE_SIZE as f32;
+
+    let first_col = ((x * scale).max(0.0) / tile).floor() as u32;
+    let first_row = ((y * scale).max(0.0) / tile).floor() as u32;
+    let last_col = ((((x + w) * scale) / tile).ceil() as u32).min(cols);
+    let last_row = ((((y + h) * scale) / tile).ceil() as u32).min(rows);
+
+    (first_row..last_row)
+        .flat_map(|row| (first_col..last_col).map(move |col| TileId { level, col, row }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_tiling() {
+        assert!(!needs_tiling(6000, 4000));
+        assert!(needs_tiling(20000, 10000));
+    }
+
+    #[test]
+    fn test_level_count_and_size() {
+        assert_eq!(level_count(512, 300), 1);
+        assert_eq!(level_count(2048, 1024), 3);
+        assert_eq!(level_size(2048, 1024, 2), (512, 256));
+        assert_eq!(level_size(1001, 3, 1), (501, 2));
+    }
+
+    #[test]
+    fn test_level_for_zoom() {
+        assert_eq!(level_for_zoom(1.0, 5), 0);
+        assert_eq!(level_for_zoom(2.0, 5), 0);
+        assert_eq!(level_for_zoom(0.5, 5), 1);
+        assert_eq!(level_for_zoom(0.3, 5), 1);
+        assert_eq!(level_for_zoom(0.01, 5), 4);
+    }
+
+    #[test]
+    fn test_tile_rect_clips_edge() {
+        assert_eq!(tile_rect(1000, 600, 0, 0), (0, 0, 512, 512));
+        assert_eq!(tile_rect(1000, 600, 1, 1), (512, 512, 488, 88));
+    }
+
+    #[test]
+    fn test_visible_tiles() {
+        // 2048x2048 at level 0 has a 4x4 grid of tiles
+        let tiles = visible_tiles(2048, 2048, 0, (600.0, 0.0, 500.0, 100.0));
+        assert_eq!(
+            tiles,
+            vec![
+                TileId {
+                    level: 0,
+                    col: 1,
+                    row: 0
+                },
+                TileId {
+                    level: 0,
+                    col: 2,
+                    row: 0
+                },
+            ]
+        );
+
+        // Level 2 is a single tile covering everything
+        assert_eq!(
+            visible_tiles(2048, 2048, 2, (0.0, 0.0, 2048.0, 2048.0)).len(),
+            1
+        );
+        assert!(visible_tiles(2048, 2048, 0, (0.0, 0.0, 0.0, 10.0)).is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+//! Grouping images into dated sections for the timeline view
+
+use crate::date::CalendarDate;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TimelineGrouping {
+    Day,
+    #[default]
+    Month,
+    Year,
+}
+
+impl TimelineGrouping {
+    pub const ALL: &'static [Self] = &[Self::Day, Self::Month, Self::Year];
+
+    /// The date all images of a section share; finer fields are reset to 1
+    pub fn key(self, date: CalendarDate) -> CalendarDate {
+        match self {
+            TimelineGrouping::Day => date,
+            TimelineGrouping::Month => CalendarDate::new(date.year, date.month, 1),
+            TimelineGrouping::Year => CalendarDate::new(date.year, 1, 1),
+        }
+    }
+
+    /// Header text for a section key
+    pub fn label(self, key: CalendarDate) -> String {
+        match self {
+            TimelineGrouping::Day => key.to_string(),
+            TimelineGrouping::Month => format!("{:04}-{:02}", key.year, key.month),
+            TimelineGrouping::Year => format!("{:04}", key.year),
+        }
+    }
+}
+
+/// A run of consecutive images with the same section key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimelineSection {
+    pub key: CalendarDate,
+    pub start: usize,
+    pub len: usize,
+}
+
+/// Indices of `dates` ordered newest first; images from the same day keep their order
+pub fn timeline_order(dates: &[CalendarDate]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..dates.len()).collect();
+    order.sort_by(|&a, &b| dates[b].cmp(&dates[a]));
+    order
+}
+
+/// Split dates that are already in timeline order into sections
+pub fn sections(dates: &[CalendarDate], grouping: TimelineGrouping) -> Vec<TimelineSection> {
+    let mut sections: Vec<TimelineSection> = Vec::new();
+
+    for (idx, &date) in dates.iter().enumerate() {
+        let key = grouping.key(date);
+        match sections.last_mut() {
+            Some(section) if section.key == key => section.len += 1,
+            _ => sections.push(TimelineSection {
+                key,
+                start: idx,
+                len: 1,
+            }),
+        }
+    }
+
+    sections
+}
+
+/// Each year with the index of its first section, in section order
+pub fn years(sections: &[TimelineSection]) -> Vec<(i32, usize)> {
+    let mut years: Vec<(i32, usize)> = Vec::new();
+
+    for (idx, section) in sections.iter().enumerate() {
+        if years.last().is_none_or(|&(year, _)| year != section.key.year) {
+            years.push((section.key.year, idx));
+        }
+    }
+
+    years
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> CalendarDate {
+        CalendarDate::new(year, month, day)
+    }
+
+    #[test]
+    fn test_order_newest_first_and_stable() {
+        let dates = [date(2020, 1, 1), date(2023, 5, 2), date(2020, 1, 1)];
+        assert_eq!(timeline_order(&dates), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_sections_by_grouping() {
+        let dates = [
+            date(2023, 5, 2),
+            date(2023, 5, 1),
+            date(2023, 4, 30),
+            date(2022, 12, 31),
+        ];
+
+        assert_eq!(sections(&dates, TimelineGrouping::Day).len(), 4);
+
+        let months = sections(&dates, TimelineGrouping::Month);
+        assert_eq!(months.len(), 3);
+        assert_eq!(
+            months[0],
+            TimelineSection {
+                key: date(2023, 5, 1),
+                start: 0,
+                len: 2
+            }
+        );
+
+        let years = sections(&dates, TimelineGrouping::Year);
+        assert_eq!(years.len(), 2);
+        assert_eq!(years[1].start, 3);
+    }
+
+    #[test]
+    fn test_labels() {
+        let key = date(2023, 5, 2);
+        assert_eq!(TimelineGrouping::Day.label(key), "2023-05-02");
+        assert_eq!(TimelineGrouping::Month.label(key), "2023-05");
+        assert_eq!(TimelineGrouping::Year.label(key), "2023");
+    }
+
+    #[test]
+    fn test_years() {
+        let dates = [date(2023, 5, 2), date(2023, 1, 1), date(2021, 6, 1)];
+        let sections = sections(&dates, TimelineGrouping::Month);
+        assert_eq!(years(&sections), vec![(2023, 0), (2021, 2)]);
+        assert!(years(&[]).is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//! Tone mapping of high dynamic range images for display
+//!
+//! OpenEXR and Radiance HDR files hold linear light values without an upper
+//! bound. They are exposed so the log-average luminance lands on middle gray,
+//! compressed with a filmic curve and encoded as 8 bit sRGB.
+
+/// Luminance the log-average of the image is mapped to
+const MIDDLE_GRAY: f32 = 0.18;
+/// Pixels sampled for the exposure estimate
+const EXPOSURE_SAMPLES: usize = 65_536;
+
+/// Relative luminance of linear Rec. 709 RGB
+pub fn luminance(r: f32, g: f32, b: f32) -> f32 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// Scale that brings the log-average luminance of `pixels` to middle gray
+///
+/// `pixels` are interleaved linear samples with `channels` per pixel, RGB first.
+pub fn auto_exposure(pixels: &[f32], channels: usize) -> f32 {
+    if channels < 3 {
+        return 1.0;
+    }
+
+    let count = pixels.len() / channels;
+    let step = (count / EXPOSURE_SAMPLES).max(1);
+
+    let (sum, samples) = pixels
+        .chunks_exact(channels)
+        .step_by(step)
+        .map(|pixel| luminance(pixel[0], pixel[1], pixel[2]))
+        .filter(|lum| lum.is_finite())
+        .fold((0.0f64, 0usize), |(sum, samples), lum| {
+            (sum + (lum.max(0.0) as f64 + 1e-4).ln(), samples + 1)
+        });
+
+    if samples == 0 {
+        return 1.0;
+    }
+
+    let log_average = (sum / samples as f64).exp() as f32;
+    MIDDLE_GRAY / log_average.max(1e-4)
+}
+
+/// Narkowicz's fit of the ACES filmic curve, maps [0, ∞) to [0, 1]
+pub fn filmic(x: f32) -> f32 {
+    let x = x.max(0.0);
+    ((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)).clamp(0.0, 1.0)
+}
+
+/// sRGB transfer function for a linear value in [0, 1]
+pub fn linear_to_srgb(x: f32) -> f32 {
+    if x <= 0.003_130_8 {
+        x * 12.92
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Tone map interleaved linear RGB or RGBA floats to 8 bit sRGB RGBA
+pub fn tone_map_rgba(pixels: &[f32], channels: usize) -> Vec<u8> {
+    let exposure = auto_exposure(pixels, channels);
+    let encode = |value: f32| {
+        let value = if value.is_finite() { value } else { 0.0 };
+        (linear_to_srgb(filmic(value * exposure)) * 255.0).round() as u8
+    };
+
+    pixels
+        .chunks_exact(channels)
+        .flat_map(|pixel| {
+            let alpha = match channels {
+                4 => (pixel[3].clamp(0.0, 1.0) * 255.0).round() as u8,
+                _ => 255,
+            };
+            let (r, g, b) = match channels {
+                1 | 2 => (pixel[0], pixel[0], pixel[0]),
+                _ => (pixel[0], pixel[1], pixel[2]),
+            };
+            [encode(r), encode(g), encode(b), alpha]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filmic_is_bounded_and_monotonic() {
+        assert_eq!(filmic(0.0), 0.0);
+        assert_eq!(filmic(-1.0), 0.0);
+        assert_eq!(filmic(1000.0), 1.0);
+        assert!(filmic(0.5) < filmic(1.0));
+    }
+
+    #[test]
+    fn test_linear_to_srgb() {
+        assert_eq!(linear_to_srgb(0.0), 0.0);
+        assert!((linear_to_srgb(1.0) - 1.0).abs() < 1e-6);
+        assert!((linear_to_srgb(0.18) - 0.461).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_auto_exposure_targets_middle_gray() {
+        // A uniformly bright scene is scaled down
+        let bright = [4.0; 3 * 16];
+        let exposure = auto_exposure(&bright, 3);
+        assert!((4.0 * exposure - MIDDLE_GRAY).abs() < 1e-3);
+
+        assert_eq!(auto_exposure(&[], 4), 1.0);
+    }
+
+    #[test]
+    fn test_tone_map_rgba() {
+        let pixels = [0.0, 0.0, 0.0, 1.0, 100.0, 100.0, 100.0, 0.5];
+        let mapped = tone_map_rgba(&pixels, 4);
+
+        assert_eq!(mapped.len(), 8);
+        assert_eq!(&mapped[..4], &[0, 0, 0, 255]);
+        assert!(mapped[4] > 200);
+        assert_eq!(mapped[7], 128);
+    }
+}
@@ -0,0 +1,296 @@
+//! Zoom and pan math for the image viewport
+//!
+//! Sizes and offsets are `(x, y)` pairs in logical pixels. Offsets are the scroll
+//! position of the scaled image inside the viewport; an axis where the scaled
+//! image fits is centered and always has an offset of zero.
+
+/// Velocity decay per second while coasting
+const KINETIC_FRICTION: f32 = 4.0;
+/// Speed in pixels per second below which coasting stops
+const KINETIC_MIN_SPEED: f32 = 30.0;
+/// Weight of the newest sample when smoothing the tracked velocity
+const KINETIC_SMOOTHING: f32 = 0.8;
+
+/// Scroll offset that keeps the image point under `anchor` in place when
+/// zooming from `old_zoom` to `new_zoom`.
+///
+/// `anchor` is relative to the top-left corner of the viewport.
+pub fn zoom_anchor_offset(
+    viewport: (f32, f32),
+    image: (f32, f32),
+    offset: (f32, f32),
+    anchor: (f32, f32),
+    old_zoom: f32,
+    new_zoom: f32,
+) -> (f32, f32) {
+    let axis = |viewport: f32, image: f32, offset: f32, anchor: f32| {
+        let old_scaled = image * old_zoom;
+        let new_scaled = image * new_zoom;
+        let old_pad = ((viewport - old_scaled) / 2.0).max(0.0);
+        let new_pad = ((viewport - new_scaled) / 2.0).max(0.0);
+        let offset = if old_scaled > viewport { offset } else { 0.0 };
+
+        let image_pos = ((offset + anchor - old_pad) / old_zoom).clamp(0.0, image);
+        let new_offset = new_pad + image_pos * new_zoom - anchor;
+
+        new_offset.clamp(0.0, (new_scaled - viewport).max(0.0))
+    };
+
+    (
+        axis(viewport.0, image.0, offset.0, anchor.0),
+        axis(viewport.1, image.1, offset.1, anchor.1),
+    )
+}
+
+/// Image pixel coordinates under `point`, `None` outside the image.
+///
+/// `point` is relative to the top-left corner of the viewport.
+pub fn image_point(
+    viewport: (f32, f32),
+    image: (f32, f32),
+    offset: (f32, f32),
+    point: (f32, f32),
+    zoom: f32,
+) -> Option<(f32, f32)> {
+    let axis = |viewport: f32, image: f32, offset: f32, point: f32| {
+        let scaled = image * zoom;
+        let pad = ((viewport - scaled) / 2.0).max(0.0);
+        let offset = if scaled > viewport { offset } else { 0.0 };
+        let pos = (offset + point - pad) / zoom;
+
+        (0.0..image).contains(&pos).then_some(pos)
+    };
+
+    Some((
+        axis(viewport.0, image.0, offset.0, point.0)?,
+        axis(viewport.1, image.1, offset.1, point.1)?,
+    ))
+}
+
+/// Clamp a scroll offset to the scrollable range of the scaled image
+pub fn clamp_offset(
+    viewport: (f32, f32),
+    image: (f32, f32),
+    zoom: f32,
+    offset: (f32, f32),
+) -> (f32, f32) {
+    let max_x = (image.0 * zoom - viewport.0).max(0.0);
+    let max_y = (image.1 * zoom - viewport.1).max(0.0);
+
+    (offset.0.clamp(0.0, max_x), offset.1.clamp(0.0, max_y))
+}
+
+/// Part of the image inside the viewport as `(x, y, width, height)` fractions of
+/// the image. An axis where the scaled image fits is shown whole.
+pub fn visible_fraction(
+    viewport: (f32, f32),
+    image: (f32, f32),
+    offset: (f32, f32),
+    zoom: f32,
+) -> (f32, f32, f32, f32) {
+    let axis = |viewport: f32, image: f32, offset: f32| {
+        let scaled = image * zoom;
+        if scaled <= viewport || scaled <= 0.0 {
+            return (0.0, 1.0);
+        }
+
+        let size = viewport / scaled;
+        ((offset / scaled).clamp(0.0, 1.0 - size), size)
+    };
+
+    let (x, width) = axis(viewport.0, image.0, offset.0);
+    let (y, height) = axis(viewport.1, image.1, offset.1);
+    (x, y, width, height)
+}
+
+/// Scroll offset that centers the viewport on `point`, given as fractions of the image
+pub fn centered_offset(
+    viewport: (f32, f32),
+    image: (f32, f32),
+    zoom: f32,
+    point: (f32, f32),
+) -> (f32, f32) {
+    let offset = (
+        point.0 * image.0 * zoom - viewport.0 / 2.0,
+        point.1 * image.1 * zoom - viewport.1 / 2.0,
+    );
+    clamp_offset(viewport, image, zoom, offset)
+}
+
+/// Tracks pan velocity and continues the motion with friction after release
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Kinetic {
+    velocity: (f32, f32),
+}
+
+impl Kinetic {
+    /// Record a pan of `delta` pixels that took `dt` seconds
+    pub fn track(&mut self, delta: (f32, f32), dt: f32) {
+        let dt = dt.max(0.001);
+        let sample = (delta.0 / dt, delta.1 / dt);
+
+        self.velocity = (
+            self.velocity.0 * (1.0 - KINETIC_SMOOTHING) + sample.0 * KINETIC_SMOOTHING,
+            self.velocity.1 * (1.0 - KINETIC_SMOOTHING) + sample.1 * KINETIC_SMOOTHING,
+        );
+    }
+
+    pub fn stop(&mut self) {
+        self.velocity = (0.0, 0.0);
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.velocity.0.hypot(self.velocity.1) >= KINETIC_MIN_SPEED
+    }
+
+    /// Advance by `dt` seconds, returning the distance to pan or `None` once stopped
+    pub fn step(&mut self, dt: f32) -> Option<(f32, f32)> {
+        if !self.is_moving() {
+            self.stop();
+            return None;
+        }
+
+        let delta = (self.velocity.0 * dt, self.velocity.1 * dt);
+        let decay = (-KINETIC_FRICTION * dt).exp();
+        self.velocity = (self.velocity.0 * decay, self.velocity.1 * decay);
+
+        Some(delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_fraction() {
+        // 1000px image at 200% in a 500px viewport scrolled to 500
+        let visible = visible_fraction((500.0, 500.0), (1000.0, 1000.0), (500.0, 0.0), 2.0);
+        assert_eq!(visible, (0.25, 0.0, 0.25, 0.25));
+
+        // An axis that fits is visible whole
+        let visible = visible_fraction((500.0, 500.0), (1000.0, 100.0), (0.0, 0.0), 1.0);
+        assert_eq!(visible, (0.0, 0.0, 0.5, 1.0));
+    }
+
+    #[test]
+    fn test_centered_offset_is_clamped() {
+        let offset = centered_offset((500.0, 500.0), (1000.0, 1000.0), 2.0, (0.5, 0.5));
+        assert_eq!(offset, (750.0, 750.0));
+
+        let offset = centered_offset((500.0, 500.0), (1000.0, 1000.0), 2.0, (0.0, 1.0));
+        assert_eq!(offset, (0.0, 1500.0));
+    }
+
+    #[test]
+    fn test_image_point_centered_and_scrolled() {
+        // 200px image at 100% centered in a 400px viewport
+        let point = image_point(
+            (400.0, 400.0),
+            (200.0, 200.0),
+            (0.0, 0.0),
+            (150.0, 250.0),
+            1.0,
+        );
+        assert_eq!(point, Some((50.0, 150.0)));
+
+        // Outside the image on the padding
+        let point = image_point(
+            (400.0, 400.0),
+            (200.0, 200.0),
+            (0.0, 0.0),
+            (50.0, 250.0),
+            1.0,
+        );
+        assert_eq!(point, None);
+
+        // 1000px image at 200% scrolled to 600
+        let point = image_point(
+            (500.0, 500.0),
+            (1000.0, 1000.0),
+            (600.0, 0.0),
+            (100.0, 40.0),
+            2.0,
+        );
+        assert_eq!(point, Some((350.0, 20.0)));
+    }
+
+    #[test]
+    fn test_zoom_anchor_keeps_point_under_cursor() {
+        // 1000px image in a 500px viewport, scrolled to 200
+        let offset = zoom_anchor_offset(
+            (500.0, 500.0),
+            (1000.0, 1000.0),
+            (200.0, 200.0),
+            (100.0, 50.0),
+            1.0,
+            2.0,
+        );
+
+        // Image point 300,250 must stay at 100,50 in the viewport
+        assert_eq!(offset, (500.0, 450.0));
+    }
+
+    #[test]
+    fn test_zoom_anchor_from_fit() {
+        // 200px image centered in a 400px viewport (100px padding)
+        let offset = zoom_anchor_offset(
+            (400.0, 400.0),
+            (200.0, 200.0),
+            (0.0, 0.0),
+            (150.0, 300.0),
+            1.0,
+            4.0,
+        );
+
+        // Anchor maps to image point 50,200 which lands at 200,800 when zoomed
+        assert_eq!(offset, (50.0, 400.0));
+    }
+
+    #[test]
+    fn test_zoom_anchor_clamps() {
+        let offset = zoom_anchor_offset(
+            (500.0, 500.0),
+            (1000.0, 1000.0),
+            (500.0, 500.0),
+            (500.0, 500.0),
+            1.0,
+            0.5,
+        );
+
+        assert_eq!(offset, (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_clamp_offset() {
+        assert_eq!(
+            clamp_offset((500.0, 500.0), (1000.0, 400.0), 1.0, (-10.0, 80.0)),
+            (0.0, 0.0)
+        );
+        assert_eq!(
+            clamp_offset((500.0, 500.0), (1000.0, 1000.0), 1.0, (900.0, 120.0)),
+            (500.0, 120.0)
+        );
+    }
+
+    #[test]
+    fn test_kinetic_decays_to_rest() {
+        let mut kinetic = Kinetic::default();
+        assert!(!kinetic.is_moving());
+
+        kinetic.track((20.0, 0.0), 0.016);
+        assert!(kinetic.is_moving());
+
+        let mut travelled = 0.0;
+        let mut steps = 0;
+        while let Some((dx, dy)) = kinetic.step(0.016) {
+            assert_eq!(dy, 0.0);
+            travelled += dx;
+            steps += 1;
+            assert!(steps < 1000);
+        }
+
+        assert!(travelled > 20.0);
+        assert!(!kinetic.is_moving());
+    }
+}
@@ -1,5 +1,6 @@
 use cosmic::cosmic_config::{self, Config, ConfigGet, ConfigSet, CosmicConfigEntry};
 use serde::{Deserialize, Serialize};
+use shared::thumbnail_zoom::{MAX_THUMBNAIL_SIZE, MIN_THUMBNAIL_SIZE};
 use std::fmt;
 
 pub const CONFIG_VERSION: u64 = 1;
@@ -83,15 +84,120 @@ pub enum ThumbnailSize {
     Medium,
     Large,
     XLarge,
+    /// Any size set by zooming the gallery
+    Custom(u32),
 }
 
 impl ThumbnailSize {
+    const PRESETS: &'static [Self] = &[Self::Small, Self::Medium, Self::Large, Self::XLarge];
+
     pub fn pixels(self) -> u32 {
         match self {
             ThumbnailSize::Small => 64,
             ThumbnailSize::Medium => 128,
             ThumbnailSize::Large => 192,
             ThumbnailSize::XLarge => 256,
+            ThumbnailSize::Custom(size) => size.clamp(MIN_THUMBNAIL_SIZE, MAX_THUMBNAIL_SIZE),
+        }
+    }
+
+    /// The preset of that size if there is one, so the settings radio stays in sync
+    pub fn from_pixels(size: u32) -> Self {
+        Self::PRESETS
+            .iter()
+            .copied()
+            .find(|preset| preset.pixels() == size)
+            .unwrap_or(Self::Custom(size))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum GalleryLayout {
+    /// Square cells with letterboxed thumbnails
+    #[default]
+    Grid,
+    /// Rows of equal height that keep each thumbnail's aspect ratio
+    Justified,
+}
+
+impl GalleryLayout {
+    pub const ALL: &'static [Self] = &[Self::Grid, Self::Justified];
+}
+
+impl fmt::Display for GalleryLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GalleryLayout::Grid => write!(f, "Grid"),
+            GalleryLayout::Justified => write!(f, "Justified"),
+        }
+    }
+}
+
+/// What is drawn behind the image in the modal and the crop dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ViewerBackground {
+    /// The dialog color of the theme
+    #[default]
+    Theme,
+    Black,
+    White,
+    Gray,
+    /// `ViewerConfig::custom_background`
+    Custom,
+    /// Gray squares, so transparent areas are told apart from the image
+    Checkerboard,
+}
+
+impl ViewerBackground {
+    pub const ALL: &'static [Self] = &[
+        Self::Theme,
+        Self::Black,
+        Self::White,
+        Self::Gray,
+        Self::Custom,
+        Self::Checkerboard,
+    ];
+
+    /// The one after it, for the quick toggle key
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|b| *b == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    /// Vector images get a checkerboard unless another background was picked
+    pub fn shows_checkerboard(self, is_vector: bool) -> bool {
+        match self {
+            ViewerBackground::Checkerboard => true,
+            ViewerBackground::Theme => is_vector,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum ReaderLayout {
+    /// One page at a time, zoomable like any image
+    Single,
+    /// Two pages side by side
+    #[default]
+    Spread,
+    /// All pages stacked at the window width
+    Continuous,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ReadingDirection {
+    #[default]
+    LeftToRight,
+    /// Manga order, the next page is on the left
+    RightToLeft,
+}
+
+impl ReadingDirection {
+    pub fn toggle(self) -> Self {
+        match self {
+            ReadingDirection::LeftToRight => ReadingDirection::RightToLeft,
+            ReadingDirection::RightToLeft => ReadingDirection::LeftToRight,
         }
     }
 }
@@ -227,6 +333,9 @@ impl fmt::Display for AppTheme {
 /// Maximum number of recent folders to remember
 pub const MAX_RECENT_FOLDERS: usize = 10;
 
+/// Maximum number of archives and folders to remember a reading position for
+pub const MAX_READING_POSITIONS: usize = 100;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ViewerConfig {
     pub app_theme: AppTheme,
@@ -237,12 +346,27 @@ pub struct ViewerConfig {
     pub slideshow_interval: u32,
     pub smooth_scaling: bool,
     pub thumbnail_size: ThumbnailSize,
+    pub gallery_layout: GalleryLayout,
+    pub viewer_background: ViewerBackground,
+    /// RGB color of `ViewerBackground::Custom`
+    pub custom_background: [u8; 3],
     pub cache_size: usize,
     pub show_hidden_files: bool,
+    /// List files without an extension when their content is a supported image
+    pub include_extensionless_files: bool,
     pub wallpaper_behavior: WallpaperBehavior,
     pub sort_mode: SortMode,
     pub sort_order: SortOrder,
     pub recent_folders: Vec<String>,
+    /// Folders pinned to the places sidebar, in the order they were added
+    pub bookmarks: Vec<String>,
+    pub show_sidebar: bool,
+    pub reader_layout: ReaderLayout,
+    pub reading_direction: ReadingDirection,
+    /// Show the first page of a spread alone, like the cover of a printed book
+    pub reader_cover_alone: bool,
+    /// Last page read per archive or folder, most recent first
+    pub reading_positions: Vec<(String, usize)>,
 }
 
 impl Default for ViewerConfig {
@@ -256,12 +380,22 @@ impl Default for ViewerConfig {
             slideshow_interval: 5,
             smooth_scaling: true,
             thumbnail_size: ThumbnailSize::default(),
+            gallery_layout: GalleryLayout::default(),
+            viewer_background: ViewerBackground::default(),
+            custom_background: [0x30, 0x30, 0x38],
             cache_size: 20,
             show_hidden_files: false,
+            include_extensionless_files: false,
             wallpaper_behavior: WallpaperBehavior::default(),
             sort_mode: SortMode::default(),
             sort_order: SortOrder::default(),
             recent_folders: Vec::new(),
+            bookmarks: Vec::new(),
+            show_sidebar: true,
+            reader_layout: ReaderLayout::default(),
+            reading_direction: ReadingDirection::default(),
+            reader_cover_alone: true,
+            reading_positions: Vec::new(),
         }
     }
 }
@@ -278,6 +412,42 @@ impl ViewerConfig {
         // Trim to max size
         self.recent_folders.truncate(MAX_RECENT_FOLDERS);
     }
+
+    /// Solid color behind the modal image, `None` for the theme color and the checkerboard
+    pub fn background_color(&self) -> Option<[u8; 3]> {
+        match self.viewer_background {
+            ViewerBackground::Theme | ViewerBackground::Checkerboard => None,
+            ViewerBackground::Black => Some([0, 0, 0]),
+            ViewerBackground::White => Some([255, 255, 255]),
+            ViewerBackground::Gray => Some([128, 128, 128]),
+            ViewerBackground::Custom => Some(self.custom_background),
+        }
+    }
+
+    /// Pin a folder to the sidebar; already bookmarked folders keep their place
+    pub fn add_bookmark(&mut self, folder: String) {
+        if !self.bookmarks.contains(&folder) {
+            self.bookmarks.push(folder);
+        }
+    }
+
+    pub fn remove_bookmark(&mut self, folder: &str) {
+        self.bookmarks.retain(|f| f != folder);
+    }
+
+    pub fn reading_position(&self, folder: &str) -> Option<usize> {
+        self.reading_positions
+            .iter()
+            .find(|(f, _)| f == folder)
+            .map(|&(_, page)| page)
+    }
+
+    /// Remember the page read in a folder; the least recently read are forgotten first
+    pub fn set_reading_position(&mut self, folder: String, page: usize) {
+        self.reading_positions.retain(|(f, _)| f != &folder);
+        self.reading_positions.insert(0, (folder, page));
+        self.reading_positions.truncate(MAX_READING_POSITIONS);
+    }
 }
 
 impl CosmicConfigEntry for ViewerConfig {
@@ -292,12 +462,25 @@ impl CosmicConfigEntry for ViewerConfig {
         config.set("slideshow_interval", self.slideshow_interval)?;
         config.set("smooth_scaling", self.smooth_scaling)?;
         config.set("thumbnail_size", self.thumbnail_size)?;
+        config.set("gallery_layout", self.gallery_layout)?;
+        config.set("viewer_background", self.viewer_background)?;
+        config.set("custom_background", self.custom_background)?;
         config.set("cache_size", self.cache_size)?;
         config.set("show_hidden_files", self.show_hidden_files)?;
+        config.set(
+            "include_extensionless_files",
+            self.include_extensionless_files,
+        )?;
         config.set("wallpaper_behavior", self.wallpaper_behavior)?;
         config.set("sort_mode", self.sort_mode)?;
         config.set("sort_order", self.sort_order)?;
         config.set("recent_folders", self.recent_folders.clone())?;
+        config.set("bookmarks", self.bookmarks.clone())?;
+        config.set("show_sidebar", self.show_sidebar)?;
+        config.set("reader_layout", self.reader_layout)?;
+        config.set("reading_direction", self.reading_direction)?;
+        config.set("reader_cover_alone", self.reader_cover_alone)?;
+        config.set("reading_positions", self.reading_positions.clone())?;
         Ok(())
     }
 
@@ -323,12 +506,26 @@ impl CosmicConfigEntry for ViewerConfig {
         get_field!("last_dir", last_dir, Option<String>);
         get_field!("slideshow_interval", slideshow_interval, u32);
         get_field!("thumbnail_size", thumbnail_size, ThumbnailSize);
+        get_field!("gallery_layout", gallery_layout, GalleryLayout);
+        get_field!("viewer_background", viewer_background, ViewerBackground);
+        get_field!("custom_background", custom_background, [u8; 3]);
         get_field!("cache_size", cache_size, usize);
         get_field!("show_hidden_files", show_hidden_files, bool);
+        get_field!(
+            "include_extensionless_files",
+            include_extensionless_files,
+            bool
+        );
         get_field!("wallpaper_behavior", wallpaper_behavior, WallpaperBehavior);
         get_field!("sort_mode", sort_mode, SortMode);
         get_field!("sort_order", sort_order, SortOrder);
         get_field!("recent_folders", recent_folders, Vec<String>);
+        get_field!("bookmarks", bookmarks, Vec<String>);
+        get_field!("show_sidebar", show_sidebar, bool);
+        get_field!("reader_layout", reader_layout, ReaderLayout);
+        get_field!("reading_direction", reading_direction, ReadingDirection);
+        get_field!("reader_cover_alone", reader_cover_alone, bool);
+        get_field!("reading_positions", reading_positions, Vec<(String, usize)>);
 
         if errors.is_empty() {
             Ok(cfg)
@@ -368,6 +565,15 @@ impl CosmicConfigEntry for ViewerConfig {
                     }
                     Err(e) => errors.push(e),
                 },
+                "include_extensionless_files" => {
+                    match config.get::<bool>("include_extensionless_files") {
+                        Ok(val) => {
+                            self.include_extensionless_files = val;
+                            updated.push("include_extensionless_files");
+                        }
+                        Err(e) => errors.push(e),
+                    }
+                }
                 "sort_mode" => match config.get::<SortMode>("sort_mode") {
                     Ok(val) => {
                         self.sort_mode = val;
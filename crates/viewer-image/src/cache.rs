@@ -1,12 +1,21 @@
+use crate::pyramid::ImagePyramid;
 use cosmic::widget::image::Handle;
 use lru::LruCache;
 use std::{
     collections::HashSet,
     num::NonZeroUsize,
     path::PathBuf,
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
 };
 
+/// Tile pyramids are huge, so only the most recent ones are kept
+const PYRAMID_CAPACITY: usize = 2;
+/// Reduced-resolution decodes shown while full images load
+const PREVIEW_CAPACITY: usize = 10;
+
 #[derive(Clone)]
 pub struct CachedImage {
     pub handle: Handle,
@@ -26,9 +35,19 @@ impl std::fmt::Debug for CachedImage {
 #[derive(Clone)]
 pub struct ImageCache {
     full_images: Arc<Mutex<LruCache<PathBuf, CachedImage>>>,
-    thumbnails: Arc<Mutex<LruCache<PathBuf, CachedImage>>>,
+    /// Thumbnails with the size they were decoded at
+    thumbnails: Arc<Mutex<LruCache<PathBuf, (CachedImage, u32)>>>,
+    previews: Arc<Mutex<LruCache<PathBuf, CachedImage>>>,
     pending: Arc<Mutex<HashSet<PathBuf>>>,
     pending_thumbnails: Arc<Mutex<HashSet<PathBuf>>>,
+    pyramids: Arc<Mutex<LruCache<PathBuf, Arc<ImagePyramid>>>>,
+    pending_pyramids: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Images only partly decoded from a truncated or corrupt file
+    damaged: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Thumbnails that couldn't be decoded at all, not retried until the file changes
+    failed_thumbnails: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Bumped whenever cached images are added or dropped
+    revision: Arc<AtomicU64>,
 }
 
 impl ImageCache {
@@ -40,11 +59,31 @@ impl ImageCache {
             thumbnails: Arc::new(Mutex::new(LruCache::new(
                 NonZeroUsize::new(thumbnail_capacity.max(1)).unwrap(),
             ))),
+            previews: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(PREVIEW_CAPACITY).unwrap(),
+            ))),
             pending: Arc::new(Mutex::new(HashSet::new())),
             pending_thumbnails: Arc::new(Mutex::new(HashSet::new())),
+            pyramids: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(PYRAMID_CAPACITY).unwrap(),
+            ))),
+            pending_pyramids: Arc::new(Mutex::new(HashSet::new())),
+            damaged: Arc::new(Mutex::new(HashSet::new())),
+            failed_thumbnails: Arc::new(Mutex::new(HashSet::new())),
+            revision: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Changes whenever an image is added to or dropped from the cache, so
+    /// layouts derived from cached image sizes know when to recompute
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::Relaxed)
+    }
+
+    fn bump_revision(&self) {
+        self.revision.fetch_add(1, Ordering::Relaxed);
+    }
+
     pub fn with_defaults() -> Self {
         Self::new(10, 200)
     }
@@ -56,12 +95,14 @@ impl ImageCache {
                     .expect("New cache capacity should be implemented"),
             )
         }
+        self.bump_revision();
     }
 
     pub fn resize_thumbnails(&self, new_capacity: usize) {
         if let Ok(mut cache) = self.thumbnails.lock() {
             cache.resize(NonZeroUsize::new(new_capacity.max(1)).unwrap());
         }
+        self.bump_revision();
     }
 
     pub fn get_full(&self, path: &PathBuf) -> Option<CachedImage> {
@@ -73,30 +114,141 @@ impl ImageCache {
             cache.put(path.clone(), image);
         }
 
+        // The preview has served its purpose
+        if let Ok(mut cache) = self.previews.lock() {
+            cache.pop(&path);
+        }
+
         self.clear_pending(&path);
+        self.bump_revision();
     }
 
     pub fn remove_full(&self, path: &PathBuf) {
         if let Ok(mut cache) = self.full_images.lock() {
             cache.pop(path);
         }
+
+        if let Ok(mut cache) = self.pyramids.lock() {
+            cache.pop(path);
+        }
+
+        // The file changed, it may decode cleanly now
+        if let Ok(mut set) = self.damaged.lock() {
+            set.remove(path);
+        }
+        self.bump_revision();
+    }
+
+    pub fn is_damaged(&self, path: &PathBuf) -> bool {
+        self.damaged
+            .lock()
+            .map(|set| set.contains(path))
+            .unwrap_or(false)
+    }
+
+    pub fn set_damaged(&self, path: PathBuf) {
+        if let Ok(mut set) = self.damaged.lock() {
+            set.insert(path);
+        }
+    }
+
+    pub fn get_preview(&self, path: &PathBuf) -> Option<CachedImage> {
+        self.previews.lock().ok()?.get(path).cloned()
+    }
+
+    pub fn insert_preview(&self, path: PathBuf, image: CachedImage) {
+        if let Ok(mut cache) = self.previews.lock() {
+            cache.put(path, image);
+        }
+        self.bump_revision();
+    }
+
+    pub fn get_pyramid(&self, path: &PathBuf) -> Option<Arc<ImagePyramid>> {
+        self.pyramids.lock().ok()?.get(path).cloned()
+    }
+
+    pub fn insert_pyramid(&self, path: PathBuf, pyramid: Arc<ImagePyramid>) {
+        if let Ok(mut cache) = self.pyramids.lock() {
+            cache.put(path.clone(), pyramid);
+        }
+        self.clear_pending_pyramid(&path);
+    }
+
+    pub fn is_pyramid_pending(&self, path: &PathBuf) -> bool {
+        self.pending_pyramids
+            .lock()
+            .map(|set| set.contains(path))
+            .unwrap_or(false)
+    }
+
+    pub fn set_pyramid_pending(&self, path: PathBuf) {
+        if let Ok(mut set) = self.pending_pyramids.lock() {
+            set.insert(path);
+        }
+    }
+
+    pub fn clear_pending_pyramid(&self, path: &PathBuf) {
+        if let Ok(mut set) = self.pending_pyramids.lock() {
+            set.remove(path);
+        }
     }
 
     pub fn get_thumbnail(&self, path: &PathBuf) -> Option<CachedImage> {
-        self.thumbnails.lock().ok()?.get(path).cloned()
+        self.thumbnails
+            .lock()
+            .ok()?
+            .get(path)
+            .map(|(image, _)| image.clone())
     }
 
-    pub fn insert_thumbnail(&self, path: PathBuf, image: CachedImage) {
+    /// Size a cached thumbnail was decoded at, without touching its recency
+    pub fn thumbnail_decode_size(&self, path: &PathBuf) -> Option<u32> {
+        self.thumbnails
+            .lock()
+            .ok()?
+            .peek(path)
+            .map(|(_, decoded_at)| *decoded_at)
+    }
+
+    /// Dimensions of a cached thumbnail, without touching its recency
+    pub fn thumbnail_dimensions(&self, path: &PathBuf) -> Option<(u32, u32)> {
+        self.thumbnails
+            .lock()
+            .ok()?
+            .peek(path)
+            .map(|(image, _)| (image.width, image.height))
+    }
+
+    pub fn insert_thumbnail(&self, path: PathBuf, image: CachedImage, decoded_at: u32) {
         if let Ok(mut cache) = self.thumbnails.lock() {
-            cache.put(path.clone(), image);
+            cache.put(path.clone(), (image, decoded_at));
         }
         self.clear_pending_thumbnail(&path);
+        self.bump_revision();
     }
 
     pub fn remove_thumbnail(&self, path: &PathBuf) {
         if let Ok(mut cache) = self.thumbnails.lock() {
             cache.pop(path);
         }
+        if let Ok(mut set) = self.failed_thumbnails.lock() {
+            set.remove(path);
+        }
+        self.bump_revision();
+    }
+
+    pub fn is_thumbnail_failed(&self, path: &PathBuf) -> bool {
+        self.failed_thumbnails
+            .lock()
+            .map(|set| set.contains(path))
+            .unwrap_or(false)
+    }
+
+    pub fn set_thumbnail_failed(&self, path: PathBuf) {
+        self.clear_pending_thumbnail(&path);
+        if let Ok(mut set) = self.failed_thumbnails.lock() {
+            set.insert(path);
+        }
     }
 
     pub fn is_thumbnail_pending(&self, path: &PathBuf) -> bool {
@@ -151,6 +303,10 @@ impl ImageCache {
         if let Ok(mut set) = self.pending_thumbnails.lock() {
             set.clear();
         }
+        if let Ok(mut set) = self.failed_thumbnails.lock() {
+            set.clear();
+        }
+        self.bump_revision();
     }
 
     pub fn clear(&self) {
@@ -158,6 +314,18 @@ impl ImageCache {
             cache.clear();
         }
 
+        if let Ok(mut cache) = self.previews.lock() {
+            cache.clear();
+        }
+
+        if let Ok(mut cache) = self.pyramids.lock() {
+            cache.clear();
+        }
+
+        if let Ok(mut set) = self.pending_pyramids.lock() {
+            set.clear();
+        }
+
         if let Ok(mut cache) = self.thumbnails.lock() {
             cache.clear();
         }
@@ -169,6 +337,15 @@ impl ImageCache {
         if let Ok(mut set) = self.pending_thumbnails.lock() {
             set.clear();
         }
+
+        if let Ok(mut set) = self.failed_thumbnails.lock() {
+            set.clear();
+        }
+
+        if let Ok(mut set) = self.damaged.lock() {
+            set.clear();
+        }
+        self.bump_revision();
     }
 }
 
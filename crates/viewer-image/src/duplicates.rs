@@ -0,0 +1,239 @@
+//! Duplicate and near-duplicate detection
+//!
+//! Images are fingerprinted with an exact content hash plus dHash/pHash computed
+//! from the same downscaled buffer the thumbnail loader produces. Fingerprints
+//! are cached on disk keyed by path and invalidated when size or mtime change.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use shared::perceptual_hash::{self, ContentHasher, ImageHashes};
+
+use crate::loader::{LoadError, decode_thumbnail, probe_dimensions};
+
+/// Size of the buffer perceptual hashes are computed from
+const HASH_THUMBNAIL_SIZE: u32 = 64;
+const CACHE_FILE: &str = "fingerprints.json";
+
+/// Default maximum Hamming distance for two images to count as similar
+pub const DEFAULT_SIMILARITY_THRESHOLD: u32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageFingerprint {
+    pub content_hash: u64,
+    pub dhash: u64,
+    pub phash: u64,
+    pub file_size: u64,
+    /// Modification time in seconds since the unix epoch
+    pub modified: i64,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImageFingerprint {
+    fn hashes(&self) -> ImageHashes {
+        ImageHashes {
+            content: self.content_hash,
+            file_size: self.file_size,
+            dhash: self.dhash,
+            phash: self.phash,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicateEntry {
+    pub path: PathBuf,
+    pub fingerprint: ImageFingerprint,
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicateGroup {
+    pub entries: Vec<DuplicateEntry>,
+    /// All entries are byte-identical
+    pub exact: bool,
+}
+
+/// Fingerprint all `paths`, reusing cached fingerprints of unchanged files.
+/// Files that cannot be decoded are left out.
+pub async fn fingerprint_images(paths: Vec<PathBuf>) -> Vec<DuplicateEntry> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    rayon::spawn(move || {
+        let result = fingerprint_images_sync(paths);
+        let _ = tx.send(result);
+    });
+
+    rx.await.unwrap_or_default()
+}
+
+fn fingerprint_images_sync(paths: Vec<PathBuf>) -> Vec<DuplicateEntry> {
+    let mut cache = FingerprintCache::load();
+
+    let fingerprints: Vec<DuplicateEntry> = paths
+        .into_par_iter()
+        .filter_map(|path| {
+            let (file_size, modified) = file_stamp(&path)?;
+
+            if let Some(fingerprint) = cache.get(&path, file_size, modified) {
+                return Some(DuplicateEntry { path, fingerprint });
+            }
+
+            match fingerprint_image(&path, file_size, modified) {
+                Ok(fingerprint) => Some(DuplicateEntry { path, fingerprint }),
+                Err(e) => {
+                    tracing::debug!("Skipping {} for duplicate scan: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    for entry in &fingerprints {
+        cache.insert(entry.path.clone(), entry.fingerprint);
+    }
+    cache.retain_existing();
+    cache.save();
+
+    fingerprints
+}
+
+/// Group fingerprinted images within `threshold` bits of each other. Cheap next
+/// to fingerprinting, so changing the threshold only regroups.
+pub fn group_duplicates(entries: &[DuplicateEntry], threshold: u32) -> Vec<DuplicateGroup> {
+    let hashes: Vec<ImageHashes> = entries
+        .iter()
+        .map(|entry| entry.fingerprint.hashes())
+        .collect();
+
+    perceptual_hash::group_similar(&hashes, threshold)
+        .into_iter()
+        .map(|indices| {
+            let entries: Vec<DuplicateEntry> = indices
+                .into_iter()
+                .map(|idx| entries[idx].clone())
+                .collect();
+
+            let first = &entries[0].fingerprint;
+            let exact = entries.iter().all(|entry| {
+                entry.fingerprint.content_hash == first.content_hash
+                    && entry.fingerprint.file_size == first.file_size
+            });
+
+            DuplicateGroup { entries, exact }
+        })
+        .collect()
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, i64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+
+    Some((metadata.len(), modified))
+}
+
+fn fingerprint_image(
+    path: &Path,
+    file_size: u64,
+    modified: i64,
+) -> Result<ImageFingerprint, LoadError> {
+    let mut file = File::open(path)?;
+    let mut hasher = ContentHasher::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    let (thumb_width, thumb_height, pixels) = decode_thumbnail(path, HASH_THUMBNAIL_SIZE)?;
+    let (width, height) = probe_dimensions(path).unwrap_or((0, 0));
+
+    Ok(ImageFingerprint {
+        content_hash: hasher.finish(),
+        dhash: perceptual_hash::dhash(&pixels, thumb_width, thumb_height),
+        phash: perceptual_hash::phash(&pixels, thumb_width, thumb_height),
+        file_size,
+        modified,
+        width,
+        height,
+    })
+}
+
+/// On-disk cache of fingerprints so rescans only hash new or changed files
+#[derive(Default, Serialize, Deserialize)]
+struct FingerprintCache {
+    entries: HashMap<PathBuf, ImageFingerprint>,
+}
+
+impl FingerprintCache {
+    fn cache_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("cupola").join(CACHE_FILE))
+    }
+
+    fn load() -> Self {
+        Self::cache_path()
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let Some(path) = Self::cache_path() else {
+            return;
+        };
+
+        if let Some(parent) = path.parent()
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            tracing::warn!("Failed to create cache directory: {}", e);
+            return;
+        }
+
+        // Written next to the cache and renamed over it, so a crash or a
+        // concurrent scan never leaves a half written file behind
+        let temp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        match serde_json::to_vec(self) {
+            Ok(bytes) => {
+                if let Err(e) =
+                    std::fs::write(&temp, bytes).and_then(|()| std::fs::rename(&temp, &path))
+                {
+                    tracing::warn!("Failed to write fingerprint cache: {}", e);
+                    let _ = std::fs::remove_file(&temp);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize fingerprint cache: {}", e),
+        }
+    }
+
+    fn get(&self, path: &Path, file_size: u64, modified: i64) -> Option<ImageFingerprint> {
+        self.entries
+            .get(path)
+            .filter(|fp| fp.file_size == file_size && fp.modified == modified)
+            .copied()
+    }
+
+    fn insert(&mut self, path: PathBuf, fingerprint: ImageFingerprint) {
+        self.entries.insert(path, fingerprint);
+    }
+
+    /// Drop entries for files that no longer exist
+    fn retain_existing(&mut self) {
+        self.entries.retain(|path, _| path.exists());
+    }
+}
@@ -4,15 +4,26 @@ pub use state::{EditState, Transform};
 
 use cosmic::widget::image::Handle;
 use image::{DynamicImage, GenericImageView};
-use std::path::{Path, PathBuf};
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
 
 use viewer_types::CropRegion;
 
 #[derive(Debug, Error)]
 pub enum EditError {
+    #[error("File not found")]
+    NotFound,
+    #[error("Permission denied")]
+    PermissionDenied,
+    #[error("File is truncated")]
+    Truncated,
+    #[error("Unsupported codec: {0}")]
+    UnsupportedCodec(String),
     #[error("Failed to load image: {0}")]
-    LoadError(#[from] image::ImageError),
+    LoadError(image::ImageError),
     #[error("Failed to save image: {0}")]
     SaveError(String),
     #[error("Invalid crop region")]
@@ -20,7 +31,36 @@ pub enum EditError {
     #[error("No image loaded")]
     NoImage,
     #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+    Io(std::io::Error),
+}
+
+impl From<std::io::Error> for EditError {
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            ErrorKind::NotFound => Self::NotFound,
+            ErrorKind::PermissionDenied => Self::PermissionDenied,
+            ErrorKind::UnexpectedEof => Self::Truncated,
+            _ => Self::Io(err),
+        }
+    }
+}
+
+impl From<image::ImageError> for EditError {
+    fn from(err: image::ImageError) -> Self {
+        match err {
+            image::ImageError::IoError(err) => err.into(),
+            image::ImageError::Unsupported(err) => Self::UnsupportedCodec(err.to_string()),
+            err => Self::LoadError(err),
+        }
+    }
+}
+
+/// Errors while writing keep their cause, other image errors are save failures
+fn save_error(err: image::ImageError) -> EditError {
+    match EditError::from(err) {
+        EditError::LoadError(err) => EditError::SaveError(err.to_string()),
+        err => err,
+    }
 }
 
 pub fn apply_transform(img: &DynamicImage, transform: Transform) -> DynamicImage {
@@ -62,12 +102,9 @@ pub fn crop_image(img: &DynamicImage, region: CropRegion) -> Result<DynamicImage
 pub async fn save_image(img: DynamicImage, path: &Path) -> Result<(), EditError> {
     let path = path.to_path_buf();
 
-    tokio::task::spawn_blocking(move || {
-        img.save(&path)
-            .map_err(|err| EditError::SaveError(err.to_string()))
-    })
-    .await
-    .map_err(|err| EditError::SaveError(err.to_string()))?
+    tokio::task::spawn_blocking(move || img.save(&path).map_err(save_error))
+        .await
+        .map_err(|err| EditError::SaveError(err.to_string()))?
 }
 
 pub async fn apply_edits_to_image(
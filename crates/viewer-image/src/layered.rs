@@ -0,0 +1,1069 @@
+//! Layered editor documents
+//!
+//! Photoshop, GIMP and Krita files open flattened. PSD and KRA files carry a
+//! merged copy of the canvas that is decoded as is; a PSD saved without
+//! "Maximize Compatibility" has a blank one. XCF files have no merged image,
+//! so their visible layers are composited here with normal blending and their
+//! layer masks applied; other blend modes are approximated.
+
+use crate::{
+    cancel::CancelToken,
+    loader::LoadError,
+    scheduler::{JobKind, Priority, scheduler},
+};
+use shared::formats::ImageFormat;
+use std::{
+    collections::HashMap,
+    io::{self, Cursor, Read},
+    path::{Path, PathBuf},
+};
+use viewer_nav::ImageSource;
+
+/// Canvas size and number of layers of a layered document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayerInfo {
+    pub canvas_width: u32,
+    pub canvas_height: u32,
+    /// Layers as the editor lists them, groups included
+    pub layer_count: usize,
+}
+
+/// Decode the flattened canvas of a layered document, returns (width, height, rgba_pixels)
+pub(crate) fn decode_composite(
+    bytes: &[u8],
+    format: ImageFormat,
+) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    match format {
+        ImageFormat::Psd => decode_psd(bytes),
+        ImageFormat::Xcf => decode_xcf(bytes),
+        ImageFormat::Kra => decode_kra(bytes),
+        _ => Err(not_layered(format)),
+    }
+}
+
+/// Canvas size and layer count of the document at `path`
+pub fn layer_info(path: &Path) -> Result<LayerInfo, LoadError> {
+    let bytes = ImageSource::locate(path).read()?;
+    match ImageFormat::detect(&bytes, path) {
+        Some(ImageFormat::Psd) => psd_layer_info(&bytes),
+        Some(ImageFormat::Xcf) => xcf_layer_info(&bytes),
+        Some(ImageFormat::Kra) => kra_layer_info(&bytes),
+        Some(format) => Err(not_layered(format)),
+        None => Err(LoadError::UnsupportedFormat("unknown".into())),
+    }
+}
+
+/// Read the layer info of a document off the UI thread
+pub async fn load_layer_info(path: PathBuf) -> Result<LayerInfo, LoadError> {
+    let task_path = path.clone();
+
+    scheduler()
+        .submit(
+            path,
+            JobKind::Preview,
+            Priority::Current,
+            CancelToken::new(),
+            move |_| layer_info(&task_path),
+        )
+        .await
+}
+
+fn not_layered(format: ImageFormat) -> LoadError {
+    LoadError::UnsupportedFormat(format!("{} has no layers", format.name()))
+}
+
+/// Bytes needed for `width` x `height` pixels of `bytes_per_pixel`, refusing
+/// sides over `max_side` and sizes that don't fit in memory
+fn buffer_len(
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+    max_side: u32,
+) -> Result<usize, LoadError> {
+    if width.max(height) > max_side as usize {
+        return Err(LoadError::TooLarge);
+    }
+    width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(bytes_per_pixel))
+        .ok_or(LoadError::TooLarge)
+}
+
+/// An empty buffer with room for `len` bytes, or an error when there isn't enough memory
+fn buffer(len: usize) -> Result<Vec<u8>, LoadError> {
+    let mut buffer = Vec::new();
+    buffer
+        .try_reserve_exact(len)
+        .map_err(|_| LoadError::TooLarge)?;
+    Ok(buffer)
+}
+
+/// Big-endian cursor over the bytes of a document
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn at(bytes: &'a [u8], pos: usize) -> Self {
+        Self { bytes, pos }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len().saturating_sub(self.pos)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], LoadError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(LoadError::Truncated)?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], LoadError> {
+        Ok(self.take(N)?.try_into().expect("slice has N bytes"))
+    }
+
+    fn u8(&mut self) -> Result<u8, LoadError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, LoadError> {
+        Ok(u16::from_be_bytes(self.array()?))
+    }
+
+    fn i16(&mut self) -> Result<i16, LoadError> {
+        Ok(i16::from_be_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, LoadError> {
+        Ok(u32::from_be_bytes(self.array()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, LoadError> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, LoadError> {
+        Ok(u64::from_be_bytes(self.array()?))
+    }
+
+    /// A 32 bit length, or a 64 bit one when `wide`
+    fn length(&mut self, wide: bool) -> Result<usize, LoadError> {
+        let len = if wide {
+            self.u64()?
+        } else {
+            u64::from(self.u32()?)
+        };
+        usize::try_from(len).map_err(|_| LoadError::Truncated)
+    }
+}
+
+// Photoshop
+
+/// Largest side of a Photoshop document, 30000 before the large format
+const PSD_MAX_SIDE: u32 = 30_000;
+const PSB_MAX_SIDE: u32 = 300_000;
+
+/// PackBits expands two bytes to at most 128
+const PACK_BITS_MAX_RATIO: usize = 64;
+
+/// Tagged blocks whose length is 64 bit in large documents (PSB)
+const PSB_WIDE_BLOCKS: &[&[u8; 4]] = &[
+    b"LMsk", b"Lr16", b"Lr32", b"Layr", b"Mt16", b"Mt32", b"Mtrn", b"Alph", b"FMsk", b"lnk2",
+    b"FEid", b"FXid", b"PxSD",
+];
+
+struct PsdHeader {
+    /// Large document format (PSB) with 64 bit lengths
+    large: bool,
+    channels: usize,
+    width: u32,
+    height: u32,
+    depth: u16,
+    mode: u16,
+}
+
+fn psd_header(reader: &mut Reader) -> Result<PsdHeader, LoadError> {
+    if reader.take(4)? != b"8BPS" {
+        return Err(LoadError::UnsupportedFormat(
+            "Not a Photoshop document".into(),
+        ));
+    }
+    let large = match reader.u16()? {
+        1 => false,
+        2 => true,
+        version => {
+            return Err(LoadError::UnsupportedCodec(format!(
+                "Photoshop document version {version}"
+            )));
+        }
+    };
+    reader.take(6)?;
+
+    let header = PsdHeader {
+        large,
+        channels: usize::from(reader.u16()?),
+        height: reader.u32()?,
+        width: reader.u32()?,
+        depth: reader.u16()?,
+        mode: reader.u16()?,
+    };
+    if header.width == 0 || header.height == 0 {
+        return Err(LoadError::UnsupportedFormat(
+            "Empty Photoshop document".into(),
+        ));
+    }
+    let max_side = if large { PSB_MAX_SIDE } else { PSD_MAX_SIDE };
+    if header.width.max(header.height) > max_side {
+        return Err(LoadError::TooLarge);
+    }
+    Ok(header)
+}
+
+/// Signed layer count from the layer and mask section, 0 for a flat image.
+/// A negative count means the first extra channel of the merged image is its
+/// transparency.
+fn psd_layer_count(reader: &mut Reader, large: bool) -> Result<i16, LoadError> {
+    let section_len = reader.length(large)?;
+    let mut section = Reader::new(reader.take(section_len)?);
+    if section_len == 0 {
+        return Ok(0);
+    }
+
+    let info_len = section.length(large)?;
+    if info_len > 0 {
+        return section.i16();
+    }
+
+    // 16 and 32 bit documents keep their layers in a tagged block instead
+    let mask_len = section.u32()? as usize;
+    section.take(mask_len)?;
+    while section.pos + 12 <= section.bytes.len() {
+        let signature = section.take(4)?;
+        if signature != b"8BIM" && signature != b"8B64" {
+            break;
+        }
+        let key: [u8; 4] = section.array()?;
+        let len = section.length(large && PSB_WIDE_BLOCKS.contains(&&key))?;
+        let mut block = Reader::new(section.take(len)?);
+        if matches!(&key, b"Lr16" | b"Lr32" | b"Layr") {
+            return block.i16();
+        }
+    }
+
+    Ok(0)
+}
+
+fn psd_layer_info(bytes: &[u8]) -> Result<LayerInfo, LoadError> {
+    let mut reader = Reader::new(bytes);
+    let header = psd_header(&mut reader)?;
+
+    // Color mode data and image resources
+    for _ in 0..2 {
+        let len = reader.u32()? as usize;
+        reader.take(len)?;
+    }
+
+    Ok(LayerInfo {
+        canvas_width: header.width,
+        canvas_height: header.height,
+        layer_count: usize::from(psd_layer_count(&mut reader, header.large)?.unsigned_abs()),
+    })
+}
+
+/// Decode the merged image stored after the layers
+fn decode_psd(bytes: &[u8]) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    let mut reader = Reader::new(bytes);
+    let header = psd_header(&mut reader)?;
+
+    let color_mode_len = reader.u32()? as usize;
+    let color_mode_data = reader.take(color_mode_len)?;
+    let resources_len = reader.u32()? as usize;
+    reader.take(resources_len)?;
+    let layer_count = psd_layer_count(&mut reader, header.large)?;
+
+    let color_channels = match header.mode {
+        // Grayscale, indexed
+        1 | 2 => 1,
+        3 => 3,
+        4 => 4,
+        mode => {
+            return Err(LoadError::UnsupportedCodec(format!(
+                "Photoshop color mode {mode}"
+            )));
+        }
+    };
+    if !matches!(header.depth, 8 | 16) {
+        return Err(LoadError::UnsupportedCodec(format!(
+            "{} bit Photoshop document",
+            header.depth
+        )));
+    }
+    if header.channels < color_channels {
+        return Err(LoadError::Truncated);
+    }
+
+    // With layers present, extra channels are saved selections, not transparency
+    let has_alpha = header.channels > color_channels && layer_count <= 0;
+    let planes = psd_planes(
+        &mut reader,
+        &header,
+        color_channels + usize::from(has_alpha),
+    )?;
+
+    let sample_size = usize::from(header.depth / 8);
+    let pixel_count = header.width as usize * header.height as usize;
+    // 16 bit samples are big endian, their first byte is the 8 bit value
+    let sample = |plane: usize, pixel: usize| planes[plane][pixel * sample_size];
+
+    let mut pixels = buffer(buffer_len(
+        header.width as usize,
+        header.height as usize,
+        4,
+        PSB_MAX_SIDE,
+    )?)?;
+    for pixel in 0..pixel_count {
+        let [r, g, b] = match header.mode {
+            1 => [sample(0, pixel); 3],
+            2 => {
+                let index = usize::from(sample(0, pixel));
+                let channel = |offset: usize| {
+                    color_mode_data
+                        .get(offset * 256 + index)
+                        .copied()
+                        .unwrap_or(0)
+                };
+                [channel(0), channel(1), channel(2)]
+            }
+            3 => [sample(0, pixel), sample(1, pixel), sample(2, pixel)],
+            // CMYK samples are stored inverted, 255 is no ink
+            _ => {
+                let black = u16::from(sample(3, pixel));
+                [0, 1, 2].map(|plane| (u16::from(sample(plane, pixel)) * black / 255) as u8)
+            }
+        };
+        let a = if has_alpha {
+            sample(color_channels, pixel)
+        } else {
+            255
+        };
+        pixels.extend_from_slice(&[r, g, b, a]);
+    }
+
+    Ok((header.width, header.height, pixels))
+}
+
+/// The first `count` channel planes of the merged image
+fn psd_planes(
+    reader: &mut Reader,
+    header: &PsdHeader,
+    count: usize,
+) -> Result<Vec<Vec<u8>>, LoadError> {
+    let height = header.height as usize;
+    let row_len = header.width as usize * usize::from(header.depth / 8);
+    let plane_len = buffer_len(
+        header.width as usize,
+        height,
+        usize::from(header.depth / 8),
+        PSB_MAX_SIDE,
+    )?;
+
+    match reader.u16()? {
+        0 => (0..count)
+            .map(|_| Ok(reader.take(plane_len)?.to_vec()))
+            .collect(),
+        1 => {
+            // Compressed lengths of every row of every channel come first,
+            // 32 bit in large documents
+            let row_lengths = (0..header.channels * height)
+                .map(|_| {
+                    if header.large {
+                        reader.u32().map(|len| len as usize)
+                    } else {
+                        reader.u16().map(usize::from)
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            // Too little data left to unpack into the planes
+            if plane_len.div_ceil(PACK_BITS_MAX_RATIO) * count > reader.remaining() {
+                return Err(LoadError::Truncated);
+            }
+
+            let mut planes = Vec::with_capacity(count);
+            for lengths in row_lengths.chunks_exact(height).take(count) {
+                let mut plane = buffer(plane_len)?;
+                for &len in lengths {
+                    unpack_bits(reader.take(len)?, row_len, &mut plane);
+                }
+                planes.push(plane);
+            }
+            Ok(planes)
+        }
+        compression => Err(LoadError::UnsupportedCodec(format!(
+            "Photoshop compression {compression}"
+        ))),
+    }
+}
+
+/// Append a PackBits compressed row of `len` bytes to `out`
+fn unpack_bits(packed: &[u8], len: usize, out: &mut Vec<u8>) {
+    let end = out.len() + len;
+    let mut pos = 0;
+
+    while pos < packed.len() && out.len() < end {
+        let header = packed[pos] as i8;
+        pos += 1;
+        match header {
+            // No-op
+            -128 => {}
+            0.. => {
+                let literal = &packed[pos..(pos + header as usize + 1).min(packed.len())];
+                out.extend_from_slice(literal);
+                pos += literal.len();
+            }
+            _ => {
+                if let Some(&value) = packed.get(pos) {
+                    out.resize(out.len() + (1 - i16::from(header)) as usize, value);
+                    pos += 1;
+                }
+            }
+        }
+    }
+
+    out.resize(end, 0);
+}
+
+// GIMP
+
+const XCF_TILE_SIZE: usize = 64;
+
+/// Largest side of a GIMP image
+const XCF_MAX_SIDE: u32 = 524_288;
+
+const PROP_END: u32 = 0;
+const PROP_COLORMAP: u32 = 1;
+const PROP_OPACITY: u32 = 6;
+const PROP_VISIBLE: u32 = 8;
+const PROP_APPLY_MASK: u32 = 11;
+const PROP_OFFSETS: u32 = 15;
+const PROP_COMPRESSION: u32 = 17;
+const PROP_GROUP_ITEM: u32 = 29;
+const PROP_ITEM_PATH: u32 = 30;
+const PROP_FLOAT_OPACITY: u32 = 33;
+
+struct XcfDocument<'a> {
+    bytes: &'a [u8],
+    /// Version 11 and later use 64 bit offsets
+    wide: bool,
+    width: u32,
+    height: u32,
+    colormap: Vec<[u8; 3]>,
+    compression: u8,
+    /// Layer offsets from top to bottom
+    layers: Vec<usize>,
+}
+
+struct XcfLayer {
+    width: usize,
+    height: usize,
+    kind: u32,
+    visible: bool,
+    opacity: f32,
+    x: i64,
+    y: i64,
+    group: bool,
+    /// Position in the layer tree, one index per nesting level
+    path: Vec<u32>,
+    apply_mask: bool,
+    hierarchy: usize,
+    mask: usize,
+}
+
+/// Walk a property list, calling `property` with each type and payload
+fn xcf_properties<'a>(
+    reader: &mut Reader<'a>,
+    mut property: impl FnMut(u32, Reader<'a>) -> Result<(), LoadError>,
+) -> Result<(), LoadError> {
+    loop {
+        let kind = reader.u32()?;
+        let len = reader.u32()? as usize;
+        if kind == PROP_END {
+            return Ok(());
+        }
+        property(kind, Reader::new(reader.take(len)?))?;
+    }
+}
+
+fn xcf_document(bytes: &[u8]) -> Result<XcfDocument<'_>, LoadError> {
+    let mut reader = Reader::new(bytes);
+    if reader.take(9)? != b"gimp xcf " {
+        return Err(LoadError::UnsupportedFormat("Not a GIMP document".into()));
+    }
+    // "file" for version 0, "vNNN" after that
+    let tag = reader.take(5)?;
+    let version: u32 = match &tag[..4] {
+        b"file" => 0,
+        tag => std::str::from_utf8(&tag[1..])
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(|| LoadError::UnsupportedFormat("GIMP document version".into()))?,
+    };
+
+    let width = reader.u32()?;
+    let height = reader.u32()?;
+    let base_type = reader.u32()?;
+    if version >= 4 {
+        let precision = reader.u32()?;
+        let eight_bit = if version < 6 {
+            precision == 0
+        } else {
+            matches!(precision, 100 | 150)
+        };
+        if !eight_bit {
+            return Err(LoadError::UnsupportedCodec(
+                "High bit depth GIMP document".into(),
+            ));
+        }
+    }
+    if base_type > 2 {
+        return Err(LoadError::UnsupportedCodec(format!(
+            "GIMP image type {base_type}"
+        )));
+    }
+
+    let mut colormap = Vec::new();
+    let mut compression = 0;
+    xcf_properties(&mut reader, |kind, mut payload| {
+        match kind {
+            PROP_COLORMAP => {
+                let count = payload.u32()?;
+                for _ in 0..count {
+                    colormap.push(payload.array()?);
+                }
+            }
+            PROP_COMPRESSION => compression = payload.u8()?,
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    let wide = version >= 11;
+    let mut layers = Vec::new();
+    loop {
+        match reader.length(wide)? {
+            0 => break,
+            offset => layers.push(offset),
+        }
+    }
+
+    Ok(XcfDocument {
+        bytes,
+        wide,
+        width,
+        height,
+        colormap,
+        compression,
+        layers,
+    })
+}
+
+fn xcf_layer(document: &XcfDocument, offset: usize) -> Result<XcfLayer, LoadError> {
+    let mut reader = Reader::at(document.bytes, offset);
+    let width = reader.u32()? as usize;
+    let height = reader.u32()? as usize;
+    let kind = reader.u32()?;
+    let name_len = reader.u32()? as usize;
+    reader.take(name_len)?;
+
+    let mut layer = XcfLayer {
+        width,
+        height,
+        kind,
+        visible: true,
+        opacity: 1.0,
+        x: 0,
+        y: 0,
+        group: false,
+        path: Vec::new(),
+        apply_mask: false,
+        hierarchy: 0,
+        mask: 0,
+    };
+    xcf_properties(&mut reader, |kind, mut payload| {
+        match kind {
+            PROP_OPACITY => layer.opacity = payload.u32()?.min(255) as f32 / 255.0,
+            PROP_FLOAT_OPACITY => {
+                layer.opacity = f32::from_bits(payload.u32()?).clamp(0.0, 1.0);
+            }
+            PROP_VISIBLE => layer.visible = payload.u32()? != 0,
+            PROP_APPLY_MASK => layer.apply_mask = payload.u32()? != 0,
+            PROP_OFFSETS => {
+                layer.x = i64::from(payload.i32()?);
+                layer.y = i64::from(payload.i32()?);
+            }
+            PROP_GROUP_ITEM => layer.group = true,
+            PROP_ITEM_PATH => {
+                while payload.pos < payload.bytes.len() {
+                    layer.path.push(payload.u32()?);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    layer.hierarchy = reader.length(document.wide)?;
+    layer.mask = reader.length(document.wide)?;
+    Ok(layer)
+}
+
+/// Interleaved pixels of the top level of a hierarchy, returns (bytes_per_pixel, pixels)
+fn xcf_hierarchy(document: &XcfDocument, offset: usize) -> Result<(usize, Vec<u8>), LoadError> {
+    let mut reader = Reader::at(document.bytes, offset);
+    let width = reader.u32()? as usize;
+    let height = reader.u32()? as usize;
+    let bpp = reader.u32()? as usize;
+    let level = reader.length(document.wide)?;
+    if !(1..=4).contains(&bpp) {
+        return Err(LoadError::UnsupportedCodec(format!(
+            "GIMP layer with {bpp} bytes per pixel"
+        )));
+    }
+
+    // The level repeats the size, then lists its tiles row by row
+    let mut reader = Reader::at(document.bytes, level);
+    reader.take(8)?;
+    let len = buffer_len(width, height, bpp, XCF_MAX_SIDE)?;
+    let tiles_x = width.div_ceil(XCF_TILE_SIZE);
+    let tiles_y = height.div_ceil(XCF_TILE_SIZE);
+    let offset_len = if document.wide { 8 } else { 4 };
+    if tiles_x * tiles_y * offset_len > reader.remaining() {
+        return Err(LoadError::Truncated);
+    }
+    let tiles = (0..tiles_x * tiles_y)
+        .map(|_| reader.length(document.wide))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut pixels = buffer(len)?;
+    pixels.resize(len, 0);
+    for (index, &tile) in tiles.iter().enumerate() {
+        let left = index % tiles_x * XCF_TILE_SIZE;
+        let top = index / tiles_x * XCF_TILE_SIZE;
+        let tile_width = XCF_TILE_SIZE.min(width - left);
+        let tile_height = XCF_TILE_SIZE.min(height - top);
+        let tile_len = tile_width * tile_height;
+
+        let mut reader = Reader::at(document.bytes, tile);
+        let data = match document.compression {
+            0 => reader.take(tile_len * bpp)?.to_vec(),
+            1 => {
+                // Each channel is run-length encoded on its own
+                let planes = (0..bpp)
+                    .map(|_| xcf_unrle(&mut reader, tile_len))
+                    .collect::<Result<Vec<_>, _>>()?;
+                (0..tile_len)
+                    .flat_map(|pixel| planes.iter().map(move |plane| plane[pixel]))
+                    .collect()
+            }
+            compression => {
+                return Err(LoadError::UnsupportedCodec(format!(
+                    "GIMP compression {compression}"
+                )));
+            }
+        };
+
+        for (row, source) in data.chunks_exact(tile_width * bpp).enumerate() {
+            let start = ((top + row) * width + left) * bpp;
+            pixels[start..start + source.len()].copy_from_slice(source);
+        }
+    }
+
+    Ok((bpp, pixels))
+}
+
+/// Decode `len` bytes of one channel of an RLE compressed tile
+fn xcf_unrle(reader: &mut Reader, len: usize) -> Result<Vec<u8>, LoadError> {
+    let mut out = Vec::with_capacity(len);
+
+    while out.len() < len {
+        match reader.u8()? {
+            // Short run of one value
+            op @ 0..=126 => {
+                let value = reader.u8()?;
+                out.resize(out.len() + usize::from(op) + 1, value);
+            }
+            // Long run of one value
+            127 => {
+                let count = usize::from(reader.u16()?);
+                let value = reader.u8()?;
+                out.resize(out.len() + count, value);
+            }
+            // Long literal
+            128 => {
+                let count = usize::from(reader.u16()?);
+                out.extend_from_slice(reader.take(count)?);
+            }
+            // Short literal
+            op => out.extend_from_slice(reader.take(256 - usize::from(op))?),
+        }
+    }
+
+    out.truncate(len);
+    Ok(out)
+}
+
+fn xcf_layer_info(bytes: &[u8]) -> Result<LayerInfo, LoadError> {
+    let document = xcf_document(bytes)?;
+
+    Ok(LayerInfo {
+        canvas_width: document.width,
+        canvas_height: document.height,
+        layer_count: document.layers.len(),
+    })
+}
+
+/// Composite the visible layers bottom to top onto a transparent canvas
+fn decode_xcf(bytes: &[u8]) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    let document = xcf_document(bytes)?;
+    let layers = document
+        .layers
+        .iter()
+        .map(|&offset| xcf_layer(&document, offset))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Hidden groups hide their children and group opacity applies to them
+    let groups: HashMap<&[u32], (bool, f32)> = layers
+        .iter()
+        .filter(|layer| layer.group)
+        .map(|layer| (layer.path.as_slice(), (layer.visible, layer.opacity)))
+        .collect();
+
+    let width = document.width as usize;
+    let height = document.height as usize;
+    let len = buffer_len(width, height, 4, XCF_MAX_SIDE)?;
+    let mut canvas = buffer(len)?;
+    canvas.resize(len, 0);
+
+    for layer in layers.iter().rev().filter(|layer| !layer.group) {
+        let (visible, opacity) = (1..layer.path.len())
+            .filter_map(|depth| groups.get(&layer.path[..depth]))
+            .fold(
+                (layer.visible, layer.opacity),
+                |(visible, opacity), group| (visible && group.0, opacity * group.1),
+            );
+        if !visible || opacity <= 0.0 {
+            continue;
+        }
+
+        let (bpp, pixels) = xcf_hierarchy(&document, layer.hierarchy)?;
+        let layer_len = layer
+            .width
+            .checked_mul(layer.height)
+            .and_then(|pixels| pixels.checked_mul(bpp));
+        if xcf_bpp(layer.kind) != Some(bpp) || Some(pixels.len()) != layer_len {
+            return Err(LoadError::UnsupportedCodec(format!(
+                "GIMP layer type {}",
+                layer.kind
+            )));
+        }
+        let mask = if layer.apply_mask && layer.mask != 0 {
+            Some(xcf_mask(&document, layer.mask)?)
+        } else {
+            None
+        };
+
+        for row in 0..layer.height {
+            let y = layer.y + row as i64;
+            if !(0..height as i64).contains(&y) {
+                continue;
+            }
+            for column in 0..layer.width {
+                let x = layer.x + column as i64;
+                if !(0..width as i64).contains(&x) {
+                    continue;
+                }
+
+                let index = row * layer.width + column;
+                let source = &pixels[index * bpp..(index + 1) * bpp];
+                let [r, g, b, a] = xcf_rgba(&document, layer.kind, source);
+                let coverage = mask.as_ref().map_or(1.0, |mask| {
+                    mask.get(index)
+                        .map_or(1.0, |&value| f32::from(value) / 255.0)
+                });
+
+                let target = (y as usize * width + x as usize) * 4;
+                blend_over(
+                    &mut canvas[target..target + 4],
+                    [r, g, b],
+                    f32::from(a) / 255.0 * opacity * coverage,
+                );
+            }
+        }
+    }
+
+    Ok((document.width, document.height, canvas))
+}
+
+/// Grayscale pixels of a layer mask; masks are channels with their own header
+fn xcf_mask(document: &XcfDocument, offset: usize) -> Result<Vec<u8>, LoadError> {
+    let mut reader = Reader::at(document.bytes, offset);
+    reader.take(8)?;
+    let name_len = reader.u32()? as usize;
+    reader.take(name_len)?;
+    xcf_properties(&mut reader, |_, _| Ok(()))?;
+
+    let hierarchy = reader.length(document.wide)?;
+    Ok(xcf_hierarchy(document, hierarchy)?.1)
+}
+
+/// Bytes per pixel of an XCF layer type: RGB, gray and indexed, each without and with alpha
+fn xcf_bpp(kind: u32) -> Option<usize> {
+    [3, 4, 1, 2, 1, 2].get(kind as usize).copied()
+}
+
+fn xcf_rgba(document: &XcfDocument, kind: u32, pixel: &[u8]) -> [u8; 4] {
+    let alpha = |index: usize| pixel.get(index).copied().unwrap_or(255);
+    let indexed = |index: u8| {
+        document
+            .colormap
+            .get(usize::from(index))
+            .copied()
+            .unwrap_or_default()
+    };
+
+    match kind {
+        0 | 1 => [pixel[0], pixel[1], pixel[2], alpha(3)],
+        2 | 3 => [pixel[0], pixel[0], pixel[0], alpha(1)],
+        _ => {
+            let [r, g, b] = indexed(pixel[0]);
+            [r, g, b, alpha(1)]
+        }
+    }
+}
+
+/// Straight alpha "over" of a color with `alpha` coverage onto an RGBA pixel
+fn blend_over(target: &mut [u8], color: [u8; 3], alpha: f32) {
+    let below = f32::from(target[3]) / 255.0;
+    let out = alpha + below * (1.0 - alpha);
+    if out <= 0.0 {
+        return;
+    }
+
+    for (channel, value) in target.iter_mut().zip(color) {
+        let mixed = (f32::from(value) * alpha + f32::from(*channel) * below * (1.0 - alpha)) / out;
+        *channel = mixed.round() as u8;
+    }
+    target[3] = (out * 255.0).round() as u8;
+}
+
+// Krita
+
+fn read_kra_entry(bytes: &[u8], name: &str) -> Result<Vec<u8>, LoadError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(io::Error::other)?;
+    let mut entry = archive.by_name(name).map_err(io::Error::other)?;
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Decode the merged PNG Krita saves next to the layers
+fn decode_kra(bytes: &[u8]) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    let merged = read_kra_entry(bytes, "mergedimage.png")?;
+    let rgba = image::load_from_memory_with_format(&merged, image::ImageFormat::Png)?.into_rgba8();
+    let (width, height) = rgba.dimensions();
+    Ok((width, height, rgba.into_raw()))
+}
+
+fn kra_layer_info(bytes: &[u8]) -> Result<LayerInfo, LoadError> {
+    let document = read_kra_entry(bytes, "maindoc.xml")?;
+    let document = String::from_utf8_lossy(&document);
+
+    let image = document
+        .find("<IMAGE ")
+        .and_then(|start| {
+            let tag = &document[start..];
+            tag.find('>').map(|end| &tag[..end])
+        })
+        .ok_or_else(|| LoadError::UnsupportedFormat("Krita document has no image".into()))?;
+    let size = |name: &str| {
+        xml_attribute(image, name)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0)
+    };
+
+    Ok(LayerInfo {
+        canvas_width: size("width"),
+        canvas_height: size("height"),
+        layer_count: document.matches("<layer ").count(),
+    })
+}
+
+/// Value of attribute `name` in the text of an XML start tag
+fn xml_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!(" {name}=\"");
+    let start = tag.find(&key)? + key.len();
+    let end = tag[start..].find('"')?;
+    Some(&tag[start..start + end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat Photoshop document with empty color mode, resource and layer sections
+    fn psd(large: bool, channels: u16, width: u32, height: u32, image_data: &[u8]) -> Vec<u8> {
+        let mut bytes = b"8BPS".to_vec();
+        bytes.extend_from_slice(&(1 + u16::from(large)).to_be_bytes());
+        bytes.extend_from_slice(&[0; 6]);
+        bytes.extend_from_slice(&channels.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&width.to_be_bytes());
+        // 8 bit RGB
+        bytes.extend_from_slice(&8u16.to_be_bytes());
+        bytes.extend_from_slice(&3u16.to_be_bytes());
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.extend_from_slice(if large { &[0; 8] } else { &[0; 4] });
+        bytes.extend_from_slice(image_data);
+        bytes
+    }
+
+    /// GIMP document header without properties or layers
+    fn xcf(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"gimp xcf file\0".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        // End of properties, end of layers
+        bytes.extend_from_slice(&[0; 12]);
+        bytes
+    }
+
+    #[test]
+    fn test_decode_psd_raw() {
+        let bytes = psd(false, 3, 2, 1, &[0, 0, 10, 20, 30, 40, 50, 60]);
+        let (width, height, pixels) = decode_psd(&bytes).unwrap();
+
+        assert_eq!((width, height), (2, 1));
+        assert_eq!(pixels, [10, 30, 50, 255, 20, 40, 60, 255]);
+    }
+
+    #[test]
+    fn test_decode_psd_rle() {
+        // Every row is a run of two equal bytes
+        let mut data = vec![0, 1];
+        data.extend_from_slice(&[0, 2, 0, 2, 0, 2]);
+        data.extend_from_slice(&[255, 10, 255, 20, 255, 30]);
+        let (_, _, pixels) = decode_psd(&psd(false, 3, 2, 1, &data)).unwrap();
+
+        assert_eq!(pixels, [10, 20, 30, 255, 10, 20, 30, 255]);
+    }
+
+    #[test]
+    fn test_unpack_bits() {
+        let mut out = Vec::new();
+        // Literal of two bytes, then a run of three
+        unpack_bits(&[1, 7, 8, 254, 9], 5, &mut out);
+        assert_eq!(out, [7, 8, 9, 9, 9]);
+
+        // Short data is padded, long runs are cut at the row length
+        let mut out = Vec::new();
+        unpack_bits(&[0, 4], 3, &mut out);
+        unpack_bits(&[129, 5], 3, &mut out);
+        assert_eq!(out, [4, 0, 0, 5, 5, 5]);
+    }
+
+    #[test]
+    fn test_psd_header_limits() {
+        let header = |bytes: &[u8]| psd_header(&mut Reader::new(bytes)).map(|_| ());
+
+        assert!(header(&psd(false, 3, 30_000, 30_000, &[])).is_ok());
+        assert!(header(&psd(true, 3, 300_000, 1, &[])).is_ok());
+        assert!(matches!(
+            header(&psd(false, 3, 30_001, 1, &[])),
+            Err(LoadError::TooLarge)
+        ));
+        assert!(matches!(
+            header(&psd(true, 3, 1, 300_001, &[])),
+            Err(LoadError::TooLarge)
+        ));
+        assert!(header(&psd(false, 3, 0, 1, &[])).is_err());
+    }
+
+    #[test]
+    fn test_decode_psd_checks_data_length() {
+        // Raw planes far larger than the file
+        let bytes = psd(false, 3, 30_000, 30_000, &[0, 0, 1, 2, 3]);
+        assert!(matches!(decode_psd(&bytes), Err(LoadError::Truncated)));
+
+        // Row lengths of a huge compressed image run past the end
+        let bytes = psd(true, 3, 300_000, 300_000, &[0, 1, 0, 0, 0, 2]);
+        assert!(matches!(decode_psd(&bytes), Err(LoadError::Truncated)));
+
+        // Row lengths present, but far too little data to unpack
+        let mut data = vec![0, 1];
+        data.extend((0..3 * 1000).flat_map(|_| 2u16.to_be_bytes()));
+        data.extend_from_slice(&[129, 0]);
+        let bytes = psd(false, 3, 30_000, 1000, &data);
+        assert!(matches!(decode_psd(&bytes), Err(LoadError::Truncated)));
+    }
+
+    #[test]
+    fn test_xcf_unrle() {
+        // Short run of three, short literal of two, long run of 300
+        let bytes = [2, 9, 254, 1, 2, 127, 1, 44, 5];
+        let out = xcf_unrle(&mut Reader::new(&bytes), 305).unwrap();
+
+        assert_eq!(&out[..5], [9, 9, 9, 1, 2]);
+        assert!(out[5..].iter().all(|&value| value == 5));
+
+        // Runs past the end of the data
+        assert!(matches!(
+            xcf_unrle(&mut Reader::new(&[128, 0, 10, 1]), 10),
+            Err(LoadError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn test_decode_xcf_limits() {
+        let (width, height, pixels) = decode_xcf(&xcf(3, 2)).unwrap();
+        assert_eq!((width, height), (3, 2));
+        assert_eq!(pixels, [0; 24]);
+
+        assert!(matches!(
+            decode_xcf(&xcf(XCF_MAX_SIDE + 1, 1)),
+            Err(LoadError::TooLarge)
+        ));
+    }
+
+    #[test]
+    fn test_xcf_hierarchy_checks_tile_table() {
+        // A 100000 x 100000 hierarchy whose tile table is missing
+        let mut bytes = Vec::new();
+        for value in [100_000u32, 100_000, 4, 16, 100_000, 100_000] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        let document = XcfDocument {
+            bytes: &bytes,
+            wide: false,
+            width: 100_000,
+            height: 100_000,
+            colormap: Vec::new(),
+            compression: 0,
+            layers: Vec::new(),
+        };
+
+        assert!(matches!(
+            xcf_hierarchy(&document, 0),
+            Err(LoadError::Truncated)
+        ));
+    }
+}
@@ -1,9 +1,29 @@
 pub mod cache;
+pub mod cancel;
+pub mod duplicates;
 pub mod edit;
+pub mod layered;
 pub mod loader;
+pub mod metadata;
+pub mod pages;
+pub mod pyramid;
+pub mod scheduler;
+pub mod svg;
 
 pub use cache::{CachedImage, ImageCache};
-pub use loader::{LoadError, LoadedImage, load_image, load_thumbnail};
+pub use cancel::CancelToken;
+pub use duplicates::{
+    DuplicateEntry, DuplicateGroup, ImageFingerprint, fingerprint_images, group_duplicates,
+};
+pub use layered::{LayerInfo, layer_info, load_layer_info};
+pub use loader::{
+    LoadError, LoadedImage, load_format, load_image, load_preview, load_thumbnail, prefetch_image,
+};
+pub use metadata::{KnownDates, capture_date, capture_dates};
+pub use pages::{load_page, load_page_thumbnails, page_count};
+pub use pyramid::{ImagePyramid, load_pyramid};
+pub use scheduler::{DecodeScheduler, JobKind, Priority, scheduler};
+pub use svg::render_vector;
 
 pub fn register_format_hooks() {
     #[cfg(feature = "heif")]
@@ -1,22 +1,81 @@
+use crate::{
+    cancel::CancelToken,
+    layered, pages,
+    pyramid::ImagePyramid,
+    scheduler::{JobKind, Priority, scheduler},
+    svg,
+};
 use cosmic::widget::image::Handle;
 use fast_image_resize::{PixelType, ResizeAlg, ResizeOptions, Resizer, images::Image as FirImage};
+use image::{ColorType, DynamicImage, ImageBuffer, ImageDecoder};
+use shared::{
+    formats::{ImageFormat, SNIFF_LEN},
+    tiling, tone_map,
+};
 use std::{
     fmt::{self, Debug, Formatter},
-    fs::File,
+    fs::{self, File},
+    io::{Cursor, ErrorKind, Read},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 use thiserror::Error;
+use viewer_nav::ImageSource;
+
+/// Largest allocation a decoder may make, a gigapixel of RGBA. The image
+/// crate's default of 512 MiB turns away images the pyramid can show.
+pub(crate) const MAX_DECODE_BYTES: u64 = 4 << 30;
 
+/// Largest pixel buffer allocated to salvage a damaged file, 512 megapixels of RGBA
+const MAX_DAMAGED_BYTES: u64 = 2 << 30;
+
+/// Why an image couldn't be loaded. The common causes have variants of their
+/// own so the viewer can explain them in the user's language.
 #[derive(Debug, Error)]
 pub enum LoadError {
+    #[error("File not found")]
+    NotFound,
+    #[error("Permission denied")]
+    PermissionDenied,
+    #[error("File is truncated")]
+    Truncated,
+    #[error("Unsupported codec: {0}")]
+    UnsupportedCodec(String),
     #[error("Failed to read file: {0}")]
-    Io(#[from] std::io::Error),
+    Io(std::io::Error),
     #[error("Failed to decode image: {0}")]
-    Decode(#[from] image::ImageError),
+    Decode(image::ImageError),
     #[error("Unsupported format: {0}")]
     UnsupportedFormat(String),
     #[error("Task cancelled")]
     Cancelled,
+    #[error("Image exceeds the prefetch budget")]
+    OverBudget,
+    #[error("Image is too large to decode")]
+    TooLarge,
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            ErrorKind::NotFound => Self::NotFound,
+            ErrorKind::PermissionDenied => Self::PermissionDenied,
+            ErrorKind::UnexpectedEof => Self::Truncated,
+            ErrorKind::FileTooLarge => Self::TooLarge,
+            _ => Self::Io(err),
+        }
+    }
+}
+
+impl From<image::ImageError> for LoadError {
+    fn from(err: image::ImageError) -> Self {
+        match err {
+            image::ImageError::IoError(err) => err.into(),
+            image::ImageError::Unsupported(err) => Self::UnsupportedCodec(err.to_string()),
+            image::ImageError::Limits(_) => Self::TooLarge,
+            err => Self::Decode(err),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -25,6 +84,13 @@ pub struct LoadedImage {
     pub width: u32,
     pub height: u32,
     pub path: PathBuf,
+    /// Page of a multi-page file, 0 for everything else
+    pub page: usize,
+    pub page_count: usize,
+    /// Decoded from a truncated or corrupt file, the part after the damage is missing
+    pub partial: bool,
+    /// Tile pyramid of a very large image, when it came out of the same decode as the preview
+    pub pyramid: Option<Arc<ImagePyramid>>,
 }
 
 impl Debug for LoadedImage {
@@ -33,43 +99,203 @@ impl Debug for LoadedImage {
             .field("width", &self.width)
             .field("height", &self.height)
             .field("path", &self.path)
+            .field("page", &self.page)
+            .field("page_count", &self.page_count)
+            .field("partial", &self.partial)
+            .field("pyramid", &self.pyramid.is_some())
             .finish()
     }
 }
 
-pub async fn load_image(path: PathBuf) -> Result<LoadedImage, LoadError> {
-    let (tx, rx) = tokio::sync::oneshot::channel();
+pub async fn load_image(path: PathBuf, priority: Priority) -> Result<LoadedImage, LoadError> {
+    let task_path = path.clone();
+
+    scheduler()
+        .submit(
+            path,
+            JobKind::Image,
+            priority,
+            CancelToken::new(),
+            move |_| load_image_sync(&task_path, true),
+        )
+        .await
+}
+
+/// Decode a neighbouring image ahead of time. Skips images whose decoded size exceeds
+/// `max_bytes` and gives up as soon as `token` is cancelled.
+pub async fn prefetch_image(
+    path: PathBuf,
+    max_bytes: u64,
+    token: CancelToken,
+) -> Result<LoadedImage, LoadError> {
+    let task_path = path.clone();
+
+    scheduler()
+        .submit(
+            path,
+            JobKind::Image,
+            Priority::Prefetch,
+            token,
+            move |token| prefetch_image_sync(&task_path, max_bytes, token),
+        )
+        .await
+}
+
+fn prefetch_image_sync(
+    path: &Path,
+    max_bytes: u64,
+    token: &CancelToken,
+) -> Result<LoadedImage, LoadError> {
+    // Tiled images only decode a preview up front, so they always fit
+    if let Ok((width, height)) = probe_dimensions(path)
+        && !tiling::needs_tiling(width, height)
+        && width as u64 * height as u64 * 4 > max_bytes
+    {
+        return Err(LoadError::OverBudget);
+    }
+
+    // A pyramid would hold the full resolution pixels of an image that may never be shown
+    let image = load_image_sync(path, false)?;
+
+    if token.is_cancelled() {
+        return Err(LoadError::Cancelled);
+    }
+
+    Ok(image)
+}
+
+/// Decode an image for display. Very large images open at fit-to-window resolution,
+/// with their tile pyramid when `with_pyramid` is set and the preview needed a full decode.
+fn load_image_sync(path: &Path, with_pyramid: bool) -> Result<LoadedImage, LoadError> {
+    let page_count = pages::page_count(path);
+
+    if let Ok((width, height)) = probe_dimensions(path)
+        && tiling::needs_tiling(width, height)
+    {
+        let decoded = if with_pyramid {
+            decode_tiled(path)
+        } else {
+            decode_preview(path, tiling::TILED_PREVIEW_SIZE)
+                .map(|(width, height, pixels)| (width, height, pixels, None))
+        };
+        let (preview_width, preview_height, pixels, pyramid, partial) = match decoded {
+            Ok((width, height, pixels, pyramid)) => (width, height, pixels, pyramid, false),
+            Err(err) => {
+                let (width, height, pixels) = decode_damaged(path).map_err(|_| err)?;
+                tracing::warn!("Showing the readable part of damaged {}", path.display());
+                let (width, height, pixels) =
+                    fast_resize_rgba(&pixels, width, height, tiling::TILED_PREVIEW_SIZE)?;
+                (width, height, pixels, None, true)
+            }
+        };
+
+        return Ok(LoadedImage {
+            handle: Handle::from_rgba(preview_width, preview_height, pixels),
+            width,
+            height,
+            path: path.to_path_buf(),
+            page: 0,
+            page_count,
+            partial,
+            pyramid,
+        });
+    }
+
+    let (width, height, pixels, partial) = match decode_rgba(path) {
+        Ok((width, height, pixels)) => (width, height, pixels, false),
+        Err(err) => {
+            let (width, height, pixels) = decode_damaged(path).map_err(|_| err)?;
+            tracing::warn!("Showing the readable part of damaged {}", path.display());
+            (width, height, pixels, true)
+        }
+    };
+    let handle = Handle::from_rgba(width, height, pixels);
+
+    Ok(LoadedImage {
+        handle,
+        width,
+        height,
+        path: path.to_path_buf(),
+        page: 0,
+        page_count,
+        partial,
+        pyramid: None,
+    })
+}
+
+/// Decode a large image once for both its screen resolution preview and its tile
+/// pyramid, returns (width, height, rgba_pixels, pyramid)
+fn decode_tiled(path: &Path) -> Result<(u32, u32, Vec<u8>, Option<Arc<ImagePyramid>>), LoadError> {
+    // DCT scaling reaches screen resolution without a full decode, so the
+    // pyramid is left to `load_pyramid` and the preview shows up early
+    if detect_format(path) == Some(ImageFormat::Jpeg)
+        && let Ok((width, height, pixels)) = decode_jpeg_scaled(path, tiling::TILED_PREVIEW_SIZE)
+    {
+        return Ok((width, height, pixels, None));
+    }
+
+    let (width, height, pixels) = decode_rgba(path)?;
+    let pyramid = ImagePyramid::build(width, height, pixels)?;
+    let (preview_width, preview_height, preview) = pyramid.preview(tiling::TILED_PREVIEW_SIZE)?;
+
+    Ok((
+        preview_width,
+        preview_height,
+        preview,
+        Some(Arc::new(pyramid)),
+    ))
+}
 
-    rayon::spawn(move || {
-        let result = load_image_sync(&path);
-        let _ = tx.send(result);
-    });
+/// Decode a large image at screen resolution, returns (width, height, rgba_pixels)
+fn decode_preview(path: &Path, max_size: u32) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    // JPEGs skip most of the work with DCT scaling. Embedded EXIF
+    // thumbnails are too small to stand in for the full image here.
+    if detect_format(path) == Some(ImageFormat::Jpeg)
+        && let Ok(result) = decode_jpeg_scaled(path, max_size)
+    {
+        return Ok(result);
+    }
 
-    rx.await.map_err(|_| LoadError::Cancelled)?
+    let (width, height, pixels) = decode_rgba(path)?;
+    fast_resize_rgba(&pixels, width, height, max_size)
 }
 
-fn load_image_sync(path: &Path) -> Result<LoadedImage, LoadError> {
-    let extension = path
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.to_lowercase())
-        .unwrap_or_default();
+/// Decode a full resolution RGBA buffer, returns (width, height, rgba_pixels)
+pub(crate) fn decode_rgba(path: &Path) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    let source = ImageSource::locate(path);
+    if source.is_archive_member() {
+        return decode_from_memory(&source.read()?, path);
+    }
+
+    let format = detect_format(path);
+
+    if format == Some(ImageFormat::Svg) {
+        return svg::decode_svg(&fs::read(path)?, path);
+    }
+
+    if let Some(format) = format.filter(|format| format.is_layered()) {
+        return layered::decode_composite(&fs::read(path)?, format);
+    }
 
     // Handle HEIC separately if feature is enabled
     #[cfg(feature = "heif")]
-    if extension == "heic" || extension == "heif" {
+    if format == Some(ImageFormat::Heif) {
         return load_heif(path);
     }
 
+    if format == Some(ImageFormat::JpegXl) {
+        return load_jxl(&fs::read(path)?);
+    }
+
     // Use turbojpeg for JPEGs (faster than zune/image crate)
-    if matches!(extension.as_str(), "jpg" | "jpeg")
-        && let Ok(img) = load_jpeg_full(path)
+    if format == Some(ImageFormat::Jpeg)
+        && let Ok(img) = load_jpeg_full(path, false)
     {
         return Ok(img);
     }
     // Fall through to other decoders if turbojpeg fails
 
-    if is_zune_supported(&extension) {
+    if format.is_some_and(is_zune_supported) {
         match load_with_zune(path) {
             Ok(img) => return Ok(img),
             Err(_) => {
@@ -79,24 +305,12 @@ fn load_image_sync(path: &Path) -> Result<LoadedImage, LoadError> {
     }
 
     // Standard image formats via the 'image' crate
-    let img = image::open(path)?;
-    let rgba = img.to_rgba8();
-    let (width, height) = rgba.dimensions();
-    let pixels = rgba.into_raw();
-
-    let handle = Handle::from_rgba(width, height, pixels);
-
-    Ok(LoadedImage {
-        handle,
-        width,
-        height,
-        path: path.to_path_buf(),
-    })
+    load_with_image(path)
 }
 
-/// Load full JPEG using turbojpeg (faster than zune/image crate)
-fn load_jpeg_full(path: &Path) -> Result<LoadedImage, LoadError> {
-    use std::io::Read;
+/// Load full JPEG using turbojpeg (faster than zune/image crate). With `keep_partial`
+/// a decode error still returns the rows decoded before it.
+fn load_jpeg_full(path: &Path, keep_partial: bool) -> Result<(u32, u32, Vec<u8>), LoadError> {
     use turbojpeg::{Decompressor, Image, PixelFormat};
 
     let mut file = File::open(path)?;
@@ -124,40 +338,127 @@ fn load_jpeg_full(path: &Path) -> Result<LoadedImage, LoadError> {
         format: PixelFormat::RGBA,
     };
 
-    decompressor
-        .decompress(&jpeg_data, output.as_deref_mut())
-        .map_err(|e| LoadError::UnsupportedFormat(format!("JPEG decode error: {}", e)))?;
+    // Decoded pixels are opaque, so a transparent first pixel means nothing was
+    // recovered. Truncated files decode to the end with the missing rows gray.
+    if let Err(e) = decompressor.decompress(&jpeg_data, output.as_deref_mut())
+        && (!keep_partial || pixels.get(3).is_none_or(|&alpha| alpha == 0))
+    {
+        return Err(LoadError::UnsupportedFormat(format!(
+            "JPEG decode error: {}",
+            e
+        )));
+    }
 
-    let handle = Handle::from_rgba(width as u32, height as u32, pixels);
+    Ok((width as u32, height as u32, pixels))
+}
 
-    Ok(LoadedImage {
-        handle,
-        width: width as u32,
-        height: height as u32,
-        path: path.to_path_buf(),
-    })
+/// Decode what can be read of a truncated or corrupt file, returns (width, height,
+/// rgba_pixels). Used once the regular decoders have given up on it.
+fn decode_damaged(path: &Path) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    // Archive members are read whole and have no partial file to salvage
+    if ImageSource::locate(path).is_archive_member() {
+        return Err(LoadError::Truncated);
+    }
+
+    if detect_format(path) == Some(ImageFormat::Jpeg)
+        && let Ok(img) = load_jpeg_full(path, true)
+    {
+        return Ok(img);
+    }
+
+    let reader = image::ImageReader::open(path)?.with_guessed_format()?;
+    let decoder = reader.into_decoder()?;
+    let (width, height) = decoder.dimensions();
+    let color = decoder.color_type();
+    // The header of a damaged file can't be trusted with the allocation
+    let len = usize::try_from(decoder.total_bytes())
+        .ok()
+        .filter(|&len| len as u64 <= MAX_DAMAGED_BYTES)
+        .ok_or(LoadError::TooLarge)?;
+    let mut buffer = Vec::new();
+    buffer
+        .try_reserve_exact(len)
+        .map_err(|_| LoadError::TooLarge)?;
+    buffer.resize(len, 0);
+
+    // Decoders fill the buffer from the top, so the rows before the damage are kept
+    if decoder.read_image(&mut buffer).is_err() && buffer.iter().all(|&byte| byte == 0) {
+        return Err(LoadError::Truncated);
+    }
+
+    let image = match color {
+        ColorType::L8 => ImageBuffer::from_raw(width, height, buffer).map(DynamicImage::ImageLuma8),
+        ColorType::La8 => {
+            ImageBuffer::from_raw(width, height, buffer).map(DynamicImage::ImageLumaA8)
+        }
+        ColorType::Rgb8 => {
+            ImageBuffer::from_raw(width, height, buffer).map(DynamicImage::ImageRgb8)
+        }
+        ColorType::Rgba8 => {
+            ImageBuffer::from_raw(width, height, buffer).map(DynamicImage::ImageRgba8)
+        }
+        _ => None,
+    };
+
+    image.map(to_display_rgba).ok_or(LoadError::Truncated)
+}
+
+/// Format of the file at `path`, by its magic bytes or else by extension
+pub(crate) fn detect_format(path: &Path) -> Option<ImageFormat> {
+    let mut header = [0; SNIFF_LEN];
+    let len = File::open(path)
+        .and_then(|mut file| file.read(&mut header))
+        .unwrap_or(0);
+    ImageFormat::detect(&header[..len], path)
+}
+
+/// Detect the format of an image off the UI thread; archive members are read
+/// from their archive
+pub async fn load_format(path: PathBuf) -> Option<ImageFormat> {
+    let task_path = path.clone();
+
+    scheduler()
+        .submit(
+            path,
+            JobKind::Preview,
+            Priority::Current,
+            CancelToken::new(),
+            move |_| {
+                let source = ImageSource::locate(&task_path);
+                if source.is_archive_member() {
+                    let bytes = source.read()?;
+                    return Ok(ImageFormat::detect(&bytes, &task_path));
+                }
+                Ok(detect_format(&task_path))
+            },
+        )
+        .await
+        .ok()
+        .flatten()
 }
 
-fn is_zune_supported(extension: &str) -> bool {
+/// Dimensions from the image header, with the decoder chosen by content
+pub(crate) fn probe_dimensions(path: &Path) -> Result<(u32, u32), LoadError> {
+    Ok(image::ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_dimensions()?)
+}
+
+/// Formats zune decodes faster than the image crate. Radiance HDR is left to
+/// the image crate, which keeps the float data for tone mapping.
+fn is_zune_supported(format: ImageFormat) -> bool {
     matches!(
-        extension,
-        "jpg"
-            | "jpeg"
-            | "png"
-            | "ppm"
-            | "pgm"
-            | "pbm"
-            | "pnm"
-            | "bmp"
-            | "qoi"
-            | "ff"
-            | "farbfeld"
-            | "hdr"
-            | "jxl"
+        format,
+        ImageFormat::Jpeg
+            | ImageFormat::Png
+            | ImageFormat::Pnm
+            | ImageFormat::Bmp
+            | ImageFormat::Qoi
+            | ImageFormat::Farbfeld
     )
 }
 
-fn load_with_zune(path: &Path) -> Result<LoadedImage, LoadError> {
+fn load_with_zune(path: &Path) -> Result<(u32, u32, Vec<u8>), LoadError> {
     use zune_image::image::Image;
 
     let mut img = Image::open(path).map_err(|e| LoadError::UnsupportedFormat(e.to_string()))?;
@@ -173,35 +474,106 @@ fn load_with_zune(path: &Path) -> Result<LoadedImage, LoadError> {
         .next()
         .ok_or_else(|| LoadError::UnsupportedFormat("No pixel data".into()))?;
 
-    let handle = Handle::from_rgba(width as u32, height as u32, pixels);
+    Ok((width as u32, height as u32, pixels))
+}
 
-    Ok(LoadedImage {
-        handle,
-        width: width as u32,
-        height: height as u32,
-        path: path.to_path_buf(),
-    })
+/// Decode an image that was read into memory, e.g. from an archive
+fn decode_from_memory(bytes: &[u8], path: &Path) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    match ImageFormat::detect(bytes, path) {
+        Some(ImageFormat::JpegXl) => return load_jxl(bytes),
+        Some(ImageFormat::Svg) => return svg::decode_svg(bytes, path),
+        Some(format) if format.is_layered() => {
+            return layered::decode_composite(bytes, format);
+        }
+        _ => {}
+    }
+
+    let mut reader = image::ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    reader.limits(decode_limits());
+
+    Ok(to_display_rgba(reader.decode()?))
 }
 
-fn load_with_image(path: &Path) -> Result<LoadedImage, LoadError> {
-    let img = image::open(path)?;
-    let rgba = img.into_rgba8();
-    let (width, height) = rgba.dimensions();
-    let pixels = rgba.into_raw();
+fn load_with_image(path: &Path) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    let mut reader = image::ImageReader::open(path)?.with_guessed_format()?;
+    reader.limits(decode_limits());
 
-    let handle = Handle::from_rgba(width, height, pixels);
+    Ok(to_display_rgba(reader.decode()?))
+}
 
-    Ok(LoadedImage {
-        handle,
-        width,
-        height,
-        path: path.to_path_buf(),
-    })
+/// Allocation limits for the image crate, so a header claiming huge dimensions
+/// fails to decode instead of exhausting memory
+fn decode_limits() -> image::Limits {
+    let mut limits = image::Limits::default();
+    limits.max_alloc = Some(MAX_DECODE_BYTES);
+    limits
+}
+
+/// 8 bit RGBA for display, returns (width, height, rgba_pixels). Float images,
+/// as decoded from OpenEXR and Radiance HDR, hold linear light and are tone mapped.
+fn to_display_rgba(image: DynamicImage) -> (u32, u32, Vec<u8>) {
+    match image {
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => {
+            let rgba = image.into_rgba32f();
+            let (width, height) = rgba.dimensions();
+            (width, height, tone_map::tone_map_rgba(rgba.as_raw(), 4))
+        }
+        image => {
+            let rgba = image.into_rgba8();
+            let (width, height) = rgba.dimensions();
+            (width, height, rgba.into_raw())
+        }
+    }
+}
+
+/// Decode a JPEG XL image to sRGB; the decoder tone maps HDR images itself
+fn load_jxl(bytes: &[u8]) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    use jxl_oxide::{EnumColourEncoding, JxlImage, RenderingIntent};
+
+    let mut image = JxlImage::builder()
+        .read(Cursor::new(bytes))
+        .map_err(jxl_error)?;
+    image.request_color_encoding(EnumColourEncoding::srgb(RenderingIntent::Relative));
+
+    let render = image.render_frame(0).map_err(jxl_error)?;
+    let frame = render.image_all_channels();
+    let channels = frame.channels();
+    let to_u8 = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    let pixels = frame
+        .buf()
+        .chunks_exact(channels)
+        .flat_map(|pixel| match channels {
+            1 => [to_u8(pixel[0]), to_u8(pixel[0]), to_u8(pixel[0]), 255],
+            2 => {
+                let gray = to_u8(pixel[0]);
+                [gray, gray, gray, to_u8(pixel[1])]
+            }
+            3 => [to_u8(pixel[0]), to_u8(pixel[1]), to_u8(pixel[2]), 255],
+            _ => [
+                to_u8(pixel[0]),
+                to_u8(pixel[1]),
+                to_u8(pixel[2]),
+                to_u8(pixel[3]),
+            ],
+        })
+        .collect();
+
+    Ok((frame.width() as u32, frame.height() as u32, pixels))
+}
+
+fn jxl_error(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> LoadError {
+    LoadError::Decode(image::ImageError::Decoding(
+        image::error::DecodingError::new(
+            image::error::ImageFormatHint::Name("JPEG XL".into()),
+            err,
+        ),
+    ))
 }
 
 #[cfg(feature = "heif")]
-fn load_heif(path: &Path) -> Result<LoadedImage, LoadError> {
-    use libheif_rs::{ColorSpace, HeifContext, RgbChroma};
+fn load_heif(path: &Path) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    use libheif_rs::HeifContext;
 
     let ctx = HeifContext::read_from_file(path.to_str().unwrap()).map_err(|e| {
         LoadError::Decode(image::ImageError::Decoding(
@@ -215,6 +587,16 @@ fn load_heif(path: &Path) -> Result<LoadedImage, LoadError> {
         ))
     })?;
 
+    decode_heif_handle(&handle)
+}
+
+/// Decode one image of a HEIF file, returns (width, height, rgba_pixels)
+#[cfg(feature = "heif")]
+pub(crate) fn decode_heif_handle(
+    handle: &libheif_rs::ImageHandle,
+) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    use libheif_rs::{ColorSpace, RgbChroma};
+
     let img = handle
         .decode(ColorSpace::Rgb(RgbChroma::Rgba), None)
         .map_err(|e| {
@@ -229,76 +611,145 @@ fn load_heif(path: &Path) -> Result<LoadedImage, LoadError> {
     let interleaved = planes.interleaved.unwrap();
     let pixels = interleaved.data.to_vec();
 
-    let cosmic_handle = Handle::from_rgba(width, height, pixels);
+    Ok((width, height, pixels))
+}
+
+/// Quick reduced-resolution decode shown while the full image loads.
+/// Only JPEGs can be decoded faster than the full image, other formats return
+/// `UnsupportedFormat`.
+pub async fn load_preview(path: PathBuf, max_size: u32) -> Result<LoadedImage, LoadError> {
+    let task_path = path.clone();
+
+    scheduler()
+        .submit(
+            path,
+            JobKind::Preview,
+            Priority::Current,
+            CancelToken::new(),
+            move |_| load_preview_sync(&task_path, max_size),
+        )
+        .await
+}
+
+fn load_preview_sync(path: &Path, max_size: u32) -> Result<LoadedImage, LoadError> {
+    let format = detect_format(path);
+    if format != Some(ImageFormat::Jpeg) {
+        return Err(LoadError::UnsupportedFormat(
+            format.map_or("unknown", ImageFormat::name).to_string(),
+        ));
+    }
+
+    let (width, height, pixels) = decode_jpeg_scaled(path, max_size)?;
+    let handle = Handle::from_rgba(width, height, pixels);
 
     Ok(LoadedImage {
-        handle: cosmic_handle,
+        handle,
         width,
         height,
         path: path.to_path_buf(),
+        page: 0,
+        page_count: 1,
+        partial: false,
+        pyramid: None,
     })
 }
 
-pub async fn load_thumbnail(path: PathBuf, max_size: u32) -> Result<LoadedImage, LoadError> {
-    let (tx, rx) = tokio::sync::oneshot::channel();
+/// Queue a thumbnail decode. Its priority can be changed while it waits with
+/// `scheduler().reprioritize(JobKind::Thumbnail, ..)`.
+pub async fn load_thumbnail(
+    path: PathBuf,
+    max_size: u32,
+    priority: Priority,
+    token: CancelToken,
+) -> Result<LoadedImage, LoadError> {
+    let task_path = path.clone();
+
+    scheduler()
+        .submit(path, JobKind::Thumbnail, priority, token, move |_| {
+            load_thumbnail_sync(&task_path, max_size)
+        })
+        .await
+}
 
-    rayon::spawn(move || {
-        let result = load_thumbnail_sync(&path, max_size);
-        let _ = tx.send(result);
-    });
+fn load_thumbnail_sync(path: &Path, max_size: u32) -> Result<LoadedImage, LoadError> {
+    let (width, height, pixels, partial) = match decode_thumbnail(path, max_size) {
+        Ok((width, height, pixels)) => (width, height, pixels, false),
+        Err(err) => {
+            let (width, height, pixels) = decode_damaged(path).map_err(|_| err)?;
+            let (width, height, pixels) = if width <= max_size && height <= max_size {
+                (width, height, pixels)
+            } else {
+                fast_resize_rgba(&pixels, width, height, max_size)?
+            };
+            (width, height, pixels, true)
+        }
+    };
+    let handle = Handle::from_rgba(width, height, pixels);
 
-    rx.await.map_err(|_| LoadError::Cancelled)?
+    Ok(LoadedImage {
+        handle,
+        width,
+        height,
+        path: path.to_path_buf(),
+        page: 0,
+        page_count: 1,
+        partial,
+        pyramid: None,
+    })
 }
 
-fn load_thumbnail_sync(path: &Path, max_size: u32) -> Result<LoadedImage, LoadError> {
-    let extension = path
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.to_lowercase())
-        .unwrap_or_default();
+/// Decode a downscaled RGBA buffer, returns (width, height, rgba_pixels)
+pub(crate) fn decode_thumbnail(
+    path: &Path,
+    max_size: u32,
+) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    let format = detect_format(path);
+    let source = ImageSource::locate(path);
+
+    // Vector images render straight at the thumbnail size
+    if format == Some(ImageFormat::Svg) {
+        return svg::decode_svg_thumbnail(&source.read()?, path, max_size);
+    }
+
+    // Archive members have no file to seek in and layered documents no
+    // smaller image to read, decode them whole
+    if source.is_archive_member() || format.is_some_and(ImageFormat::is_layered) {
+        let (width, height, pixels) = decode_from_memory(&source.read()?, path)?;
+        if width <= max_size && height <= max_size {
+            return Ok((width, height, pixels));
+        }
+        return fast_resize_rgba(&pixels, width, height, max_size);
+    }
 
     // 1. For JPEGs, try EXIF thumbnail extraction (very fast, no full decode)
-    if matches!(extension.as_str(), "jpg" | "jpeg") {
-        if let Ok((width, height, pixels)) = extract_exif_thumbnail(path, max_size) {
-            let handle = Handle::from_rgba(width, height, pixels);
-            return Ok(LoadedImage {
-                handle,
-                width,
-                height,
-                path: path.to_path_buf(),
-            });
+    if format == Some(ImageFormat::Jpeg) {
+        if let Ok(result) = extract_exif_thumbnail(path, max_size) {
+            return Ok(result);
         }
 
         // 2. For JPEGs without EXIF, use turbojpeg with DCT scaling (4-8x faster)
-        if let Ok((width, height, pixels)) = decode_jpeg_scaled(path, max_size) {
-            let handle = Handle::from_rgba(width, height, pixels);
-            return Ok(LoadedImage {
-                handle,
-                width,
-                height,
-                path: path.to_path_buf(),
-            });
+        if let Ok(result) = decode_jpeg_scaled(path, max_size) {
+            return Ok(result);
+        }
+    }
+
+    if format == Some(ImageFormat::JpegXl) {
+        let (width, height, pixels) = load_jxl(&fs::read(path)?)?;
+        if width <= max_size && height <= max_size {
+            return Ok((width, height, pixels));
         }
+        return fast_resize_rgba(&pixels, width, height, max_size);
     }
 
     // 3. Fall back to full decode + resize (non-JPEGs or if turbojpeg fails)
-    let (width, height, pixels) = if is_zune_supported(&extension) {
+    if format.is_some_and(is_zune_supported) {
         match decode_and_resize_zune(path, max_size) {
-            Ok(result) => result,
-            Err(_) => decode_and_resize_image(path, max_size)?,
+            Ok(result) => Ok(result),
+            Err(_) => decode_and_resize_image(path, max_size),
         }
     } else {
-        decode_and_resize_image(path, max_size)?
-    };
-
-    let handle = Handle::from_rgba(width, height, pixels);
-
-    Ok(LoadedImage {
-        handle,
-        width,
-        height,
-        path: path.to_path_buf(),
-    })
+        decode_and_resize_image(path, max_size)
+    }
 }
 
 /// Extract embedded EXIF thumbnail from JPEG files
@@ -332,7 +783,7 @@ fn extract_exif_thumbnail(path: &Path, max_size: u32) -> Result<(u32, u32, Vec<u
 
     // Decode the embedded JPEG thumbnail
     let img = image::load_from_memory_with_format(&thumb_bytes, image::ImageFormat::Jpeg)
-        .map_err(LoadError::Decode)?;
+        .map_err(LoadError::from)?;
 
     let rgba = img.to_rgba8();
     let (width, height) = rgba.dimensions();
@@ -457,7 +908,6 @@ fn find_tiff_header_offset(file: &mut File) -> Result<u64, LoadError> {
 /// Decode JPEG with DCT scaling using turbojpeg (4-8x faster than full decode)
 /// This decodes directly to a smaller resolution, skipping most IDCT computation
 fn decode_jpeg_scaled(path: &Path, max_size: u32) -> Result<(u32, u32, Vec<u8>), LoadError> {
-    use std::io::Read;
     use turbojpeg::{Decompressor, Image, PixelFormat};
 
     // Read the JPEG file
@@ -574,22 +1024,19 @@ fn decode_and_resize_zune(path: &Path, max_size: u32) -> Result<(u32, u32, Vec<u
 
 /// Decode and resize using image crate, returns (width, height, rgba_pixels)
 fn decode_and_resize_image(path: &Path, max_size: u32) -> Result<(u32, u32, Vec<u8>), LoadError> {
-    let img = image::open(path)?;
-    let rgba = img.to_rgba8();
-    let (width, height) = rgba.dimensions();
+    let (width, height, pixels) = load_with_image(path)?;
 
     // If already small enough, return directly
     if width <= max_size && height <= max_size {
-        return Ok((width, height, rgba.into_raw()));
+        return Ok((width, height, pixels));
     }
 
     // Resize
-    let pixels = rgba.into_raw();
     fast_resize_rgba(&pixels, width, height, max_size)
 }
 
 /// Fast RGBA image resize using SIMD-optimized fast_image_resize crate
-fn fast_resize_rgba(
+pub(crate) fn fast_resize_rgba(
     pixels: &[u8],
     src_width: u32,
     src_height: u32,
@@ -0,0 +1,87 @@
+//! Capture dates for the timeline view
+//!
+//! Only the EXIF block is read, never the pixels. Files without a usable
+//! `DateTimeOriginal` (or `DateTime`) fall back to their modification time.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use rayon::prelude::*;
+use shared::date::CalendarDate;
+use viewer_nav::ImageSource;
+
+/// Capture dates that were already read, with the modification time they were read at
+pub type KnownDates = HashMap<PathBuf, (SystemTime, CalendarDate)>;
+
+/// Date a photo was taken, or the file's modification date
+///
+/// Archive members are dated by the archive itself.
+pub fn capture_date(path: &Path) -> CalendarDate {
+    capture_date_at(path, modified_time(path))
+}
+
+fn capture_date_at(path: &Path, modified: SystemTime) -> CalendarDate {
+    if ImageSource::locate(path).is_archive_member() {
+        return CalendarDate::from_system_time(modified);
+    }
+
+    exif_date(path).unwrap_or_else(|| CalendarDate::from_system_time(modified))
+}
+
+/// Modification time of a file, or of the archive holding it
+fn modified_time(path: &Path) -> SystemTime {
+    let source = ImageSource::locate(path);
+    fs::metadata(source.file())
+        .and_then(|meta| meta.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+fn exif_date(path: &Path) -> Option<CalendarDate> {
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    let exif = exif::Reader::new().read_from_container(&mut reader).ok()?;
+
+    [exif::Tag::DateTimeOriginal, exif::Tag::DateTime]
+        .into_iter()
+        .filter_map(|tag| exif.get_field(tag, exif::In::PRIMARY))
+        .find_map(|field| match field.value {
+            exif::Value::Ascii(ref values) => values
+                .first()
+                .and_then(|raw| std::str::from_utf8(raw).ok())
+                .and_then(CalendarDate::from_exif),
+            _ => None,
+        })
+}
+
+/// Capture dates of all `paths`, in the same order. Only files missing from `known`
+/// or modified since are read; the returned map holds the dates of `paths`.
+pub async fn capture_dates(
+    paths: Arc<[PathBuf]>,
+    known: Arc<KnownDates>,
+) -> (Vec<CalendarDate>, KnownDates) {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    rayon::spawn(move || {
+        let stamped: Vec<(SystemTime, CalendarDate)> = paths
+            .par_iter()
+            .map(|path| {
+                let modified = modified_time(path);
+                match known.get(path) {
+                    Some(&(read_at, date)) if read_at == modified => (modified, date),
+                    _ => (modified, capture_date_at(path, modified)),
+                }
+            })
+            .collect();
+
+        let dates = stamped.iter().map(|&(_, date)| date).collect();
+        let known = paths.iter().cloned().zip(stamped).collect();
+        let _ = tx.send((dates, known));
+    });
+
+    rx.await.unwrap_or_default()
+}
@@ -0,0 +1,446 @@
+//! Files holding several images
+//!
+//! Multi-page TIFFs, icons with one entry per resolution and HEIF collections
+//! open on their first page like any other image; the other pages are decoded
+//! on request. Every other format has a single page.
+
+use crate::{
+    cancel::CancelToken,
+    loader::{
+        LoadError, LoadedImage, MAX_DECODE_BYTES, decode_rgba, detect_format, fast_resize_rgba,
+    },
+    scheduler::{JobKind, Priority, scheduler},
+};
+use cosmic::widget::image::Handle;
+use image::error::{DecodingError, ImageFormatHint};
+use shared::formats::ImageFormat;
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+use viewer_nav::ImageSource;
+
+/// Size of the ICO file header
+const ICO_HEADER_LEN: usize = 6;
+/// Size of one ICO directory entry
+const ICO_ENTRY_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PagedFormat {
+    Tiff,
+    Ico,
+    #[cfg(feature = "heif")]
+    Heif,
+}
+
+impl PagedFormat {
+    fn of(path: &Path) -> Option<Self> {
+        // Archive members are read whole from memory and only show their first page
+        if ImageSource::locate(path).is_archive_member() {
+            return None;
+        }
+
+        match detect_format(path)? {
+            ImageFormat::Tiff => Some(Self::Tiff),
+            ImageFormat::Ico => Some(Self::Ico),
+            #[cfg(feature = "heif")]
+            ImageFormat::Heif => Some(Self::Heif),
+            _ => None,
+        }
+    }
+}
+
+/// Number of pages in the file, 1 for single image formats or unreadable files
+pub fn page_count(path: &Path) -> usize {
+    let count = match PagedFormat::of(path) {
+        Some(PagedFormat::Tiff) => tiff_page_count(path),
+        Some(PagedFormat::Ico) => fs::read(path)
+            .map_err(LoadError::from)
+            .and_then(|bytes| ico_entries(&bytes).map(|entries| entries.len())),
+        #[cfg(feature = "heif")]
+        Some(PagedFormat::Heif) => heif_page_count(path),
+        None => Ok(1),
+    };
+
+    count.unwrap_or(1).max(1)
+}
+
+/// Decode one page at full resolution, returns (width, height, rgba_pixels)
+pub(crate) fn decode_page(path: &Path, page: usize) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    match PagedFormat::of(path) {
+        Some(PagedFormat::Tiff) => decode_tiff_page(path, page),
+        Some(PagedFormat::Ico) => decode_ico_page(path, page),
+        #[cfg(feature = "heif")]
+        Some(PagedFormat::Heif) => decode_heif_page(path, page),
+        None if page == 0 => decode_rgba(path),
+        None => Err(LoadError::UnsupportedFormat(format!(
+            "{} has a single page",
+            path.display()
+        ))),
+    }
+}
+
+/// Decode a page other than the one the image opened on
+pub async fn load_page(path: PathBuf, page: usize) -> Result<LoadedImage, LoadError> {
+    let task_path = path.clone();
+
+    scheduler()
+        .submit(
+            path,
+            JobKind::Image,
+            Priority::Current,
+            CancelToken::new(),
+            move |_| load_page_sync(&task_path, page),
+        )
+        .await
+}
+
+fn load_page_sync(path: &Path, page: usize) -> Result<LoadedImage, LoadError> {
+    let page_count = page_count(path);
+    let (width, height, pixels) = decode_page(path, page)?;
+
+    Ok(LoadedImage {
+        handle: Handle::from_rgba(width, height, pixels),
+        width,
+        height,
+        path: path.to_path_buf(),
+        page,
+        page_count,
+        partial: false,
+        pyramid: None,
+    })
+}
+
+/// Thumbnails of every page, in page order, for the page strip
+///
+/// Pages that fail to decode are left out rather than failing the whole strip.
+pub async fn load_page_thumbnails(
+    path: PathBuf,
+    max_size: u32,
+) -> Result<Vec<LoadedImage>, LoadError> {
+    let task_path = path.clone();
+
+    scheduler()
+        .submit(
+            path,
+            JobKind::Thumbnail,
+            Priority::Visible,
+            CancelToken::new(),
+            move |token| {
+                let page_count = page_count(&task_path);
+                let mut thumbnails = Vec::with_capacity(page_count);
+
+                for page in 0..page_count {
+                    if token.is_cancelled() {
+                        return Err(LoadError::Cancelled);
+                    }
+
+                    let Ok((width, height, pixels)) = decode_page(&task_path, page) else {
+                        continue;
+                    };
+                    let (width, height, pixels) = if width <= max_size && height <= max_size {
+                        (width, height, pixels)
+                    } else {
+                        fast_resize_rgba(&pixels, width, height, max_size)?
+                    };
+
+                    thumbnails.push(LoadedImage {
+                        handle: Handle::from_rgba(width, height, pixels),
+                        width,
+                        height,
+                        path: task_path.clone(),
+                        page,
+                        page_count,
+                        partial: false,
+                        pyramid: None,
+                    });
+                }
+
+                Ok(thumbnails)
+            },
+        )
+        .await
+}
+
+fn decode_error(
+    format: image::ImageFormat,
+    err: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+) -> LoadError {
+    LoadError::Decode(image::ImageError::Decoding(DecodingError::new(
+        ImageFormatHint::Exact(format),
+        err,
+    )))
+}
+
+fn tiff_decoder(path: &Path) -> Result<tiff::decoder::Decoder<BufReader<File>>, LoadError> {
+    let decoder = tiff::decoder::Decoder::new(BufReader::new(File::open(path)?))
+        .map_err(|e| decode_error(image::ImageFormat::Tiff, e))?;
+
+    // Pages of large scans outgrow the default limits; allow what the loader allows
+    let budget = usize::try_from(MAX_DECODE_BYTES).unwrap_or(usize::MAX);
+    let mut limits = tiff::decoder::Limits::default();
+    limits.decoding_buffer_size = budget;
+    limits.intermediate_buffer_size = budget;
+
+    Ok(decoder.with_limits(limits))
+}
+
+fn tiff_page_count(path: &Path) -> Result<usize, LoadError> {
+    let mut decoder = tiff_decoder(path)?;
+    let mut count = 1;
+
+    while decoder.more_images() {
+        decoder
+            .next_image()
+            .map_err(|e| decode_error(image::ImageFormat::Tiff, e))?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+fn decode_tiff_page(path: &Path, page: usize) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    use tiff::{
+        ColorType,
+        decoder::DecodingResult,
+        tags::{PhotometricInterpretation, Tag},
+    };
+
+    let tiff_error = |e| match e {
+        tiff::TiffError::LimitsExceeded => LoadError::TooLarge,
+        e => decode_error(image::ImageFormat::Tiff, e),
+    };
+
+    let mut decoder = tiff_decoder(path)?;
+    decoder.seek_to_image(page).map_err(tiff_error)?;
+
+    let (width, height) = decoder.dimensions().map_err(tiff_error)?;
+    let color = decoder.colortype().map_err(tiff_error)?;
+    let white_is_zero = decoder
+        .get_tag_u32(Tag::PhotometricInterpretation)
+        .is_ok_and(|value| value == PhotometricInterpretation::WhiteIsZero.to_u16() as u32);
+
+    // Only the high byte of 16 bit samples is kept
+    let samples: Vec<u8> = match decoder.read_image().map_err(tiff_error)? {
+        DecodingResult::U8(samples) => samples,
+        DecodingResult::U16(samples) => samples.iter().map(|&v| (v >> 8) as u8).collect(),
+        _ => {
+            return Err(LoadError::UnsupportedFormat(format!(
+                "TIFF page {} uses floating point or 32 bit samples",
+                page + 1
+            )));
+        }
+    };
+
+    let gray = |value: u8| if white_is_zero { 255 - value } else { value };
+
+    let pixels = match color {
+        ColorType::Gray(1) => {
+            // Bilevel rows are packed eight pixels to a byte
+            let row_len = (width as usize).div_ceil(8);
+            let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+            for row in samples.chunks(row_len).take(height as usize) {
+                for x in 0..width as usize {
+                    let bit = row.get(x / 8).map_or(0, |byte| (byte >> (7 - x % 8)) & 1);
+                    let value = gray(if bit == 1 { 255 } else { 0 });
+                    pixels.extend_from_slice(&[value, value, value, 255]);
+                }
+            }
+            pixels
+        }
+        ColorType::Gray(8 | 16) => samples
+            .iter()
+            .flat_map(|&v| {
+                let v = gray(v);
+                [v, v, v, 255]
+            })
+            .collect(),
+        ColorType::GrayA(8 | 16) => samples
+            .chunks_exact(2)
+            .flat_map(|p| {
+                let v = gray(p[0]);
+                [v, v, v, p[1]]
+            })
+            .collect(),
+        ColorType::RGB(8 | 16) => samples
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        ColorType::RGBA(8 | 16) => samples,
+        ColorType::CMYK(8 | 16) => samples
+            .chunks_exact(4)
+            .flat_map(|p| {
+                let k = 255 - p[3] as u32;
+                let channel = |c: u8| ((255 - c as u32) * k / 255) as u8;
+                [channel(p[0]), channel(p[1]), channel(p[2]), 255]
+            })
+            .collect(),
+        other => {
+            return Err(LoadError::UnsupportedFormat(format!(
+                "TIFF page {} has unsupported color type {:?}",
+                page + 1,
+                other
+            )));
+        }
+    };
+
+    Ok((width, height, pixels))
+}
+
+/// Directory entries of an icon, each with the image data it points to
+fn ico_entries(bytes: &[u8]) -> Result<Vec<(&[u8], &[u8])>, LoadError> {
+    let invalid = || LoadError::UnsupportedFormat("Invalid ICO directory".into());
+
+    let header = bytes.get(..ICO_HEADER_LEN).ok_or_else(invalid)?;
+    if header[..4] != [0, 0, 1, 0] {
+        return Err(invalid());
+    }
+    let count = u16::from_le_bytes([header[4], header[5]]) as usize;
+
+    (0..count)
+        .map(|index| {
+            let start = ICO_HEADER_LEN + index * ICO_ENTRY_LEN;
+            let entry = bytes
+                .get(start..start + ICO_ENTRY_LEN)
+                .ok_or_else(invalid)?;
+            let size = u32::from_le_bytes([entry[8], entry[9], entry[10], entry[11]]) as usize;
+            let offset = u32::from_le_bytes([entry[12], entry[13], entry[14], entry[15]]) as usize;
+            let data = bytes
+                .get(offset..offset.saturating_add(size))
+                .ok_or_else(invalid)?;
+            Ok((entry, data))
+        })
+        .collect()
+}
+
+/// The image crate only decodes the largest entry, so each page is decoded
+/// from a copy of the icon holding just that entry
+fn decode_ico_page(path: &Path, page: usize) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    let bytes = fs::read(path)?;
+    let entries = ico_entries(&bytes)?;
+    let (entry, data) = entries
+        .get(page)
+        .ok_or_else(|| LoadError::UnsupportedFormat(format!("Icon has no page {}", page + 1)))?;
+
+    let offset = (ICO_HEADER_LEN + ICO_ENTRY_LEN) as u32;
+    let mut icon = Vec::with_capacity(ICO_HEADER_LEN + ICO_ENTRY_LEN + data.len());
+    icon.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+    icon.extend_from_slice(&entry[..12]);
+    icon.extend_from_slice(&offset.to_le_bytes());
+    icon.extend_from_slice(data);
+
+    let rgba = image::load_from_memory_with_format(&icon, image::ImageFormat::Ico)?.into_rgba8();
+    let (width, height) = rgba.dimensions();
+
+    Ok((width, height, rgba.into_raw()))
+}
+
+#[cfg(feature = "heif")]
+fn heif_context(path: &Path) -> Result<libheif_rs::HeifContext<'static>, LoadError> {
+    libheif_rs::HeifContext::read_from_file(&path.to_string_lossy()).map_err(|e| {
+        LoadError::Decode(image::ImageError::Decoding(DecodingError::new(
+            ImageFormatHint::Unknown,
+            e,
+        )))
+    })
+}
+
+#[cfg(feature = "heif")]
+fn heif_page_count(path: &Path) -> Result<usize, LoadError> {
+    Ok(heif_context(path)?.number_of_top_level_images())
+}
+
+#[cfg(feature = "heif")]
+fn decode_heif_page(path: &Path, page: usize) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    let heif_error = |e| {
+        LoadError::Decode(image::ImageError::Decoding(DecodingError::new(
+            ImageFormatHint::Unknown,
+            e,
+        )))
+    };
+
+    let ctx = heif_context(path)?;
+    let mut ids = vec![0; ctx.number_of_top_level_images()];
+    ctx.top_level_image_ids(&mut ids);
+
+    let id = ids
+        .get(page)
+        .ok_or_else(|| LoadError::UnsupportedFormat(format!("HEIF has no page {}", page + 1)))?;
+    let handle = ctx.image_handle(*id).map_err(heif_error)?;
+
+    crate::loader::decode_heif_handle(&handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Icon directory with one entry per data blob, each blob stored after the directory
+    fn icon(blobs: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = vec![0, 0, 1, 0, blobs.len() as u8, 0];
+        let mut offset = ICO_HEADER_LEN + blobs.len() * ICO_ENTRY_LEN;
+        for (index, blob) in blobs.iter().enumerate() {
+            let size = 16 * (index as u8 + 1);
+            bytes.extend_from_slice(&[size, size, 0, 0, 1, 0, 32, 0]);
+            bytes.extend_from_slice(&(blob.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&(offset as u32).to_le_bytes());
+            offset += blob.len();
+        }
+        for blob in blobs {
+            bytes.extend_from_slice(blob);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_ico_entries() {
+        let bytes = icon(&[b"small", b"larger"]);
+        let entries = ico_entries(&bytes).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0[0], 16);
+        assert_eq!(entries[0].1, b"small");
+        assert_eq!(entries[1].0[0], 32);
+        assert_eq!(entries[1].1, b"larger");
+    }
+
+    #[test]
+    fn test_ico_entries_rejects_bad_directories() {
+        // Not an icon
+        assert!(ico_entries(&[0, 0, 2, 0, 1, 0]).is_err());
+        // Header only
+        assert!(ico_entries(&[0, 0, 1]).is_err());
+
+        // Directory claims more entries than it holds
+        let mut bytes = icon(&[b"data"]);
+        bytes[4] = 2;
+        assert!(ico_entries(&bytes).is_err());
+
+        // Entry data runs past the end of the file
+        let mut bytes = icon(&[b"data"]);
+        bytes.truncate(bytes.len() - 1);
+        assert!(ico_entries(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_tiff_page_count() {
+        use tiff::encoder::{TiffEncoder, colortype::Gray8};
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("pages.tiff");
+        let mut encoder = TiffEncoder::new(File::create(&path).unwrap()).unwrap();
+        for page in 0..3u8 {
+            encoder.write_image::<Gray8>(2, 2, &[page; 4]).unwrap();
+        }
+        drop(encoder);
+
+        assert_eq!(tiff_page_count(&path).unwrap(), 3);
+        assert_eq!(page_count(&path), 3);
+
+        let (width, height, pixels) = decode_page(&path, 2).unwrap();
+        assert_eq!((width, height), (2, 2));
+        assert_eq!(&pixels[..4], &[2, 2, 2, 255]);
+    }
+}
@@ -0,0 +1,167 @@
+//! Mipmapped tile pyramid for images too large to upload as a single texture.
+//! Pixel data stays on the CPU; only the tiles that are on screen become image handles.
+
+use crate::{
+    cancel::CancelToken,
+    loader::{LoadError, decode_rgba, fast_resize_rgba},
+    scheduler::{JobKind, Priority, scheduler},
+};
+use cosmic::widget::image::Handle;
+use fast_image_resize::{
+    PixelType, ResizeAlg, ResizeOptions, Resizer,
+    images::{Image as FirImage, ImageRef},
+};
+use lru::LruCache;
+use shared::tiling::{self, TileId};
+use std::{
+    fmt::{self, Debug, Formatter},
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::Mutex,
+};
+
+/// Tile handles kept alive so the GPU textures are reused between frames
+const TILE_CACHE_SIZE: usize = 256;
+
+struct PyramidLevel {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+pub struct ImagePyramid {
+    pub width: u32,
+    pub height: u32,
+    levels: Vec<PyramidLevel>,
+    tiles: Mutex<LruCache<TileId, Handle>>,
+}
+
+impl Debug for ImagePyramid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImagePyramid")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("levels", &self.levels.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ImagePyramid {
+    /// Build all levels from full resolution RGBA pixels
+    pub fn build(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self, LoadError> {
+        let count = tiling::level_count(width, height);
+        let mut levels = vec![PyramidLevel {
+            width,
+            height,
+            pixels,
+        }];
+
+        let mut resizer = Resizer::new();
+        let options = ResizeOptions::new().resize_alg(ResizeAlg::Convolution(
+            fast_image_resize::FilterType::Bilinear,
+        ));
+
+        for level in 1..count {
+            let (dst_width, dst_height) = tiling::level_size(width, height, level);
+            let src = levels.last().expect("Level 0 is always present");
+
+            let src_image = ImageRef::new(src.width, src.height, &src.pixels, PixelType::U8x4)
+                .map_err(|e| LoadError::UnsupportedFormat(e.to_string()))?;
+            let mut dst_image = FirImage::new(dst_width, dst_height, PixelType::U8x4);
+
+            resizer
+                .resize(&src_image, &mut dst_image, Some(&options))
+                .map_err(|e| LoadError::UnsupportedFormat(e.to_string()))?;
+
+            levels.push(PyramidLevel {
+                width: dst_width,
+                height: dst_height,
+                pixels: dst_image.into_vec(),
+            });
+        }
+
+        Ok(Self {
+            width,
+            height,
+            levels,
+            tiles: Mutex::new(LruCache::new(NonZeroUsize::new(TILE_CACHE_SIZE).unwrap())),
+        })
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Downscale to fit `max_size`, starting from the smallest level that is still larger
+    pub(crate) fn preview(&self, max_size: u32) -> Result<(u32, u32, Vec<u8>), LoadError> {
+        let level = self
+            .levels
+            .iter()
+            .rev()
+            .find(|level| level.width.max(level.height) >= max_size)
+            .unwrap_or(&self.levels[0]);
+
+        fast_resize_rgba(&level.pixels, level.width, level.height, max_size)
+    }
+
+    /// RGBA value of a pixel at full resolution
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        let level = &self.levels[0];
+        if x >= level.width || y >= level.height {
+            return None;
+        }
+        let idx = (y as usize * level.width as usize + x as usize) * 4;
+        level
+            .pixels
+            .get(idx..idx + 4)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+    }
+
+    /// Handle for a tile, cut out of its level the first time it is requested
+    pub fn tile(&self, id: TileId) -> Option<Handle> {
+        if let Ok(mut tiles) = self.tiles.lock()
+            && let Some(handle) = tiles.get(&id)
+        {
+            return Some(handle.clone());
+        }
+
+        let level = self.levels.get(id.level)?;
+        let (x, y, width, height) = tiling::tile_rect(level.width, level.height, id.col, id.row);
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let stride = level.width as usize * 4;
+        let row_len = width as usize * 4;
+        let mut pixels = Vec::with_capacity(row_len * height as usize);
+        for row in y..y + height {
+            let start = row as usize * stride + x as usize * 4;
+            pixels.extend_from_slice(&level.pixels[start..start + row_len]);
+        }
+
+        let handle = Handle::from_rgba(width, height, pixels);
+        if let Ok(mut tiles) = self.tiles.lock() {
+            tiles.put(id, handle.clone());
+        }
+
+        Some(handle)
+    }
+}
+
+/// Decode an image at full resolution and build its tile pyramid
+pub async fn load_pyramid(path: PathBuf) -> Result<ImagePyramid, LoadError> {
+    let task_path = path.clone();
+
+    scheduler()
+        .submit(
+            path,
+            JobKind::Pyramid,
+            Priority::Current,
+            CancelToken::new(),
+            move |_| {
+                decode_rgba(&task_path)
+                    .and_then(|(width, height, pixels)| ImagePyramid::build(width, height, pixels))
+            },
+        )
+        .await
+}
@@ -0,0 +1,332 @@
+//! Central queue for all decode work.
+//!
+//! Jobs wait in a priority queue and only as many run on rayon as there are worker
+//! threads, so a burst of thumbnail requests can't starve the image the user is
+//! looking at. Queued jobs can be reprioritized or cancelled; cancelled jobs are
+//! dropped without running and their futures resolve to `LoadError::Cancelled`.
+
+use crate::{cancel::CancelToken, loader::LoadError};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex},
+};
+
+static SCHEDULER: LazyLock<DecodeScheduler> = LazyLock::new(DecodeScheduler::new);
+
+/// The shared scheduler used by the loader functions
+pub fn scheduler() -> &'static DecodeScheduler {
+    &SCHEDULER
+}
+
+/// Higher priorities run first; jobs of equal priority run in submission order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Background,
+    Prefetch,
+    Current,
+    Visible,
+}
+
+impl Priority {
+    const COUNT: usize = 4;
+
+    fn bucket(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobKind {
+    Image,
+    Preview,
+    Pyramid,
+    Thumbnail,
+}
+
+struct Job {
+    path: PathBuf,
+    kind: JobKind,
+    priority: Priority,
+    seq: u64,
+    token: CancelToken,
+    run: Box<dyn FnOnce() + Send>,
+}
+
+/// One FIFO per priority, each ordered by `seq`
+#[derive(Default)]
+struct Queue {
+    buckets: [VecDeque<Job>; Priority::COUNT],
+    running: usize,
+    next_seq: u64,
+}
+
+impl Queue {
+    fn push(&mut self, mut job: Job) {
+        job.seq = self.next_seq;
+        self.next_seq += 1;
+        self.buckets[job.priority.bucket()].push_back(job);
+    }
+
+    /// Remove the most urgent job, dropping cancelled ones on the way
+    fn pop(&mut self) -> Option<Job> {
+        for bucket in self.buckets.iter_mut().rev() {
+            while let Some(job) = bucket.pop_front() {
+                if !job.token.is_cancelled() {
+                    return Some(job);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Move jobs of `kind` to the priority returned by `priority`, keeping submission order
+    fn reprioritize(&mut self, kind: JobKind, priority: impl Fn(&Path, Priority) -> Priority) {
+        let mut moved = Vec::new();
+        for bucket in &mut self.buckets {
+            let (leaving, staying) = std::mem::take(bucket).into_iter().partition(|job| {
+                job.kind == kind && priority(&job.path, job.priority) != job.priority
+            });
+            *bucket = staying;
+            moved.extend(leaving);
+        }
+
+        for mut job in moved {
+            job.priority = priority(&job.path, job.priority);
+            let bucket = &mut self.buckets[job.priority.bucket()];
+            let idx = bucket.partition_point(|queued| queued.seq < job.seq);
+            bucket.insert(idx, job);
+        }
+    }
+
+    /// Remove queued jobs of `kind` matching `filter`
+    fn remove_where(&mut self, kind: JobKind, filter: impl Fn(&Path) -> bool) -> Vec<Job> {
+        let mut removed = Vec::new();
+        for bucket in &mut self.buckets {
+            let (matching, kept) = std::mem::take(bucket)
+                .into_iter()
+                .partition(|job| job.kind == kind && filter(&job.path));
+            *bucket = kept;
+            removed.extend(matching);
+        }
+
+        removed
+    }
+
+    fn len(&self, kind: JobKind) -> usize {
+        self.buckets
+            .iter()
+            .flatten()
+            .filter(|job| job.kind == kind)
+            .count()
+    }
+}
+
+#[derive(Clone)]
+pub struct DecodeScheduler {
+    queue: Arc<Mutex<Queue>>,
+    workers: usize,
+}
+
+impl DecodeScheduler {
+    pub fn new() -> Self {
+        Self {
+            queue: Arc::new(Mutex::new(Queue::default())),
+            workers: rayon::current_num_threads().max(1),
+        }
+    }
+
+    /// Queue `work` and return a future resolving to its result
+    pub fn submit<T, F>(
+        &self,
+        path: PathBuf,
+        kind: JobKind,
+        priority: Priority,
+        token: CancelToken,
+        work: F,
+    ) -> impl Future<Output = Result<T, LoadError>> + use<T, F>
+    where
+        T: Send + 'static,
+        F: FnOnce(&CancelToken) -> Result<T, LoadError> + Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        let job_token = token.clone();
+        let run = Box::new(move || {
+            let result = if job_token.is_cancelled() {
+                Err(LoadError::Cancelled)
+            } else {
+                work(&job_token)
+            };
+            let _ = tx.send(result);
+        });
+
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.push(Job {
+                path,
+                kind,
+                priority,
+                seq: 0,
+                token,
+                run,
+            });
+        }
+
+        self.pump();
+
+        // A dropped sender means the job was cancelled while queued
+        async move { rx.await.map_err(|_| LoadError::Cancelled)? }
+    }
+
+    /// Change the priority of queued jobs of `kind`
+    pub fn reprioritize(&self, kind: JobKind, priority: impl Fn(&Path, Priority) -> Priority) {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.reprioritize(kind, priority);
+        }
+    }
+
+    /// Cancel queued jobs of `kind` matching `filter`; returns their paths
+    pub fn cancel_where(&self, kind: JobKind, filter: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+        let Ok(mut queue) = self.queue.lock() else {
+            return Vec::new();
+        };
+
+        queue
+            .remove_where(kind, filter)
+            .into_iter()
+            .map(|job| {
+                job.token.cancel();
+                job.path
+            })
+            .collect()
+    }
+
+    /// Number of jobs of `kind` waiting to run
+    pub fn queued(&self, kind: JobKind) -> usize {
+        self.queue.lock().map(|queue| queue.len(kind)).unwrap_or(0)
+    }
+
+    /// Start queued jobs while workers are free
+    fn pump(&self) {
+        loop {
+            let job = {
+                let Ok(mut queue) = self.queue.lock() else {
+                    return;
+                };
+
+                if queue.running >= self.workers {
+                    return;
+                }
+
+                let Some(job) = queue.pop() else {
+                    return;
+                };
+                queue.running += 1;
+                job
+            };
+
+            let scheduler = self.clone();
+            rayon::spawn(move || {
+                (job.run)();
+
+                if let Ok(mut queue) = scheduler.queue.lock() {
+                    queue.running -= 1;
+                }
+                scheduler.pump();
+            });
+        }
+    }
+}
+
+impl Default for DecodeScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(path: &str, kind: JobKind, priority: Priority, token: &CancelToken) -> Job {
+        Job {
+            path: PathBuf::from(path),
+            kind,
+            priority,
+            seq: 0,
+            token: token.clone(),
+            run: Box::new(|| {}),
+        }
+    }
+
+    fn drain(queue: &mut Queue) -> Vec<PathBuf> {
+        std::iter::from_fn(|| queue.pop())
+            .map(|job| job.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_pop_by_priority_then_submission_order() {
+        let token = CancelToken::new();
+        let mut queue = Queue::default();
+        queue.push(job("a", JobKind::Thumbnail, Priority::Background, &token));
+        queue.push(job("b", JobKind::Image, Priority::Current, &token));
+        queue.push(job("c", JobKind::Thumbnail, Priority::Visible, &token));
+        queue.push(job("d", JobKind::Image, Priority::Current, &token));
+        queue.push(job("e", JobKind::Image, Priority::Prefetch, &token));
+
+        assert_eq!(
+            drain(&mut queue),
+            ["c", "b", "d", "e", "a"].map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn test_pop_skips_cancelled_jobs() {
+        let live = CancelToken::new();
+        let cancelled = CancelToken::new();
+        let mut queue = Queue::default();
+        queue.push(job("a", JobKind::Image, Priority::Current, &cancelled));
+        queue.push(job("b", JobKind::Image, Priority::Prefetch, &live));
+        cancelled.cancel();
+
+        assert_eq!(drain(&mut queue), [PathBuf::from("b")]);
+    }
+
+    #[test]
+    fn test_reprioritize_keeps_submission_order() {
+        let token = CancelToken::new();
+        let mut queue = Queue::default();
+        queue.push(job("a", JobKind::Thumbnail, Priority::Background, &token));
+        queue.push(job("b", JobKind::Thumbnail, Priority::Visible, &token));
+        queue.push(job("c", JobKind::Thumbnail, Priority::Background, &token));
+        queue.push(job("d", JobKind::Image, Priority::Background, &token));
+
+        queue.reprioritize(JobKind::Thumbnail, |path, priority| {
+            if path == Path::new("b") {
+                Priority::Background
+            } else {
+                priority.max(Priority::Visible)
+            }
+        });
+
+        assert_eq!(drain(&mut queue), ["a", "c", "b", "d"].map(PathBuf::from));
+    }
+
+    #[test]
+    fn test_remove_where_only_matches_kind() {
+        let token = CancelToken::new();
+        let mut queue = Queue::default();
+        queue.push(job("a", JobKind::Thumbnail, Priority::Visible, &token));
+        queue.push(job("a", JobKind::Image, Priority::Current, &token));
+        queue.push(job("b", JobKind::Thumbnail, Priority::Background, &token));
+
+        let removed = queue.remove_where(JobKind::Thumbnail, |path| path == Path::new("a"));
+
+        assert_eq!(removed.len(), 1);
+        assert_eq!(queue.len(JobKind::Thumbnail), 1);
+        assert_eq!(queue.len(JobKind::Image), 1);
+    }
+}
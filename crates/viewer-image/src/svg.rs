@@ -0,0 +1,149 @@
+//! Vector images
+//!
+//! SVG and SVGZ files have no fixed resolution. They open at their intrinsic
+//! size and are rasterized again at the zoom level they are shown at, so they
+//! stay sharp instead of scaling up a bitmap. Thumbnails are rendered to fit
+//! the requested size the same way.
+
+use crate::{
+    cancel::CancelToken,
+    loader::{LoadError, LoadedImage},
+    scheduler::{JobKind, Priority, scheduler},
+};
+use cosmic::widget::image::Handle;
+use resvg::{tiny_skia, usvg};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+use viewer_nav::ImageSource;
+
+/// Longest side a vector image is rasterized at, bounds memory at high zoom
+pub const MAX_RENDER_SIZE: u32 = 8192;
+
+/// System fonts for text in SVGs, loaded once on first use
+fn fonts() -> Arc<usvg::fontdb::Database> {
+    static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = usvg::fontdb::Database::new();
+            fonts.load_system_fonts();
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+/// Parse an SVG document; gzip compressed SVGZ data is detected and inflated
+fn parse(bytes: &[u8], path: &Path) -> Result<usvg::Tree, LoadError> {
+    let options = usvg::Options {
+        resources_dir: path.parent().map(Path::to_path_buf),
+        fontdb: fonts(),
+        ..usvg::Options::default()
+    };
+
+    usvg::Tree::from_data(bytes, &options)
+        .map_err(|e| LoadError::UnsupportedFormat(format!("Invalid SVG: {e}")))
+}
+
+/// Intrinsic size of the document, at least one pixel
+fn intrinsic_size(tree: &usvg::Tree) -> (u32, u32) {
+    let size = tree.size();
+    (
+        (size.width().ceil() as u32).max(1),
+        (size.height().ceil() as u32).max(1),
+    )
+}
+
+/// Rasterize `tree` at `scale` times its intrinsic size, returns (width, height, rgba_pixels)
+fn render(tree: &usvg::Tree, scale: f32) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    let (width, height) = intrinsic_size(tree);
+
+    // Keep the longest side within the render limit
+    let longest = width.max(height) as f32;
+    let scale = scale
+        .min(MAX_RENDER_SIZE as f32 / longest)
+        .max(f32::EPSILON);
+    let render_width = ((width as f32 * scale).round() as u32).max(1);
+    let render_height = ((height as f32 * scale).round() as u32).max(1);
+
+    let mut pixmap = tiny_skia::Pixmap::new(render_width, render_height)
+        .ok_or_else(|| LoadError::UnsupportedFormat("SVG render size is invalid".into()))?;
+    resvg::render(
+        tree,
+        tiny_skia::Transform::from_scale(
+            render_width as f32 / tree.size().width(),
+            render_height as f32 / tree.size().height(),
+        ),
+        &mut pixmap.as_mut(),
+    );
+
+    // tiny-skia works with premultiplied alpha
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+
+    Ok((render_width, render_height, pixels))
+}
+
+/// Render at the intrinsic size, returns (width, height, rgba_pixels)
+pub(crate) fn decode_svg(bytes: &[u8], path: &Path) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    render(&parse(bytes, path)?, 1.0)
+}
+
+/// Render so the longest side is `max_size`, scaling small icons up
+pub(crate) fn decode_svg_thumbnail(
+    bytes: &[u8],
+    path: &Path,
+    max_size: u32,
+) -> Result<(u32, u32, Vec<u8>), LoadError> {
+    let tree = parse(bytes, path)?;
+    let (width, height) = intrinsic_size(&tree);
+    render(&tree, max_size as f32 / width.max(height) as f32)
+}
+
+/// Rasterize a vector image at `scale` times its intrinsic size
+///
+/// The returned image keeps the intrinsic `width` and `height`; only the
+/// handle has more pixels, so it can replace the cached image in layout.
+pub async fn render_vector(
+    path: PathBuf,
+    scale: f32,
+    token: CancelToken,
+) -> Result<LoadedImage, LoadError> {
+    let task_path = path.clone();
+
+    scheduler()
+        .submit(
+            path,
+            JobKind::Image,
+            Priority::Current,
+            token,
+            move |token| {
+                let tree = parse(&ImageSource::locate(&task_path).read()?, &task_path)?;
+                if token.is_cancelled() {
+                    return Err(LoadError::Cancelled);
+                }
+
+                let (width, height) = intrinsic_size(&tree);
+                let (render_width, render_height, pixels) = render(&tree, scale)?;
+
+                Ok(LoadedImage {
+                    handle: Handle::from_rgba(render_width, render_height, pixels),
+                    width,
+                    height,
+                    path: task_path,
+                    page: 0,
+                    page_count: 1,
+                    partial: false,
+                    pyramid: None,
+                })
+            },
+        )
+        .await
+}
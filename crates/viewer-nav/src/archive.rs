@@ -0,0 +1,422 @@
+//! Archives opened as virtual folders
+//!
+//! Members are addressed by virtual paths that continue past the archive file,
+//! e.g. `/comics/issue-1.cbz/pages/001.jpg`. They work unchanged as cache keys
+//! and with file name based code; only reading them has to go through
+//! [`ImageSource`].
+//!
+//! Each archive is listed once into an index of where its members are stored,
+//! so reading a page doesn't walk the archive again. Names that are absolute or
+//! climb out with `..` are left out, their virtual paths would point outside
+//! the archive.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
+    path::{Component, Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex},
+    time::SystemTime,
+};
+
+use viewer_config::SortOrder;
+
+use crate::{human_sort, is_hidden, is_supported_image};
+
+/// Archives whose index is kept, e.g. the comic being read and its neighbours
+const MAX_INDEXES: usize = 8;
+
+/// Largest member read into memory. The size a zip entry declares can be a
+/// lie, so inflating stops here too.
+const MAX_MEMBER_SIZE: u64 = 512 << 20;
+
+/// Most recently used last
+static INDEXES: LazyLock<Mutex<Vec<(PathBuf, Arc<ArchiveIndex>)>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+}
+
+impl ArchiveKind {
+    fn of(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "zip" | "cbz" => Some(Self::Zip),
+            "tar" | "cbt" => Some(Self::Tar),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the path has an archive extension; doesn't touch the filesystem
+pub fn is_archive(path: &Path) -> bool {
+    ArchiveKind::of(path).is_some()
+}
+
+/// Whether the path is a comic book archive, which opens in reader mode
+pub fn is_comic_book(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cbz") || ext.eq_ignore_ascii_case("cbt"))
+}
+
+/// Where the bytes of an image in the navigation list come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageSource {
+    File(PathBuf),
+    ArchiveMember { archive: PathBuf, member: String },
+}
+
+impl ImageSource {
+    /// Split a virtual path at the archive it points into
+    pub fn locate(path: &Path) -> Self {
+        for archive in path.ancestors().skip(1) {
+            if is_archive(archive)
+                && archive.is_file()
+                && let Ok(member) = path.strip_prefix(archive)
+            {
+                return Self::ArchiveMember {
+                    archive: archive.to_path_buf(),
+                    member: member.to_string_lossy().into_owned(),
+                };
+            }
+        }
+
+        Self::File(path.to_path_buf())
+    }
+
+    pub fn is_archive_member(&self) -> bool {
+        matches!(self, Self::ArchiveMember { .. })
+    }
+
+    /// The file on disk, the archive itself for members
+    pub fn file(&self) -> &Path {
+        match self {
+            Self::File(path) => path,
+            Self::ArchiveMember { archive, .. } => archive,
+        }
+    }
+
+    /// Read the whole image into memory
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        match self {
+            Self::File(path) => fs::read(path),
+            Self::ArchiveMember { archive, member } => read_member(archive, member),
+        }
+    }
+}
+
+/// Whether `path` addresses a member inside an archive
+pub fn is_archive_member(path: &Path) -> bool {
+    ImageSource::locate(path).is_archive_member()
+}
+
+/// Virtual paths of the supported images in an archive, in natural name order
+pub(crate) fn scan_archive(
+    archive: &Path,
+    include_hidden: bool,
+    sort_order: SortOrder,
+) -> Vec<PathBuf> {
+    let Ok(index) = archive_index(archive) else {
+        return Vec::new();
+    };
+
+    let mut members: Vec<&String> = index
+        .members
+        .keys()
+        .filter(|member| {
+            let member = Path::new(member);
+            // Hidden entries include the resource forks macOS adds to zips
+            (include_hidden
+                || !member
+                    .components()
+                    .any(|part| is_hidden(Path::new(part.as_os_str()))))
+                && is_supported_image(member)
+        })
+        .collect();
+
+    members.sort_by(|a, b| human_sort(a, b));
+    if sort_order == SortOrder::Descending {
+        members.reverse();
+    }
+
+    members
+        .into_iter()
+        .map(|member| archive.join(member))
+        .collect()
+}
+
+/// Where a member's bytes are stored
+#[derive(Debug, Clone, Copy)]
+enum MemberData {
+    /// Entry in the zip central directory
+    Zip(usize),
+    /// Byte range of the entry's data in the tar file
+    Tar { offset: u64, len: u64 },
+}
+
+/// The regular files of an archive, listed once
+struct ArchiveIndex {
+    /// Modification time of the archive when it was listed
+    modified: Option<SystemTime>,
+    members: HashMap<String, MemberData>,
+    /// Parsed central directory of a zip, reused by every read
+    zip: Option<Mutex<zip::ZipArchive<File>>>,
+}
+
+impl ArchiveIndex {
+    fn build(archive: &Path, modified: Option<SystemTime>) -> io::Result<Self> {
+        let file = File::open(archive)?;
+        let mut members = HashMap::new();
+
+        match ArchiveKind::of(archive) {
+            Some(ArchiveKind::Zip) => {
+                let mut zip = zip::ZipArchive::new(file).map_err(io::Error::other)?;
+                for idx in 0..zip.len() {
+                    let entry = zip.by_index_raw(idx).map_err(io::Error::other)?;
+                    if entry.is_file()
+                        && let Some(name) = entry.enclosed_name().as_deref().and_then(member_name)
+                    {
+                        members.insert(name, MemberData::Zip(idx));
+                    }
+                }
+
+                Ok(Self {
+                    modified,
+                    members,
+                    zip: Some(Mutex::new(zip)),
+                })
+            }
+            Some(ArchiveKind::Tar) => {
+                let mut tar = tar::Archive::new(file);
+                for entry in tar.entries()? {
+                    let entry = entry?;
+                    if entry.header().entry_type().is_file()
+                        && let Some(name) = member_name(&entry.path()?)
+                    {
+                        let data = MemberData::Tar {
+                            offset: entry.raw_file_position(),
+                            len: entry.size(),
+                        };
+                        members.insert(name, data);
+                    }
+                }
+
+                Ok(Self {
+                    modified,
+                    members,
+                    zip: None,
+                })
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "not a supported archive",
+            )),
+        }
+    }
+
+    fn read(&self, archive: &Path, member: &str) -> io::Result<Vec<u8>> {
+        let data = self
+            .members
+            .get(member)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+        let mut bytes = Vec::new();
+
+        match (*data, &self.zip) {
+            (MemberData::Zip(idx), Some(zip)) => {
+                let mut zip = zip
+                    .lock()
+                    .map_err(|_| io::Error::other("archive lock poisoned"))?;
+                let entry = zip.by_index(idx).map_err(io::Error::other)?;
+                if entry.size() > MAX_MEMBER_SIZE {
+                    return Err(member_too_large());
+                }
+                entry.take(MAX_MEMBER_SIZE + 1).read_to_end(&mut bytes)?;
+                if bytes.len() as u64 > MAX_MEMBER_SIZE {
+                    return Err(member_too_large());
+                }
+            }
+            (MemberData::Tar { offset, len }, _) => {
+                if len > MAX_MEMBER_SIZE {
+                    return Err(member_too_large());
+                }
+                let mut file = File::open(archive)?;
+                file.seek(SeekFrom::Start(offset))?;
+                file.take(len).read_to_end(&mut bytes)?;
+                if (bytes.len() as u64) < len {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+            }
+            (MemberData::Zip(_), None) => return Err(io::ErrorKind::InvalidData.into()),
+        }
+
+        Ok(bytes)
+    }
+}
+
+fn member_too_large() -> io::Error {
+    io::Error::new(io::ErrorKind::FileTooLarge, "archive member is too large")
+}
+
+/// Member name of a path stored in an archive; `None` if it would leave the archive
+fn member_name(path: &Path) -> Option<String> {
+    let mut name = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => name.push(part),
+            Component::CurDir => {}
+            Component::RootDir | Component::Prefix(_) | Component::ParentDir => return None,
+        }
+    }
+
+    (!name.as_os_str().is_empty()).then(|| name.to_string_lossy().into_owned())
+}
+
+/// Index of an archive, listed again once the file changed
+fn archive_index(archive: &Path) -> io::Result<Arc<ArchiveIndex>> {
+    let modified = fs::metadata(archive)?.modified().ok();
+    let mut indexes = INDEXES
+        .lock()
+        .map_err(|_| io::Error::other("archive index lock poisoned"))?;
+
+    if let Some(pos) = indexes.iter().position(|(path, _)| path == archive) {
+        let entry = indexes.remove(pos);
+        if entry.1.modified == modified {
+            let index = entry.1.clone();
+            indexes.push(entry);
+            return Ok(index);
+        }
+    }
+
+    let index = Arc::new(ArchiveIndex::build(archive, modified)?);
+    if indexes.len() >= MAX_INDEXES {
+        indexes.remove(0);
+    }
+    indexes.push((archive.to_path_buf(), index.clone()));
+
+    Ok(index)
+}
+
+fn read_member(archive: &Path, member: &str) -> io::Result<Vec<u8>> {
+    archive_index(archive)?.read(archive, member)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn write_zip(path: &Path, members: &[(&str, &[u8])]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        for (name, data) in members {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    /// Names are written raw, `tar::Builder` itself refuses `..`
+    fn write_tar(path: &Path, members: &[(&str, &[u8])]) {
+        let mut tar = tar::Builder::new(File::create(path).unwrap());
+        for (name, data) in members {
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append(&header, *data).unwrap();
+        }
+        tar.finish().unwrap();
+    }
+
+    fn member_names(archive: &Path) -> Vec<String> {
+        scan_archive(archive, false, SortOrder::Ascending)
+            .iter()
+            .map(|path| {
+                path.strip_prefix(archive)
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_lists_images_in_natural_order() {
+        let dir = TempDir::new().unwrap();
+        let archive = dir.path().join("comic.cbz");
+        write_zip(
+            &archive,
+            &[
+                ("pages/10.jpg", b"ten"),
+                ("pages/2.jpg", b"two"),
+                ("notes.txt", b"text"),
+                ("__MACOSX/.pages/2.jpg", b"fork"),
+            ],
+        );
+
+        assert_eq!(member_names(&archive), ["pages/2.jpg", "pages/10.jpg"]);
+    }
+
+    #[test]
+    fn test_read_zip_and_tar_members() {
+        let dir = TempDir::new().unwrap();
+        let members: &[(&str, &[u8])] = &[("a.png", b"first"), ("b/c.png", b"second")];
+        let zip = dir.path().join("pages.zip");
+        let tar = dir.path().join("pages.tar");
+        write_zip(&zip, members);
+        write_tar(&tar, members);
+
+        for archive in [&zip, &tar] {
+            for (name, data) in members {
+                let source = ImageSource::locate(&archive.join(name));
+                assert!(source.is_archive_member());
+                assert_eq!(source.read().unwrap(), *data);
+            }
+            assert!(read_member(archive, "missing.png").is_err());
+        }
+    }
+
+    #[test]
+    fn test_names_leaving_the_archive_are_skipped() {
+        let dir = TempDir::new().unwrap();
+        let members: &[(&str, &[u8])] = &[
+            ("../outside.jpg", b"parent"),
+            ("/etc/absolute.jpg", b"absolute"),
+            ("pages/../../up.jpg", b"climbs"),
+            ("./kept.jpg", b"kept"),
+        ];
+        let zip = dir.path().join("evil.zip");
+        let tar = dir.path().join("evil.tar");
+        write_zip(&zip, members);
+        write_tar(&tar, members);
+
+        for archive in [&zip, &tar] {
+            assert_eq!(member_names(archive), ["kept.jpg"]);
+            assert_eq!(
+                ImageSource::locate(&archive.join("kept.jpg"))
+                    .read()
+                    .unwrap(),
+                b"kept"
+            );
+        }
+    }
+
+    #[test]
+    fn test_member_name() {
+        assert_eq!(
+            member_name(Path::new("a/./b.jpg")).as_deref(),
+            Some("a/b.jpg")
+        );
+        assert_eq!(member_name(Path::new("a/../b.jpg")), None);
+        assert_eq!(member_name(Path::new("/b.jpg")), None);
+        assert_eq!(member_name(Path::new(".")), None);
+    }
+}
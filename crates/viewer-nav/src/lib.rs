@@ -1,19 +1,24 @@
+pub mod archive;
+
+pub use archive::{ImageSource, is_archive, is_archive_member, is_comic_book};
+
+use shared::{
+    formats::{ImageFormat, SNIFF_LEN},
+    places::{self, FolderEntry},
+};
 use std::{
-    fs,
+    fs::{self, File},
+    io::Read,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 use viewer_config::{SortMode, SortOrder};
 
 use tokio::task::spawn_blocking;
 
-pub const EXTENSIONS: &[&str] = &[
-    "png", "jpg", "jpeg", "gif", "webp", "bmp", "tiff", "tif", "ico", "avif", "raw", "cr2", "cr3",
-    "nef", "arw", "dng", "orf", "rw2",
-];
-
 #[derive(Debug, Clone, Default)]
 pub struct NavState {
-    images: Vec<PathBuf>,
+    images: Arc<[PathBuf]>,
     cur_idx: Option<usize>,
 }
 
@@ -42,12 +47,26 @@ impl NavState {
         self.images.is_empty()
     }
 
-    pub fn images(&self) -> Vec<PathBuf> {
-        self.images.clone()
+    pub fn images(&self) -> &[PathBuf] {
+        &self.images
+    }
+
+    /// The image list as a cheap shared handle, for views and tasks that outlive the borrow
+    pub fn shared_images(&self) -> Arc<[PathBuf]> {
+        Arc::clone(&self.images)
+    }
+
+    /// Where the current image is read from
+    pub fn current_source(&self) -> Option<ImageSource> {
+        self.current().map(|path| ImageSource::locate(path))
+    }
+
+    pub fn source(&self, idx: usize) -> Option<ImageSource> {
+        self.images.get(idx).map(|path| ImageSource::locate(path))
     }
 
     pub fn set_images(&mut self, images: Vec<PathBuf>, select: Option<&Path>) {
-        self.images = images;
+        self.images = images.into();
         // Only set selection if explicitly requested and path exists
         self.cur_idx = select.and_then(|path| self.images.iter().position(|pos| pos == path));
     }
@@ -119,48 +138,65 @@ impl NavState {
     }
 }
 
+/// Folder to list for `path`; archives are folders of their own
 pub fn get_image_dir(path: &Path) -> Option<PathBuf> {
-    if path.is_file() {
+    if is_archive(path) && path.is_file() {
+        Some(path.to_path_buf())
+    } else if path.is_file() {
         path.parent().map(|par| par.to_path_buf())
     } else if path.is_dir() {
         Some(path.to_path_buf())
+    } else if let ImageSource::ArchiveMember { archive, .. } = ImageSource::locate(path) {
+        Some(archive)
     } else {
         None
     }
 }
 
+/// Images in `dir` sorted for display. Files without an extension are only
+/// listed with `include_extensionless`, which reads the start of each one.
 pub async fn scan_dir(
     dir: &Path,
     include_hidden: bool,
+    include_extensionless: bool,
     sort_mode: SortMode,
     sort_order: SortOrder,
 ) -> Vec<PathBuf> {
     let dir = dir.to_path_buf();
 
-    spawn_blocking(move || scan_dir_sync(&dir, include_hidden, sort_mode, sort_order))
-        .await
-        .unwrap_or_default()
+    spawn_blocking(move || {
+        scan_dir_sync(
+            &dir,
+            include_hidden,
+            include_extensionless,
+            sort_mode,
+            sort_order,
+        )
+    })
+    .await
+    .unwrap_or_default()
 }
 
 fn scan_dir_sync(
     dir: &Path,
     include_hidden: bool,
+    include_extensionless: bool,
     sort_mode: SortMode,
     sort_order: SortOrder,
 ) -> Vec<PathBuf> {
+    if is_archive(dir) && dir.is_file() {
+        return archive::scan_archive(dir, include_hidden, sort_order);
+    }
+
     let mut images: Vec<PathBuf> = fs::read_dir(dir)
         .into_iter()
         .flatten()
         .filter_map(|entry| entry.ok())
         .map(|entry| entry.path())
         .filter(|path| {
-            if !include_hidden
-                && let Some(name) = path.file_name().and_then(|name| name.to_str())
-                && name.starts_with('.')
-            {
-                return false;
-            }
-            is_supported_image(path)
+            (include_hidden || !is_hidden(path))
+                && (is_supported_image(path)
+                    || include_extensionless && is_extensionless_image(path))
         })
         .collect();
 
@@ -197,10 +233,105 @@ fn scan_dir_sync(
 }
 
 pub fn is_supported_image(path: &Path) -> bool {
-    path.extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| EXTENSIONS.contains(&ext.to_lowercase().as_str()))
-        .unwrap_or(false)
+    ImageFormat::from_path(path).is_some()
+}
+
+/// Whether `path` has no extension and starts with the signature of a supported format
+pub fn is_extensionless_image(path: &Path) -> bool {
+    if path.extension().is_some() || !path.is_file() {
+        return false;
+    }
+
+    let mut header = [0; SNIFF_LEN];
+    File::open(path)
+        .and_then(|mut file| file.read(&mut header))
+        .is_ok_and(|len| ImageFormat::sniff(&header[..len]).is_some())
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+/// Subfolders of `dir` for the sidebar tree, in name order
+pub async fn list_folders(dir: &Path, include_hidden: bool) -> Vec<FolderEntry> {
+    let dir = dir.to_path_buf();
+
+    spawn_blocking(move || {
+        let mut folders: Vec<FolderEntry> = fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir() && (include_hidden || !is_hidden(path)))
+            .map(|path| {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                folder_entry(path, name, include_hidden)
+            })
+            .collect();
+
+        folders.sort_by(|a, b| human_sort(&a.name, &b.name));
+        folders
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Entries for folders shown under their own labels, e.g. places
+pub async fn folder_entries(
+    folders: Vec<(PathBuf, String)>,
+    include_hidden: bool,
+) -> Vec<FolderEntry> {
+    spawn_blocking(move || {
+        folders
+            .into_iter()
+            .map(|(path, name)| folder_entry(path, name, include_hidden))
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Count a folder's images and check for subfolders in a single listing
+fn folder_entry(path: PathBuf, name: String, include_hidden: bool) -> FolderEntry {
+    let mut image_count = 0;
+    let mut has_children = false;
+
+    for child in fs::read_dir(&path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|child| include_hidden || !is_hidden(child))
+    {
+        if child.is_dir() {
+            has_children = true;
+        } else if is_supported_image(&child) {
+            image_count += 1;
+        }
+    }
+
+    FolderEntry {
+        path,
+        name,
+        image_count,
+        has_children,
+    }
+}
+
+/// Mounted removable drives and other user volumes
+pub async fn mounted_volumes() -> Vec<PathBuf> {
+    spawn_blocking(|| {
+        fs::read_to_string("/proc/self/mounts")
+            .map(|mounts| places::parse_mounts(&mounts))
+            .unwrap_or_default()
+    })
+    .await
+    .unwrap_or_default()
 }
 
 fn human_sort(a: &str, b: &str) -> std::cmp::Ordering {
@@ -1,6 +1,7 @@
 //! Shared grid layout utilities
 
 use cosmic::iced::{Padding, Rectangle};
+use std::ops::Range;
 
 /// Configuration for grid layout calculation
 #[derive(Debug, Clone)]
@@ -61,6 +62,17 @@ pub fn calculate_scroll_offset(
     let item_top = padding_top + (row as f32 * (row_height + row_spacing));
     let item_bottom = item_top + row_height;
 
+    scroll_offset_for_span(item_top, item_bottom, viewport_top, viewport_height)
+}
+
+/// Scroll offset needed to bring the vertical span of an item into view.
+/// Works for any layout, including rows of varying height.
+pub fn scroll_offset_for_span(
+    item_top: f32,
+    item_bottom: f32,
+    viewport_top: f32,
+    viewport_height: f32,
+) -> Option<f32> {
     let viewport_bottom = viewport_top + viewport_height;
 
     // Check if item is already fully visible
@@ -74,10 +86,34 @@ pub fn calculate_scroll_offset(
         Some(item_top)
     } else {
         // Item is below viewport - scroll down
-        Some(item_bottom - viewport_height)
+        Some((item_bottom - viewport_height).max(0.0))
     }
 }
 
+/// Range of item indices in rows that intersect the viewport
+pub fn visible_range(
+    viewport_top: f32,
+    viewport_height: f32,
+    cols: usize,
+    row_height: f32,
+    row_spacing: f32,
+    padding_top: f32,
+    item_count: usize,
+) -> Range<usize> {
+    if cols == 0 || row_height <= 0.0 || item_count == 0 {
+        return 0..0;
+    }
+
+    let cell_height = row_height + row_spacing;
+    let top = (viewport_top - padding_top).max(0.0);
+    let bottom = (viewport_top + viewport_height - padding_top).max(0.0);
+
+    let first_row = (top / cell_height).floor() as usize;
+    let last_row = (bottom / cell_height).ceil() as usize;
+
+    (first_row * cols).min(item_count)..(last_row * cols).min(item_count)
+}
+
 /// Calculate the index of an item at a given position
 #[allow(clippy::too_many_arguments)]
 pub fn item_at_position(
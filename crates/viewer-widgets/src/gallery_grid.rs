@@ -6,31 +6,60 @@
 //! - Mouse hover updates focus
 //! - Keyboard navigation (arrows)
 //! - Auto-scroll on focus change
-
-use std::cell::Cell;
+//! - Uniform square grid or justified rows that keep each thumbnail's aspect ratio
+//! - Ctrl+wheel requests a thumbnail size change
+//! - Optional sections under collapsible header widgets, e.g. timeline dates
+//! - Virtualized: only rows inside the scroll viewport (plus an overscan) are
+//!   materialized, so folders with tens of thousands of images stay responsive
+
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
 use std::path::PathBuf;
 
 use cosmic::{
     Element, Renderer,
     iced::{
-        Color, Length, Padding, Point, Rectangle, Size,
+        Color, Length, Padding, Point, Rectangle, Size, Vector,
         advanced::{
             Clipboard, Layout, Shell, Widget,
             image::Renderer as ImageRenderer,
             layout::{Limits, Node},
             overlay,
             renderer::{self as iced_renderer, Quad, Renderer as QuadRenderer},
-            widget::{Id, Operation, Tree},
+            widget::{Id, Operation, Tree, tree},
         },
         event::{Event, Status},
         keyboard::{self, Key},
         mouse::{self, Button, Cursor},
     },
+    iced_widget::scrollable::Viewport,
     widget::{container, image::Handle, scrollable},
 };
 
+use shared::justified::{JustifiedCell, JustifiedLayout, JustifiedRow};
+
 use crate::flex_grid_core as core;
 
+/// Rows drawn above and below the viewport so scrolling doesn't reveal empty cells
+const OVERSCAN_ROWS: usize = 2;
+
+/// Pixel scroll distance that counts as one wheel notch when zooming
+const PIXELS_PER_ZOOM_STEP: f32 = 50.0;
+
+/// Height reserved for each section header
+const HEADER_HEIGHT: f32 = 40.0;
+
+/// How an item's image decoded, shown on its tile
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ItemStatus {
+    #[default]
+    Normal,
+    /// Only part of a truncated or corrupt file could be decoded, gets a warning badge
+    Damaged,
+    /// Nothing could be decoded, a broken image placeholder replaces the thumbnail
+    Failed,
+}
+
 /// An item in the gallery grid
 #[derive(Debug, Clone)]
 pub struct GalleryItem {
@@ -38,6 +67,7 @@ pub struct GalleryItem {
     pub handle: Option<Handle>,
     pub width: u32,
     pub height: u32,
+    pub status: ItemStatus,
 }
 
 impl GalleryItem {
@@ -47,8 +77,25 @@ impl GalleryItem {
             handle,
             width,
             height,
+            status: ItemStatus::Normal,
         }
     }
+
+    pub fn status(mut self, status: ItemStatus) -> Self {
+        self.status = status;
+        self
+    }
+}
+
+/// A run of consecutive items shown below a header
+///
+/// The items of a collapsed section are hidden; its header stands in for the
+/// first item, so it can still be focused and toggled from the keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridSection {
+    pub start: usize,
+    pub len: usize,
+    pub collapsed: bool,
 }
 
 /// Scroll request for auto-scrolling
@@ -61,14 +108,25 @@ pub struct ScrollRequest {
 pub struct GalleryGrid<'a, M> {
     inner: GalleryGridInner<'a, M>,
     scrollable_id: Option<Id>,
+    on_scroll: Option<Box<dyn Fn(Viewport) -> M + 'a>>,
     keyboard_nav_enabled: bool,
 }
 
 impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
     pub fn new(items: Vec<GalleryItem>) -> Self {
+        Self::from_fn(items.len(), move |index| items[index].clone())
+    }
+
+    /// Grid of `count` items built on demand, only for the rows that are drawn
+    pub fn from_fn<F>(count: usize, item: F) -> Self
+    where
+        F: Fn(usize) -> GalleryItem + 'a,
+    {
         Self {
             inner: GalleryGridInner {
-                items,
+                item_count: count,
+                item: Box::new(item),
+                aspect: None,
                 thumbnail_size: 128,
                 focused_index: None,
                 selected_indices: Vec::new(),
@@ -79,13 +137,23 @@ impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
                 height: Length::Fill,
                 on_focus: None,
                 on_activate: None,
+                on_toggle_select: None,
                 on_scroll_request: None,
+                on_zoom: None,
                 last_layout: Cell::new((0, 0)),
                 cached_cols: Cell::new(0),
                 cached_row_height: Cell::new(0.0),
+                justified: false,
+                rows: RefCell::new(JustifiedLayout::default()),
+                sections: Vec::new(),
+                headers: Vec::new(),
+                header_offsets: RefCell::new(Vec::new()),
+                on_toggle_section: None,
+                reveal: None,
                 keyboard_nav_enabled: true,
             },
             scrollable_id: None,
+            on_scroll: None,
             keyboard_nav_enabled: true,
         }
     }
@@ -102,6 +170,35 @@ impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
         self
     }
 
+    /// Pack thumbnails into justified rows at `thumbnail_size` height instead of square cells.
+    /// Every item is queried for its dimensions during layout, unless `aspect` is set.
+    pub fn justified(mut self, justified: bool) -> Self {
+        self.inner.justified = justified;
+        self
+    }
+
+    /// Width to height ratio of an item, so justified layout doesn't build every item
+    pub fn aspect<F>(mut self, aspect: F) -> Self
+    where
+        F: Fn(usize) -> f32 + 'a,
+    {
+        self.inner.aspect = Some(Box::new(aspect));
+        self
+    }
+
+    /// Add a section with its header; sections must be in order and must not overlap
+    pub fn section(mut self, section: GridSection, header: impl Into<Element<'a, M>>) -> Self {
+        self.inner.sections.push(section);
+        self.inner.headers.push(header.into());
+        self
+    }
+
+    /// Scroll an item into view once; a section's first item scrolls to its header
+    pub fn reveal(mut self, index: Option<usize>) -> Self {
+        self.inner.reveal = index;
+        self
+    }
+
     pub fn focused(mut self, index: Option<usize>) -> Self {
         self.inner.focused_index = index;
         self
@@ -166,6 +263,42 @@ impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
         self
     }
 
+    /// Callback when an item is Ctrl+clicked to add or remove it from the selection
+    pub fn on_toggle_select<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> M + 'a,
+    {
+        self.inner.on_toggle_select = Some(Box::new(f));
+        self
+    }
+
+    /// Callback when Space (or Enter on a collapsed header) toggles a section, by section index
+    pub fn on_toggle_section<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> M + 'a,
+    {
+        self.inner.on_toggle_section = Some(Box::new(f));
+        self
+    }
+
+    /// Callback on Ctrl+wheel with the number of notches, positive to enlarge thumbnails
+    pub fn on_zoom<F>(mut self, f: F) -> Self
+    where
+        F: Fn(f32) -> M + 'a,
+    {
+        self.inner.on_zoom = Some(Box::new(f));
+        self
+    }
+
+    /// Callback when the built-in scrollable moves (requires `scrollable`)
+    pub fn on_scroll<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Viewport) -> M + 'a,
+    {
+        self.on_scroll = Some(Box::new(f));
+        self
+    }
+
     /// Callback when scroll is needed (for external scrollable container)
     pub fn on_scroll_request<F>(mut self, f: F) -> Self
     where
@@ -177,7 +310,8 @@ impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
 
     pub fn into_element(self) -> Element<'a, M> {
         if let Some(scroll_id) = self.scrollable_id {
-            scrollable(
+            let on_scroll = self.on_scroll;
+            let scroll = scrollable(
                 container({
                     let mut inner = self.inner;
                     inner.height = Length::Shrink;
@@ -187,8 +321,12 @@ impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
             )
             .id(scroll_id)
             .width(Length::Fill)
-            .height(Length::Fill)
-            .into()
+            .height(Length::Fill);
+
+            match on_scroll {
+                Some(on_scroll) => scroll.on_scroll(on_scroll).into(),
+                None => scroll.into(),
+            }
         } else {
             self.inner.into()
         }
@@ -200,9 +338,80 @@ pub fn gallery_grid<M: Clone + 'static>(items: Vec<GalleryItem>) -> GalleryGrid<
     GalleryGrid::new(items)
 }
 
+/// Constructor function for a grid whose items are produced lazily by index
+pub fn gallery_grid_from_fn<'a, M: Clone + 'static>(
+    count: usize,
+    item: impl Fn(usize) -> GalleryItem + 'a,
+) -> GalleryGrid<'a, M> {
+    GalleryGrid::from_fn(count, item)
+}
+
+/// Justified rows and sections of a grid, as set with `justified`, `aspect` and `section`
+#[derive(Clone, Copy, Default)]
+pub struct GridArrangement<'a> {
+    /// Width to height ratio of each item when rows are justified
+    pub aspect: Option<&'a dyn Fn(usize) -> f32>,
+    pub sections: &'a [GridSection],
+}
+
+/// Indices of the items a scrolled grid shows, using the same metrics as its layout
+pub fn visible_items(
+    viewport: &Viewport,
+    thumbnail_size: u32,
+    spacing: u16,
+    padding: Padding,
+    item_count: usize,
+    arrangement: GridArrangement<'_>,
+) -> Range<usize> {
+    let bounds = viewport.bounds();
+    let cell_size = thumbnail_size as f32 + (spacing as f32 * 2.0);
+
+    if arrangement.aspect.is_some() || !arrangement.sections.is_empty() {
+        let (rows, _) = arrange_rows(
+            item_count,
+            arrangement.sections,
+            arrangement.aspect,
+            bounds.width - padding.horizontal(),
+            cell_size,
+            spacing as f32,
+            spacing as f32,
+        );
+        return rows.visible_range(viewport.absolute_offset().y - padding.top, bounds.height, 0);
+    }
+
+    let cols = core::calculate_columns(
+        bounds.width - padding.horizontal(),
+        cell_size,
+        spacing as f32,
+        1,
+        None,
+        item_count,
+    );
+
+    core::visible_range(
+        viewport.absolute_offset().y,
+        bounds.height,
+        cols,
+        cell_size,
+        spacing as f32,
+        padding.top,
+        item_count,
+    )
+}
+
+/// Widget state that has to survive rebuilds
+#[derive(Debug, Default)]
+struct GridState {
+    modifiers: keyboard::Modifiers,
+    /// Last item scrolled to by `reveal`, so it only happens once
+    revealed: Option<usize>,
+}
+
 /// Inner widget that handles the actual rendering and events
 struct GalleryGridInner<'a, M> {
-    items: Vec<GalleryItem>,
+    item_count: usize,
+    item: Box<dyn Fn(usize) -> GalleryItem + 'a>,
+    aspect: Option<Box<dyn Fn(usize) -> f32 + 'a>>,
     thumbnail_size: u32,
     focused_index: Option<usize>,
     selected_indices: Vec<usize>,
@@ -213,15 +422,95 @@ struct GalleryGridInner<'a, M> {
     height: Length,
     on_focus: Option<Box<dyn Fn(usize) -> M + 'a>>,
     on_activate: Option<Box<dyn Fn(usize) -> M + 'a>>,
+    on_toggle_select: Option<Box<dyn Fn(usize) -> M + 'a>>,
     on_scroll_request: Option<Box<dyn Fn(ScrollRequest) -> M + 'a>>,
+    on_zoom: Option<Box<dyn Fn(f32) -> M + 'a>>,
     last_layout: Cell<(usize, u32)>,
     cached_cols: Cell<usize>,
     cached_row_height: Cell<f32>,
+    justified: bool,
+    /// Item rows for justified or sectioned layouts
+    rows: RefCell<JustifiedLayout>,
+    sections: Vec<GridSection>,
+    headers: Vec<Element<'a, M>>,
+    /// Top of each section header relative to the content area
+    header_offsets: RefCell<Vec<f32>>,
+    on_toggle_section: Option<Box<dyn Fn(usize) -> M + 'a>>,
+    reveal: Option<usize>,
     keyboard_nav_enabled: bool,
 }
 
 impl<'a, M> GalleryGridInner<'a, M> {
+    /// Whether items are placed from `rows` rather than the uniform grid math
+    fn uses_rows(&self) -> bool {
+        self.justified || !self.sections.is_empty()
+    }
+
+    /// Index of the section containing `index`
+    fn section_of(&self, index: usize) -> Option<usize> {
+        let section = self
+            .sections
+            .partition_point(|section| section.start <= index)
+            .checked_sub(1)?;
+
+        (index < self.sections[section].start + self.sections[section].len).then_some(section)
+    }
+
+    /// Whether `index` is the first item of a collapsed section, drawn as its header
+    fn is_collapsed_anchor(&self, index: usize) -> bool {
+        self.section_of(index).is_some_and(|section| {
+            self.sections[section].collapsed && self.sections[section].start == index
+        })
+    }
+
+    /// Item rows plus the top of each section header
+    fn section_rows(&self, available_width: f32, cell_size: f32) -> (JustifiedLayout, Vec<f32>) {
+        let item_aspect = |index: usize| {
+            let item = (self.item)(index);
+            item.width as f32 / item.height.max(1) as f32
+        };
+        let aspect: Option<&dyn Fn(usize) -> f32> = if !self.justified {
+            None
+        } else if let Some(ref aspect) = self.aspect {
+            Some(aspect.as_ref())
+        } else {
+            Some(&item_aspect)
+        };
+
+        arrange_rows(
+            self.item_count,
+            &self.sections,
+            aspect,
+            available_width,
+            cell_size,
+            self.column_spacing as f32,
+            self.row_spacing as f32,
+        )
+    }
+
+    /// Offset to scroll to so `index` (or its section header) sits at the top
+    fn reveal_offset(&self, index: usize) -> Option<f32> {
+        if let Some(section) = self.section_of(index)
+            && self.sections[section].start == index
+        {
+            return self
+                .header_offsets
+                .borrow()
+                .get(section)
+                .map(|y| y + self.padding.top);
+        }
+
+        self.cell_bounds(index).map(|cell| cell.y)
+    }
+
     fn item_at_position(&self, position: Point, bounds: Rectangle) -> Option<usize> {
+        if self.uses_rows() {
+            return self.rows.borrow().item_at(
+                position.x - bounds.x - self.padding.left,
+                position.y - bounds.y - self.padding.top,
+            );
+        }
+
         let cols = self.cached_cols.get();
         let row_height = self.cached_row_height.get();
 
@@ -229,7 +518,7 @@ impl<'a, M> GalleryGridInner<'a, M> {
             return None;
         }
 
-        let rows = self.items.len().div_ceil(cols);
+        let rows = self.item_count.div_ceil(cols);
         let item_size = self.thumbnail_size as f32;
 
         // Convert to local coordinates
@@ -245,30 +534,186 @@ impl<'a, M> GalleryGridInner<'a, M> {
             self.column_spacing as f32,
             self.row_spacing as f32,
             self.padding,
-            self.items.len(),
+            self.item_count,
         )
     }
 
     fn is_selected(&self, index: usize) -> bool {
         self.selected_indices.contains(&index)
     }
+
+    /// Bounds of an item's cell relative to the top left of the grid
+    fn cell_bounds(&self, index: usize) -> Option<Rectangle> {
+        if self.uses_rows() {
+            let (x, y, width, height) = self.rows.borrow().cell_rect(index)?;
+            return Some(Rectangle::new(
+                Point::new(self.padding.left + x, self.padding.top + y),
+                Size::new(width, height),
+            ));
+        }
+
+        let cols = self.cached_cols.get();
+        if cols == 0 || index >= self.item_count {
+            return None;
+        }
+
+        let cell_size = self.thumbnail_size as f32 + (self.column_spacing as f32 * 2.0);
+        let row_height = self.cached_row_height.get();
+        let row = index / cols;
+        let col = index % cols;
+
+        Some(Rectangle::new(
+            Point::new(
+                self.padding.left + (col as f32 * (cell_size + self.column_spacing as f32)),
+                self.padding.top + (row as f32 * (row_height + self.row_spacing as f32)),
+            ),
+            Size::new(cell_size, cell_size),
+        ))
+    }
+
+    /// Items in the rows intersecting `viewport`, plus the overscan rows around them
+    fn rows_in_view(&self, bounds: Rectangle, viewport: &Rectangle) -> Range<usize> {
+        let cols = self.cached_cols.get();
+        let Some(visible) = viewport.intersection(&bounds) else {
+            return 0..0;
+        };
+
+        if self.uses_rows() {
+            return self.rows.borrow().visible_range(
+                visible.y - bounds.y - self.padding.top,
+                visible.height,
+                OVERSCAN_ROWS,
+            );
+        }
+
+        let range = core::visible_range(
+            visible.y - bounds.y,
+            visible.height,
+            cols,
+            self.cached_row_height.get(),
+            self.row_spacing as f32,
+            self.padding.top,
+            self.item_count,
+        );
+
+        let overscan = OVERSCAN_ROWS * cols;
+        range.start.saturating_sub(overscan)..(range.end + overscan).min(self.item_count)
+    }
+}
+
+/// Rows for `len` items starting at `start`, relative to their own top. Rows are
+/// justified by `aspect` when it is set, square cells otherwise.
+fn block_rows(
+    start: usize,
+    len: usize,
+    aspect: Option<&dyn Fn(usize) -> f32>,
+    available_width: f32,
+    cell_size: f32,
+    column_spacing: f32,
+) -> JustifiedLayout {
+    match aspect {
+        Some(aspect) => JustifiedLayout::new(
+            (start..start + len).map(aspect),
+            available_width,
+            cell_size,
+            column_spacing,
+        ),
+        None => JustifiedLayout::uniform(len, available_width, cell_size, column_spacing),
+    }
+}
+
+/// Item rows plus the top of each section header
+fn arrange_rows(
+    item_count: usize,
+    sections: &[GridSection],
+    aspect: Option<&dyn Fn(usize) -> f32>,
+    available_width: f32,
+    cell_size: f32,
+    column_spacing: f32,
+    row_spacing: f32,
+) -> (JustifiedLayout, Vec<f32>) {
+    if sections.is_empty() {
+        return (
+            block_rows(
+                0,
+                item_count,
+                aspect,
+                available_width,
+                cell_size,
+                column_spacing,
+            ),
+            Vec::new(),
+        );
+    }
+
+    let mut rows = JustifiedLayout::default();
+    let mut offsets = Vec::with_capacity(sections.len());
+    let mut y = 0.0;
+
+    for section in sections {
+        offsets.push(y);
+
+        let block = if section.collapsed {
+            // A single full-width cell under the header holds the section's first item
+            JustifiedLayout {
+                rows: vec![JustifiedRow {
+                    start: 0,
+                    y: 0.0,
+                    height: HEADER_HEIGHT,
+                    cells: vec![JustifiedCell {
+                        x: 0.0,
+                        width: available_width,
+                    }],
+                }],
+                height: HEADER_HEIGHT,
+            }
+        } else {
+            y += HEADER_HEIGHT;
+            block_rows(
+                section.start,
+                section.len,
+                aspect,
+                available_width,
+                cell_size,
+                column_spacing,
+            )
+        };
+
+        let height = block.height;
+        if section.len > 0 {
+            rows.append(block, section.start, y);
+        }
+        y += height + row_spacing;
+    }
+
+    rows.height = (y - row_spacing).max(0.0);
+    (rows, offsets)
 }
 
 impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridInner<'a, M> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<GridState>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(GridState::default())
+    }
+
     fn children(&self) -> Vec<Tree> {
-        Vec::new() // No child widgets - we render thumbnails directly
+        // Thumbnails are rendered directly; only section headers are child widgets
+        self.headers.iter().map(Tree::new).collect()
     }
 
-    fn diff(&mut self, _tree: &mut Tree) {
-        // No children to diff
+    fn diff(&mut self, tree: &mut Tree) {
+        tree.diff_children(&mut self.headers);
     }
 
     fn size(&self) -> Size<Length> {
         Size::new(self.width, self.height)
     }
 
-    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &Limits) -> Node {
-        if self.items.is_empty() {
+    fn layout(&self, tree: &mut Tree, renderer: &Renderer, limits: &Limits) -> Node {
+        if self.item_count == 0 {
             return Node::new(Size::ZERO);
         }
 
@@ -280,16 +725,46 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
         let button_padding = self.column_spacing as f32; // Use spacing as button padding
         let cell_size = item_size + (button_padding * 2.0);
 
+        if self.uses_rows() {
+            let (rows, header_offsets) = self.section_rows(available_width, cell_size);
+
+            let header_limits = Limits::new(Size::ZERO, Size::new(available_width, HEADER_HEIGHT));
+            let headers = self
+                .headers
+                .iter()
+                .zip(tree.children.iter_mut())
+                .zip(&header_offsets)
+                .map(|((header, state), y)| {
+                    header
+                        .as_widget()
+                        .layout(state, renderer, &header_limits)
+                        .move_to(Point::new(self.padding.left, self.padding.top + y))
+                })
+                .collect();
+
+            let total_height = rows.height + self.padding.vertical();
+            self.cached_cols.set(1);
+            self.cached_row_height.set(cell_size);
+            self.rows.replace(rows);
+            self.header_offsets.replace(header_offsets);
+
+            let content_size = Size::new(max_size.width, total_height);
+            return Node::with_children(
+                limits.resolve(self.width, self.height, content_size),
+                headers,
+            );
+        }
+
         let cols = core::calculate_columns(
             available_width,
             cell_size,
             self.column_spacing as f32,
             1,
             None,
-            self.items.len(),
+            self.item_count,
         );
 
-        let rows = self.items.len().div_ceil(cols);
+        let rows = self.item_count.div_ceil(cols);
 
         // Calculate total height
         let row_height = cell_size;
@@ -312,25 +787,22 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
 
     fn draw(
         &self,
-        _tree: &Tree,
+        tree: &Tree,
         renderer: &mut Renderer,
         theme: &cosmic::Theme,
-        _style: &iced_renderer::Style,
+        style: &iced_renderer::Style,
         layout: Layout<'_>,
         cursor: Cursor,
-        _viewport: &Rectangle,
+        viewport: &Rectangle,
     ) {
         let bounds = layout.bounds();
         let cols = self.cached_cols.get();
-        let row_height = self.cached_row_height.get();
 
-        if cols == 0 || self.items.is_empty() {
+        if cols == 0 || self.item_count == 0 {
             return;
         }
 
-        let item_size = self.thumbnail_size as f32;
         let button_padding = self.column_spacing as f32;
-        let cell_size = item_size + (button_padding * 2.0);
 
         let cosmic_theme = theme.cosmic();
 
@@ -343,17 +815,12 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
             }
         });
 
-        for (index, item) in self.items.iter().enumerate() {
-            let row = index / cols;
-            let col = index % cols;
-
-            let x = bounds.x
-                + self.padding.left
-                + (col as f32 * (cell_size + self.column_spacing as f32));
-            let y =
-                bounds.y + self.padding.top + (row as f32 * (row_height + self.row_spacing as f32));
-
-            let cell_bounds = Rectangle::new(Point::new(x, y), Size::new(cell_size, cell_size));
+        for index in self.rows_in_view(bounds, viewport) {
+            let Some(cell) = self.cell_bounds(index) else {
+                continue;
+            };
+            let item = (self.item)(index);
+            let cell_bounds = cell + Vector::new(bounds.x, bounds.y);
 
             let is_focused = self.focused_index == Some(index);
             let is_selected = self.is_selected(index);
@@ -381,11 +848,13 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
                 bg_color,
             );
 
+            // Collapsed sections only show their header on top of the highlight
+            if self.is_collapsed_anchor(index) {
+                continue;
+            }
+
             // Draw thumbnail or placeholder
-            let image_bounds = Rectangle::new(
-                Point::new(x + button_padding, y + button_padding),
-                Size::new(item_size, item_size),
-            );
+            let image_bounds = cell_bounds.shrink(button_padding);
 
             if let Some(ref handle) = item.handle {
                 // Calculate centered bounds maintaining aspect ratio
@@ -403,13 +872,19 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
                     1.0,      // opacity
                     [0.0; 4], // snap
                 );
+
+                if item.status == ItemStatus::Damaged {
+                    draw_damaged_badge(renderer, centered, cosmic_theme.warning_color().into());
+                }
+            } else if item.status == ItemStatus::Failed {
+                draw_failed_placeholder(renderer, image_bounds);
             } else {
                 // Draw placeholder (simple gray box)
-                let placeholder_size = item_size / 2.0;
+                let placeholder_size = image_bounds.width.min(image_bounds.height) / 2.0;
                 let placeholder_bounds = Rectangle::new(
                     Point::new(
-                        image_bounds.x + (item_size - placeholder_size) / 2.0,
-                        image_bounds.y + (item_size - placeholder_size) / 2.0,
+                        image_bounds.center_x() - placeholder_size / 2.0,
+                        image_bounds.center_y() - placeholder_size / 2.0,
                     ),
                     Size::new(placeholder_size, placeholder_size),
                 );
@@ -424,21 +899,72 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
                 );
             }
         }
+
+        for ((header, state), header_layout) in self
+            .headers
+            .iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+        {
+            if header_layout.bounds().intersects(viewport) {
+                header.as_widget().draw(
+                    state,
+                    renderer,
+                    theme,
+                    style,
+                    header_layout,
+                    cursor,
+                    viewport,
+                );
+            }
+        }
     }
 
     fn on_event(
         &mut self,
-        _tree: &mut Tree,
+        tree: &mut Tree,
         event: Event,
         layout: Layout<'_>,
         cursor: Cursor,
-        _renderer: &Renderer,
-        _clipboard: &mut dyn Clipboard,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
         shell: &mut Shell<'_, M>,
         viewport: &Rectangle,
     ) -> Status {
         let bounds = layout.bounds();
 
+        for ((header, state), header_layout) in self
+            .headers
+            .iter_mut()
+            .zip(tree.children.iter_mut())
+            .zip(layout.children())
+        {
+            let status = header.as_widget_mut().on_event(
+                state,
+                event.clone(),
+                header_layout,
+                cursor,
+                renderer,
+                clipboard,
+                shell,
+                viewport,
+            );
+            if status == Status::Captured {
+                return status;
+            }
+        }
+
+        let state = tree.state.downcast_mut::<GridState>();
+
+        if self.reveal != state.revealed {
+            state.revealed = self.reveal;
+            if let Some(ref on_scroll_request) = self.on_scroll_request
+                && let Some(offset_y) = self.reveal.and_then(|index| self.reveal_offset(index))
+            {
+                shell.publish(on_scroll_request(ScrollRequest { offset_y }));
+            }
+        }
+
         match event {
             // Mouse hover - visual only, no messages (draw() handles highlight from cursor)
             Event::Mouse(mouse::Event::CursorMoved { .. }) => {
@@ -446,13 +972,46 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
                 // We don't fire callbacks here to avoid scroll jank from UI refreshes
             }
 
-            // Click - activate
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = modifiers;
+            }
+
+            // Ctrl+wheel - resize thumbnails instead of scrolling
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                if state.modifiers.control()
+                    && let Some(ref on_zoom) = self.on_zoom
+                    && cursor
+                        .position()
+                        .is_some_and(|position| bounds.contains(position))
+                {
+                    let steps = match delta {
+                        mouse::ScrollDelta::Lines { y, .. } => y,
+                        mouse::ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_ZOOM_STEP,
+                    };
+                    if steps != 0.0 {
+                        shell.publish(on_zoom(steps));
+                    }
+                    return Status::Captured;
+                }
+            }
+
+            // Click - activate, Ctrl+click - toggle selection
             Event::Mouse(mouse::Event::ButtonPressed(Button::Left)) => {
                 if let Some(position) = cursor.position()
                     && bounds.contains(position)
                     && let Some(index) = self.item_at_position(position, bounds)
                 {
-                    if let Some(ref on_activate) = self.on_activate {
+                    if self.is_collapsed_anchor(index) {
+                        if let Some(ref on_toggle_section) = self.on_toggle_section
+                            && let Some(section) = self.section_of(index)
+                        {
+                            shell.publish(on_toggle_section(section));
+                        }
+                    } else if state.modifiers.control()
+                        && let Some(ref on_toggle_select) = self.on_toggle_select
+                    {
+                        shell.publish(on_toggle_select(index));
+                    } else if let Some(ref on_activate) = self.on_activate {
                         shell.publish(on_activate(index));
                     }
                     return Status::Captured;
@@ -466,14 +1025,45 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
                 }
 
                 let cols = self.cached_cols.get();
-                if cols == 0 || self.items.is_empty() {
+                if cols == 0 || self.item_count == 0 {
                     return Status::Ignored;
                 }
 
                 let current = self.focused_index.unwrap_or(0);
-                let total = self.items.len();
+                let total = self.item_count;
+                let uses_rows = self.uses_rows();
 
                 let new_index = match key {
+                    Key::Named(keyboard::key::Named::Space) if !self.sections.is_empty() => {
+                        if let Some(ref on_toggle_section) = self.on_toggle_section
+                            && let Some(section) = self.section_of(current)
+                        {
+                            shell.publish(on_toggle_section(section));
+                        }
+                        return Status::Captured;
+                    }
+                    Key::Named(keyboard::key::Named::Enter)
+                        if self.is_collapsed_anchor(current) =>
+                    {
+                        if let Some(ref on_toggle_section) = self.on_toggle_section
+                            && let Some(section) = self.section_of(current)
+                        {
+                            shell.publish(on_toggle_section(section));
+                        }
+                        return Status::Captured;
+                    }
+                    Key::Named(keyboard::key::Named::ArrowLeft) if uses_rows => {
+                        self.rows.borrow().prev_item(current)
+                    }
+                    Key::Named(keyboard::key::Named::ArrowRight) if uses_rows => {
+                        self.rows.borrow().next_item(current)
+                    }
+                    Key::Named(keyboard::key::Named::Home) if uses_rows => {
+                        self.rows.borrow().first_item()
+                    }
+                    Key::Named(keyboard::key::Named::End) if uses_rows => {
+                        self.rows.borrow().last_item()
+                    }
                     Key::Named(keyboard::key::Named::ArrowLeft) => {
                         if current > 0 {
                             Some(current - 1)
@@ -488,6 +1078,12 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
                             None
                         }
                     }
+                    Key::Named(keyboard::key::Named::ArrowUp) if uses_rows => {
+                        self.rows.borrow().move_focus_up(current)
+                    }
+                    Key::Named(keyboard::key::Named::ArrowDown) if uses_rows => {
+                        self.rows.borrow().move_focus_down(current)
+                    }
                     Key::Named(keyboard::key::Named::ArrowUp) => {
                         if current >= cols {
                             Some(current - cols)
@@ -520,32 +1116,17 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
                     }
 
                     // Only scroll if item is out of view
-                    if let Some(ref on_scroll_request) = self.on_scroll_request {
-                        let row_height = self.cached_row_height.get();
-                        let row = new_idx / cols;
-                        let row_spacing = self.row_spacing as f32;
-
-                        // Item position relative to grid content
-                        let item_top = self.padding.top + (row as f32 * (row_height + row_spacing));
-                        let item_bottom = item_top + row_height;
-
-                        // Viewport position relative to grid (scroll offset)
-                        let scroll_offset = viewport.y - bounds.y;
-                        let visible_top = scroll_offset;
-                        let visible_bottom = scroll_offset + viewport.height;
-
-                        // Only scroll if item is not fully visible
-                        if item_top < visible_top {
-                            // Item is above viewport - scroll up to show it at top
-                            shell.publish(on_scroll_request(ScrollRequest { offset_y: item_top }));
-                        } else if item_bottom > visible_bottom {
-                            // Item is below viewport - scroll down to show it at bottom
-                            let new_offset = item_bottom - viewport.height;
-                            shell.publish(on_scroll_request(ScrollRequest {
-                                offset_y: new_offset.max(0.0),
-                            }));
-                        }
-                        // else: item is visible, no scroll needed
+                    if let Some(ref on_scroll_request) = self.on_scroll_request
+                        && let Some(cell) = self.cell_bounds(new_idx)
+                        && let Some(offset_y) = core::scroll_offset_for_span(
+                            cell.y,
+                            cell.y + cell.height,
+                            // Viewport position relative to grid (scroll offset)
+                            viewport.y - bounds.y,
+                            viewport.height,
+                        )
+                    {
+                        shell.publish(on_scroll_request(ScrollRequest { offset_y }));
                     }
 
                     return Status::Captured;
@@ -560,13 +1141,33 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
 
     fn mouse_interaction(
         &self,
-        _tree: &Tree,
+        tree: &Tree,
         layout: Layout<'_>,
         cursor: Cursor,
-        _viewport: &Rectangle,
-        _renderer: &Renderer,
+        viewport: &Rectangle,
+        renderer: &Renderer,
     ) -> mouse::Interaction {
         let bounds = layout.bounds();
+
+        let header_interaction = self
+            .headers
+            .iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .map(|((header, state), header_layout)| {
+                header.as_widget().mouse_interaction(
+                    state,
+                    header_layout,
+                    cursor,
+                    viewport,
+                    renderer,
+                )
+            })
+            .max()
+            .unwrap_or_default();
+        if header_interaction != mouse::Interaction::default() {
+            return header_interaction;
+        }
         if let Some(position) = cursor.position()
             && bounds.contains(position)
             && self.item_at_position(position, bounds).is_some()
@@ -596,6 +1197,109 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
     }
 }
 
+/// Warning badge in the top right corner of a damaged image's thumbnail
+fn draw_damaged_badge(renderer: &mut Renderer, image: Rectangle, color: Color) {
+    let size = (image.width.min(image.height) * 0.2).clamp(12.0, 24.0);
+    let badge = Rectangle::new(
+        Point::new(image.x + image.width - size - 4.0, image.y + 4.0),
+        Size::new(size, size),
+    );
+
+    renderer.fill_quad(
+        Quad {
+            bounds: badge,
+            border: cosmic::iced::Border {
+                radius: (size / 2.0).into(),
+                width: 1.0,
+                color: Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+            },
+            shadow: Default::default(),
+        },
+        color,
+    );
+
+    // Exclamation mark: a bar and a dot
+    let mark = Color::from_rgba(0.0, 0.0, 0.0, 0.85);
+    let stroke = (size / 8.0).max(1.5);
+    let bar = Rectangle::new(
+        Point::new(badge.center_x() - stroke / 2.0, badge.y + size * 0.22),
+        Size::new(stroke, size * 0.36),
+    );
+    let dot = Rectangle::new(
+        Point::new(badge.center_x() - stroke / 2.0, badge.y + size * 0.66),
+        Size::new(stroke, stroke),
+    );
+    for bounds in [bar, dot] {
+        renderer.fill_quad(
+            Quad {
+                bounds,
+                border: cosmic::iced::Border {
+                    radius: (stroke / 2.0).into(),
+                    ..Default::default()
+                },
+                shadow: Default::default(),
+            },
+            mark,
+        );
+    }
+}
+
+/// Picture frame outline standing in for a thumbnail that couldn't be decoded
+fn draw_failed_placeholder(renderer: &mut Renderer, bounds: Rectangle) {
+    let color = Color::from_rgba(0.5, 0.5, 0.5, 0.6);
+    let width = bounds.width.min(bounds.height) / 2.0;
+    let height = width * 0.8;
+    let stroke = (width / 16.0).max(1.5);
+    let frame = Rectangle::new(
+        Point::new(
+            bounds.center_x() - width / 2.0,
+            bounds.center_y() - height / 2.0,
+        ),
+        Size::new(width, height),
+    );
+
+    renderer.fill_quad(
+        Quad {
+            bounds: frame,
+            border: cosmic::iced::Border {
+                radius: (stroke * 2.0).into(),
+                width: stroke,
+                color,
+            },
+            shadow: Default::default(),
+        },
+        Color::TRANSPARENT,
+    );
+
+    // Sun in the top left and a horizon line across the lower third
+    let sun = width / 6.0;
+    renderer.fill_quad(
+        Quad {
+            bounds: Rectangle::new(
+                Point::new(frame.x + width / 5.0, frame.y + height / 5.0),
+                Size::new(sun, sun),
+            ),
+            border: cosmic::iced::Border {
+                radius: (sun / 2.0).into(),
+                ..Default::default()
+            },
+            shadow: Default::default(),
+        },
+        color,
+    );
+    renderer.fill_quad(
+        Quad {
+            bounds: Rectangle::new(
+                Point::new(frame.x + stroke, frame.y + height * 0.66),
+                Size::new(width - stroke * 2.0, stroke),
+            ),
+            border: cosmic::iced::Border::default(),
+            shadow: Default::default(),
+        },
+        color,
+    );
+}
+
 impl<'a, M: Clone + 'static> From<GalleryGridInner<'a, M>> for Element<'a, M> {
     fn from(grid: GalleryGridInner<'a, M>) -> Self {
         Element::new(grid)
@@ -1,7 +1,10 @@
 pub mod flex_grid_core;
 pub mod gallery_grid;
 
-pub use gallery_grid::{GalleryGrid, GalleryItem, ScrollRequest, gallery_grid};
+pub use gallery_grid::{
+    GalleryGrid, GalleryItem, GridArrangement, GridSection, ItemStatus, ScrollRequest,
+    gallery_grid, gallery_grid_from_fn, visible_items,
+};
 
 // Re-export types from viewer-types for convenience
 pub use viewer_types::types::{CropRegion, CropSelection, DragHandle};
@@ -5,10 +5,17 @@ use crate::{
     key_binds::{self, MenuAction},
     menu::menu_bar,
     message::{
-        ContextPage, DeleteAction, DragHandle, EditMessage, ImageMessage, Message, NavMessage,
-        SettingsMessage, ViewMessage,
+        CompareMessage, ContextPage, DeleteAction, DragHandle, DuplicatesMessage, EditMessage,
+        ImageMessage, Message, NavMessage, NotificationMessage, SettingsMessage, SidebarMessage,
+        ViewMessage,
+    },
+    views::{
+        CONTROLS_TICK_MS, CompareView, DuplicatesView, GalleryView, ImageViewState, Inspector,
+        KINETIC_TICK_MS, Notifications, Pages, Reader, Sidebar, Timeline, VectorRender, gallery,
+        inspector,
+        notifications::{edit_error_text, load_error_text},
+        sidebar::{self, Place, PlaceKind},
     },
-    views::{GalleryView, ImageViewState},
     watcher,
 };
 use ashpd::{
@@ -27,22 +34,44 @@ use cosmic::{
     task::future,
     widget::{
         Id, button, column, dropdown,
+        image::Handle,
         menu::key_bind::{KeyBind, Modifier},
-        radio, settings, slider, spin_button, text,
+        radio, settings, slider, spin_button, text, text_input,
     },
 };
 use rfd::AsyncFileDialog;
+use shared::{
+    color,
+    formats::ImageFormat,
+    prefetch::{self, NavDirection},
+    thumbnail_zoom, tiling, timeline,
+};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
+};
+use viewer_config::{
+    AppTheme, GalleryLayout, ThumbnailSize, ViewerBackground, ViewerConfig, WallpaperBehavior,
 };
-use viewer_config::{AppTheme, ThumbnailSize, ViewerConfig, WallpaperBehavior};
 use viewer_image::edit::Transform;
-use viewer_image::{self as image, CachedImage, ImageCache, edit::EditState};
+use viewer_image::{
+    self as image, CachedImage, CancelToken, ImageCache, JobKind, LayerInfo, LoadError, Priority,
+    edit::EditState,
+};
 use viewer_nav::{self as nav, NavState};
 
+/// Quiet period after the last threshold change before duplicates are regrouped
+const REGROUP_DELAY: Duration = Duration::from_millis(300);
+
+/// Quiet period after the last zoom change before a vector image is rendered again
+const VECTOR_RENDER_DELAY: Duration = Duration::from_millis(150);
+
+/// Quiet period before rapidly repeated changes, like wheel zooming, are written to the config
+const CONFIG_SAVE_DELAY: Duration = Duration::from_secs(1);
+
 pub struct ImageViewer {
     core: Core,
     config: ViewerConfig,
@@ -52,16 +81,39 @@ pub struct ImageViewer {
     cache: ImageCache,
     image_state: ImageViewState,
     gallery_view: GalleryView,
+    sidebar: Sidebar,
     context_page: Option<ContextPage>,
     is_loading: bool,
-    is_fullscreen: bool,
     is_slideshow_active: bool,
+    /// Custom background color field, as typed
+    background_input: String,
     wallpaper_dialog: Option<PathBuf>,
     available_outputs: Vec<String>,
     delete_dialog: Option<PathBuf>,
+    duplicates: Option<DuplicatesView>,
+    compare: Option<CompareView>,
     edit_state: EditState,
     _save_dialog: Option<PathBuf>,
-    thumbnail_load_cursor: usize,
+    /// Direction of the last navigation, steers prefetching
+    nav_direction: Option<NavDirection>,
+    /// In-flight prefetch decodes that can still be cancelled
+    prefetching: HashMap<PathBuf, CancelToken>,
+    /// Page counts of the loaded files that have more than one page
+    page_counts: HashMap<PathBuf, usize>,
+    /// Formats of the opened files as detected from their content
+    formats: HashMap<PathBuf, ImageFormat>,
+    /// Canvas size and layer count of the layered documents that were opened
+    layer_infos: HashMap<PathBuf, LayerInfo>,
+    /// Render of the current vector image in flight, with the generation it was started for
+    vector_render: Option<(u64, CancelToken)>,
+    /// Bumped whenever the zoom asks for a new render; renders start once it settles
+    vector_generation: u64,
+    /// The zoom settled while a render was in flight, render again once it is done
+    vector_waiting: bool,
+    /// Generation of the scheduled config write, `None` once it is on disk
+    pending_config_save: Option<u64>,
+    config_save_generation: u64,
+    notifications: Notifications,
 }
 
 impl ImageViewer {
@@ -76,16 +128,20 @@ impl ImageViewer {
         self.is_loading = true;
 
         cosmic::task::future(async move {
-            match image::load_image(path.clone()).await {
+            match image::load_image(path.clone(), Priority::Current).await {
                 Ok(img) => Message::Image(ImageMessage::Loaded {
                     path,
                     handle: img.handle,
                     width: img.width,
                     height: img.height,
+                    page_count: img.page_count,
+                    partial: img.partial,
+                    pyramid: img.pyramid,
                 }),
                 Err(e) => Message::Image(ImageMessage::LoadFailed {
                     path,
-                    error: e.to_string(),
+                    error: Arc::new(e),
+                    report: true,
                 }),
             }
         })
@@ -93,12 +149,380 @@ impl ImageViewer {
 
     fn load_current_image(&mut self) -> Task<Action<Message>> {
         if let Some(path) = self.nav.current().cloned() {
-            self.load_image(path)
+            Task::batch([
+                self.load_preview(path.clone()),
+                self.load_image(path.clone()),
+                self.load_pyramid(path.clone()),
+                self.load_pages(),
+                self.load_format(path),
+            ])
         } else {
             Task::none()
         }
     }
 
+    /// Set up the page strip when the current file has several pages
+    fn load_pages(&mut self) -> Task<Action<Message>> {
+        // Decoded at twice the strip height for HiDPI screens
+        const PAGE_THUMBNAIL_SIZE: u32 = 128;
+
+        let Some(path) = self.nav.current().cloned() else {
+            self.image_state.pages = None;
+            return Task::none();
+        };
+        if self.image_state.pages_of(&path).is_some() {
+            return Task::none();
+        }
+
+        let count = self.page_counts.get(&path).copied().unwrap_or(1);
+        // The page strip takes room from the image, so the fit zoom changes with it
+        if count <= 1 {
+            if self.image_state.pages.take().is_some() {
+                self.update_fit_zoom();
+            }
+            return Task::none();
+        }
+        self.image_state.pages = Some(Pages::new(path.clone(), count));
+        self.update_fit_zoom();
+
+        cosmic::task::future(async move {
+            match image::load_page_thumbnails(path.clone(), PAGE_THUMBNAIL_SIZE).await {
+                Ok(thumbnails) => {
+                    Message::Image(ImageMessage::PageThumbnailsReady { path, thumbnails })
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to load pages of {}: {e}", path.display());
+                    Message::Cancelled
+                }
+            }
+        })
+    }
+
+    /// Detect the format of a file from its content for the info page
+    fn load_format(&self, path: PathBuf) -> Task<Action<Message>> {
+        if self.formats.contains_key(&path) {
+            return self.load_layer_info(path);
+        }
+
+        cosmic::task::future(async move {
+            let format = image::load_format(path.clone()).await;
+            Message::Image(ImageMessage::FormatDetected { path, format })
+        })
+    }
+
+    /// Read the canvas size and layer count of a layered document for the info page
+    fn load_layer_info(&self, path: PathBuf) -> Task<Action<Message>> {
+        if self.layer_infos.contains_key(&path)
+            || !self
+                .formats
+                .get(&path)
+                .is_some_and(|format| format.is_layered())
+        {
+            return Task::none();
+        }
+
+        cosmic::task::future(async move {
+            match image::load_layer_info(path.clone()).await {
+                Ok(info) => Message::Image(ImageMessage::LayerInfoReady { path, info }),
+                Err(e) => {
+                    tracing::warn!("Failed to read layers of {}: {e}", path.display());
+                    Message::Cancelled
+                }
+            }
+        })
+    }
+
+    /// Format of the current image as detected from its content
+    fn current_format(&self) -> Option<ImageFormat> {
+        self.nav
+            .current()
+            .and_then(|path| self.formats.get(path))
+            .copied()
+    }
+
+    /// Ask for a sharper render of the current vector image when it is zoomed past its
+    /// intrinsic size. The render starts once the zoom has settled.
+    fn render_vector(&mut self) -> Task<Action<Message>> {
+        // Renders that differ less than this from the one on screen aren't worth redoing
+        const RENDER_TOLERANCE: f32 = 0.05;
+
+        let is_vector = self.current_format().is_some_and(ImageFormat::is_vector);
+        let path = self
+            .nav
+            .current()
+            .cloned()
+            .filter(|_| is_vector && self.image_state.preview_image.is_none());
+        let scale = self.image_state.effective_zoom();
+
+        // Up to the intrinsic size the cached render is sharp
+        let Some(path) = path.filter(|_| scale > 1.0) else {
+            self.image_state.vector = None;
+            self.cancel_vector_render();
+            return Task::none();
+        };
+
+        let previous = self
+            .image_state
+            .vector
+            .take()
+            .filter(|vector| vector.path == path);
+        if let Some(ref vector) = previous
+            && (vector.scale / scale - 1.0).abs() < RENDER_TOLERANCE
+        {
+            self.image_state.vector = previous;
+            return Task::none();
+        }
+        if previous.is_none() {
+            // Still rendering another image
+            self.cancel_vector_render();
+        }
+
+        self.image_state.vector = Some(VectorRender {
+            path,
+            scale,
+            image: previous.and_then(|vector| vector.image),
+        });
+        self.vector_generation += 1;
+        self.vector_waiting = false;
+
+        delayed(
+            VECTOR_RENDER_DELAY,
+            Message::Image(ImageMessage::RenderVector(self.vector_generation)),
+        )
+    }
+
+    /// Rasterize the current vector image at the scale it was last asked for
+    fn start_vector_render(&mut self) -> Task<Action<Message>> {
+        let Some(ref vector) = self.image_state.vector else {
+            return Task::none();
+        };
+        let (path, scale) = (vector.path.clone(), vector.scale);
+        let generation = self.vector_generation;
+        let token = CancelToken::new();
+        self.vector_render = Some((generation, token.clone()));
+
+        cosmic::task::future(async move {
+            match image::render_vector(path.clone(), scale, token).await {
+                Ok(img) => Message::Image(ImageMessage::VectorRendered {
+                    generation,
+                    path,
+                    scale,
+                    handle: img.handle,
+                    width: img.width,
+                    height: img.height,
+                }),
+                Err(LoadError::Cancelled) => Message::Cancelled,
+                Err(e) => Message::Image(ImageMessage::VectorFailed {
+                    generation,
+                    path,
+                    error: Arc::new(e),
+                }),
+            }
+        })
+    }
+
+    fn cancel_vector_render(&mut self) {
+        if let Some((_, token)) = self.vector_render.take() {
+            token.cancel();
+        }
+        self.vector_waiting = false;
+    }
+
+    /// A render finished; returns whether it was the one in flight
+    fn finish_vector_render(&mut self, generation: u64) -> bool {
+        let current = self
+            .vector_render
+            .as_ref()
+            .is_some_and(|(started, _)| *started == generation);
+        if current {
+            self.vector_render = None;
+        }
+        current
+    }
+
+    /// Image drawn in the modal: the edited preview, a later page or the decoded file
+    fn displayed_image(&self) -> Option<CachedImage> {
+        if let Some(ref preview) = self.image_state.preview_image {
+            return Some(preview.clone());
+        }
+
+        let path = self.nav.current()?;
+        // The first page of icons and HEIF files is decoded apart from the cached image
+        if let Some(pages) = self.image_state.pages_of(path)
+            && (pages.current > 0 || pages.image.is_some())
+        {
+            return pages.image.clone();
+        }
+
+        self.cache.get_full(path)
+    }
+
+    /// Start over the inspector's histogram when the image it looks at changed
+    fn refresh_inspector(&mut self) -> Task<Action<Message>> {
+        let displayed = self.displayed_image();
+        let Some(inspector) = self.image_state.inspector.as_mut() else {
+            return Task::none();
+        };
+        let Some(displayed) = displayed.filter(|displayed| !inspector.is_for(&displayed.handle))
+        else {
+            return Task::none();
+        };
+
+        inspector.set_source(displayed.handle.clone(), displayed.width, displayed.height);
+
+        let mut tasks = vec![Self::compute_histogram(displayed.handle.clone())];
+        if inspector.shows_clipping() {
+            tasks.push(Self::compute_clipping_mask(displayed.handle));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Group the fingerprints of the last scan off the UI thread
+    fn group_duplicates(
+        generation: u64,
+        entries: Arc<Vec<image::DuplicateEntry>>,
+        threshold: u32,
+    ) -> Task<Action<Message>> {
+        cosmic::task::future(async move {
+            match tokio::task::spawn_blocking(move || image::group_duplicates(&entries, threshold))
+                .await
+            {
+                Ok(groups) => {
+                    Message::Duplicates(DuplicatesMessage::Grouped { generation, groups })
+                }
+                Err(_) => Message::Cancelled,
+            }
+        })
+    }
+
+    fn compute_histogram(handle: Handle) -> Task<Action<Message>> {
+        let source = handle.id();
+
+        cosmic::task::future(async move {
+            match tokio::task::spawn_blocking(move || inspector::compute_histogram(&handle)).await {
+                Ok(Some(histogram)) => Message::View(ViewMessage::HistogramReady {
+                    source,
+                    histogram: Arc::new(histogram),
+                }),
+                _ => Message::Cancelled,
+            }
+        })
+    }
+
+    fn compute_clipping_mask(handle: Handle) -> Task<Action<Message>> {
+        let source = handle.id();
+
+        cosmic::task::future(async move {
+            match tokio::task::spawn_blocking(move || inspector::compute_clipping_mask(&handle))
+                .await
+            {
+                Ok(Some(mask)) => Message::View(ViewMessage::ClippingMaskReady { source, mask }),
+                _ => Message::Cancelled,
+            }
+        })
+    }
+
+    /// Show `page` of the current multi-page file
+    fn show_page(&mut self, page: usize) -> Task<Action<Message>> {
+        let Some(path) = self.nav.current().cloned() else {
+            return Task::none();
+        };
+        let Some(pages) = self
+            .image_state
+            .pages
+            .as_mut()
+            .filter(|pages| pages.is_for(&path) && page < pages.count)
+        else {
+            return Task::none();
+        };
+
+        pages.current = page;
+        pages.image = None;
+        self.image_state.zoom_fit();
+        self.update_fit_zoom();
+
+        // TIFFs open on their first page, so it is the cached image. Icons open on
+        // their largest entry and HEIF files on their primary image, whatever page that is.
+        if page == 0 && self.formats.get(&path) == Some(&ImageFormat::Tiff) {
+            return Task::none();
+        }
+
+        cosmic::task::future(async move {
+            match image::load_page(path.clone(), page).await {
+                Ok(img) => Message::Image(ImageMessage::PageLoaded {
+                    path,
+                    page,
+                    handle: img.handle,
+                    width: img.width,
+                    height: img.height,
+                }),
+                Err(e) => Message::Image(ImageMessage::LoadFailed {
+                    path,
+                    error: Arc::new(e),
+                    report: true,
+                }),
+            }
+        })
+    }
+
+    /// Fast reduced-resolution decode to show until the full image arrives
+    fn load_preview(&mut self, path: PathBuf) -> Task<Action<Message>> {
+        // Decoded at a fixed size and upscaled to fit; just a stand-in
+        const PREVIEW_SIZE: u32 = 1024;
+
+        if self.cache.get_full(&path).is_some()
+            || self.cache.get_preview(&path).is_some()
+            || self.cache.is_pending(&path)
+        {
+            return Task::none();
+        }
+
+        cosmic::task::future(async move {
+            match image::load_preview(path.clone(), PREVIEW_SIZE).await {
+                Ok(img) => Message::Image(ImageMessage::PreviewReady {
+                    path,
+                    handle: img.handle,
+                    width: img.width,
+                    height: img.height,
+                }),
+                // Formats without a fast path keep showing the thumbnail
+                Err(_) => Message::Cancelled,
+            }
+        })
+    }
+
+    /// Build the tile pyramid once a very large image has been opened at preview resolution
+    fn load_pyramid(&mut self, path: PathBuf) -> Task<Action<Message>> {
+        let Some(cached) = self.cache.get_full(&path) else {
+            return Task::none();
+        };
+
+        // A damaged file only has the preview salvaged from it
+        if !tiling::needs_tiling(cached.width, cached.height)
+            || self.cache.is_damaged(&path)
+            || self.cache.get_pyramid(&path).is_some()
+            || self.cache.is_pyramid_pending(&path)
+        {
+            return Task::none();
+        }
+
+        self.cache.set_pyramid_pending(path.clone());
+
+        cosmic::task::future(async move {
+            match image::load_pyramid(path.clone()).await {
+                Ok(pyramid) => Message::Image(ImageMessage::PyramidLoaded {
+                    path,
+                    pyramid: Arc::new(pyramid),
+                }),
+                Err(e) => Message::Image(ImageMessage::LoadFailed {
+                    path,
+                    error: Arc::new(e),
+                    report: false,
+                }),
+            }
+        })
+    }
+
     fn reload_thumbnail(&mut self, path: PathBuf) -> Task<Action<Message>> {
         // Remove from cache to force reload
         self.cache.remove_thumbnail(&path);
@@ -110,19 +534,29 @@ impl ImageViewer {
         }
 
         self.cache.set_thumbnail_pending(path.clone());
-        let max_size = self.config.thumbnail_size.pixels();
+        let max_size = self.thumbnail_decode_size();
 
         cosmic::task::future(async move {
-            match image::load_thumbnail(path.clone(), max_size).await {
+            match image::load_thumbnail(
+                path.clone(),
+                max_size,
+                Priority::Visible,
+                CancelToken::new(),
+            )
+            .await
+            {
                 Ok(img) => Message::Image(ImageMessage::ThumbnailReady {
                     path,
                     handle: img.handle,
                     width: img.width,
                     height: img.height,
+                    decoded_at: max_size,
+                    partial: img.partial,
                 }),
-                Err(_) => Message::Image(ImageMessage::LoadFailed {
+                Err(LoadError::Cancelled) => Message::Cancelled,
+                Err(e) => Message::Image(ImageMessage::ThumbnailFailed {
                     path,
-                    error: "Failed to load thumbnail".to_string(),
+                    error: Arc::new(e),
                 }),
             }
         })
@@ -139,46 +573,59 @@ impl ImageViewer {
             .count()
     }
 
-    // Load thumbnails in batches to avoid GPU memory exhaustion
-    fn load_thumbnails(&mut self) -> Task<Action<Message>> {
-        const MAX_PENDING: usize = 8;
-
-        let pending = self.cache.pending_thumbnail_count();
-        if pending >= MAX_PENDING {
-            return Task::none();
-        }
+    /// Resolution gallery thumbnails are decoded at for the current tile size
+    fn thumbnail_decode_size(&self) -> u32 {
+        thumbnail_zoom::decode_size(self.config.thumbnail_size.pixels())
+    }
 
-        let slots = MAX_PENDING - pending;
-        let thumbnail_size = self.config.thumbnail_size.pixels();
-        let images = self.nav.images();
-        let total = images.len();
+    /// Queue every missing or outgrown thumbnail; visible ones jump the queue and
+    /// the rest are reprioritized as the gallery scrolls
+    fn load_thumbnails(&mut self) -> Task<Action<Message>> {
+        let thumbnail_size = self.thumbnail_decode_size();
+        let visible = self.visible_thumbnails();
         let mut tasks = Vec::new();
 
-        while tasks.len() < slots && self.thumbnail_load_cursor < total {
-            let path = images[self.thumbnail_load_cursor].clone();
-            self.thumbnail_load_cursor += 1;
-
-            if self.cache.get_thumbnail(&path).is_some() || self.cache.is_thumbnail_pending(&path) {
+        for (idx, path) in self.nav.images().iter().enumerate() {
+            // Smaller cached thumbnails stay on screen, scaled, until the sharper one arrives
+            let decoded_at = self.cache.thumbnail_decode_size(path);
+            if decoded_at.is_some_and(|size| size >= thumbnail_size)
+                || self.cache.is_thumbnail_pending(path)
+                || self.cache.is_thumbnail_failed(path)
+            {
                 continue;
             }
 
+            let priority = if visible.contains(&idx) {
+                Priority::Visible
+            } else {
+                Priority::Background
+            };
+            let path = path.clone();
             self.cache.set_thumbnail_pending(path.clone());
 
             tasks.push(cosmic::task::future(async move {
-                match image::load_thumbnail(path.clone(), thumbnail_size).await {
+                match image::load_thumbnail(
+                    path.clone(),
+                    thumbnail_size,
+                    priority,
+                    CancelToken::new(),
+                )
+                .await
+                {
                     Ok(img) => Message::Image(ImageMessage::ThumbnailReady {
                         path,
                         handle: img.handle,
                         width: img.width,
                         height: img.height,
+                        decoded_at: thumbnail_size,
+                        partial: img.partial,
+                    }),
+                    // Pending state was cleared when the job was cancelled
+                    Err(LoadError::Cancelled) => Message::Cancelled,
+                    Err(e) => Message::Image(ImageMessage::ThumbnailFailed {
+                        path,
+                        error: Arc::new(e),
                     }),
-                    Err(e) => {
-                        tracing::warn!("Thumbnail failed to load: {e}");
-                        Message::Image(ImageMessage::LoadFailed {
-                            path,
-                            error: e.to_string(),
-                        })
-                    }
                 }
             }));
         }
@@ -186,6 +633,41 @@ impl ImageViewer {
         Task::batch(tasks)
     }
 
+    /// Gallery indices currently on screen
+    fn visible_thumbnails(&self) -> Range<usize> {
+        self.gallery_view
+            .visible_range(&self.nav, &self.cache, &self.config)
+    }
+
+    /// Move queued thumbnails on screen to the front and everything else to the back
+    fn reprioritize_thumbnails(&self) {
+        let images = self.nav.images();
+        let visible: HashSet<&Path> = images[self.visible_thumbnails()]
+            .iter()
+            .map(PathBuf::as_path)
+            .collect();
+
+        image::scheduler().reprioritize(JobKind::Thumbnail, |path, _| {
+            if visible.contains(path) {
+                Priority::Visible
+            } else {
+                Priority::Background
+            }
+        });
+    }
+
+    /// Whether the thumbnail grid is what the user is looking at
+    fn is_gallery_shown(&self) -> bool {
+        !self.nav.is_selected() && self.compare.is_none() && self.duplicates.is_none()
+    }
+
+    /// Drop queued thumbnail decodes, e.g. for a folder that is no longer shown
+    fn cancel_thumbnails(&self) {
+        for path in image::scheduler().cancel_where(JobKind::Thumbnail, |_| true) {
+            self.cache.clear_pending_thumbnail(&path);
+        }
+    }
+
     // Preload adjacent images for smooth navigation (not all images)
     fn preload_images(&mut self) -> Task<Action<Message>> {
         let current_idx = self.nav.index().unwrap_or(0);
@@ -193,40 +675,80 @@ impl ImageViewer {
     }
 
     fn preload_images_at(&mut self, current_idx: usize) -> Task<Action<Message>> {
-        const PRELOAD_AHEAD: usize = 2;
-        const PRELOAD_BEHIND: usize = 2;
-
         let images = self.nav.images();
         let total = images.len();
         if total == 0 {
             return Task::none();
         }
-        let mut tasks = Vec::new();
 
-        // Calculate range of images to preload (current + adjacent)
-        let start = current_idx.saturating_sub(PRELOAD_BEHIND);
-        let end = (current_idx + PRELOAD_AHEAD + 1).min(total);
+        // Favour the direction of travel, look further ahead during slideshows
+        let window: Vec<PathBuf> = prefetch::prefetch_window(
+            current_idx,
+            total,
+            self.nav_direction,
+            self.is_slideshow_active,
+        )
+        .into_iter()
+        .filter_map(|idx| images.get(idx).cloned())
+        .collect();
+
+        // The image now shown goes first, a previously current one is just a prefetch now
+        let current = images.get(current_idx).cloned();
+        image::scheduler().reprioritize(JobKind::Image, |path, priority| {
+            if current.as_deref() == Some(path) {
+                Priority::Current
+            } else {
+                priority.min(Priority::Prefetch)
+            }
+        });
+
+        // Cancel decodes that fell out of the window, unless it is the image now shown
+        let stale: Vec<PathBuf> = self
+            .prefetching
+            .keys()
+            .filter(|path| !window.contains(path) && current.as_ref() != Some(*path))
+            .cloned()
+            .collect();
+        for path in stale {
+            if let Some(token) = self.prefetching.remove(&path) {
+                token.cancel();
+                self.cache.clear_pending(&path);
+            }
+        }
 
-        for img in images.iter().take(end).skip(start) {
-            let path = img.clone();
+        let max_bytes = prefetch::per_image_budget(window.len());
+        let mut tasks = Vec::new();
 
+        for path in window {
             if self.cache.get_full(&path).is_some() || self.cache.is_pending(&path) {
                 continue;
             }
 
+            let token = CancelToken::new();
             self.cache.set_pending(path.clone());
+            self.prefetching.insert(path.clone(), token.clone());
 
             tasks.push(cosmic::task::future(async move {
-                match image::load_image(path.clone()).await {
+                match image::prefetch_image(path.clone(), max_bytes, token).await {
                     Ok(img) => Message::Image(ImageMessage::Loaded {
                         path,
                         handle: img.handle,
                         width: img.width,
                         height: img.height,
+                        page_count: img.page_count,
+                        partial: img.partial,
+                        pyramid: img.pyramid,
                     }),
+                    Err(e @ (LoadError::Cancelled | LoadError::OverBudget)) => {
+                        Message::Image(ImageMessage::PrefetchSkipped {
+                            path,
+                            cancelled: matches!(e, LoadError::Cancelled),
+                        })
+                    }
                     Err(e) => Message::Image(ImageMessage::LoadFailed {
                         path,
-                        error: e.to_string(),
+                        error: Arc::new(e),
+                        report: false,
                     }),
                 }
             }));
@@ -235,11 +757,26 @@ impl ImageViewer {
         Task::batch(tasks)
     }
 
+    /// Drop every outstanding prefetch, e.g. when the folder changes
+    fn cancel_prefetch(&mut self) {
+        for (path, token) in self.prefetching.drain() {
+            token.cancel();
+            self.cache.clear_pending(&path);
+        }
+    }
+
     fn update_fit_zoom(&mut self) {
         // Use preview image dimensions if available, otherwise use cached
         if let Some(ref preview) = self.image_state.preview_image {
             self.image_state
                 .calculate_fit_zoom(preview.width, preview.height);
+        } else if let Some(page) = self
+            .nav
+            .current()
+            .and_then(|path| self.image_state.pages_of(path))
+            .and_then(|pages| pages.image.as_ref())
+        {
+            self.image_state.calculate_fit_zoom(page.width, page.height);
         } else if let Some(path) = self.nav.current()
             && let Some(cached) = self.cache.get_full(path)
         {
@@ -248,9 +785,129 @@ impl ImageViewer {
         }
     }
 
+    /// Reapply the preserved zoom and scroll position if the current image is already loaded
+    fn restore_viewport(&mut self) -> Task<Action<Message>> {
+        if self.image_state.preview_image.is_none()
+            && let Some(path) = self.nav.current()
+            && let Some(cached) = self.cache.get_full(path)
+        {
+            return self
+                .image_state
+                .restore_viewport(cached.width, cached.height)
+                .map(Action::from);
+        }
+
+        Task::none()
+    }
+
+    /// Show `page` in reader mode and remember it as the reading position
+    fn turn_to_page(&mut self, page: usize, direction: NavDirection) -> Task<Action<Message>> {
+        self.is_slideshow_active = false;
+        self.edit_state.reset();
+        self.nav.go_to(page);
+        self.nav_direction = Some(direction);
+        self.image_state.zoom_fit();
+        self.update_fit_zoom();
+
+        Task::batch([
+            self.remember_reading_position(),
+            self.scroll_reader(),
+            self.load_current_image(),
+            self.load_reader_pages(),
+            self.preload_images(),
+            self.update_title(),
+        ])
+    }
+
+    /// Load the other pages of the spread shown with the current one
+    fn load_reader_pages(&mut self) -> Task<Action<Message>> {
+        let Some(page) = self.nav.index() else {
+            return Task::none();
+        };
+        if !self.gallery_view.reader.shows_pages() {
+            return Task::none();
+        }
+
+        let pages = self.gallery_view.reader.pages(page, self.nav.total());
+        let paths: Vec<PathBuf> = self.nav.images()[pages].to_vec();
+        Task::batch(paths.into_iter().map(|path| self.load_image(path)))
+    }
+
+    /// Bring the current page to the top of the continuous reader strip
+    fn scroll_reader(&mut self) -> Task<Action<Message>> {
+        let Some(page) = self.nav.index() else {
+            return Task::none();
+        };
+
+        let reader = &mut self.gallery_view.reader;
+        if !reader.shows_pages() {
+            return Task::none();
+        }
+        // Estimated from the window until the strip reports its size
+        if reader.strip_width <= 0.0 {
+            reader.strip_width = self.image_state.viewport_size().0;
+        }
+
+        reader
+            .scroll_to_page(&self.nav, &self.cache, page)
+            .map(Action::from)
+    }
+
+    /// Write the config once it stopped changing for `CONFIG_SAVE_DELAY`
+    fn save_config_later(&mut self) -> Task<Action<Message>> {
+        self.config_save_generation += 1;
+        self.pending_config_save = Some(self.config_save_generation);
+        delayed(
+            CONFIG_SAVE_DELAY,
+            Message::SaveConfig(self.config_save_generation),
+        )
+    }
+
+    /// Apply a thumbnail size without saving it
+    fn set_thumbnail_size(&mut self, size: ThumbnailSize) -> Task<Action<Message>> {
+        let previous = self.thumbnail_decode_size();
+        self.config.thumbnail_size = size;
+
+        // Cached thumbnails are scaled to the new size; queued decodes
+        // only need redoing when the decode resolution changed
+        if self.thumbnail_decode_size() != previous {
+            self.cancel_thumbnails();
+            self.load_thumbnails()
+        } else {
+            self.reprioritize_thumbnails();
+            Task::none()
+        }
+    }
+
+    /// Write the config now, e.g. before closing, which also covers a scheduled write
+    fn save_config_now(&mut self) {
+        self.pending_config_save = None;
+        if let Some(ref handler) = self.config_handler {
+            let _ = self.config.write_entry(handler);
+        }
+    }
+
+    /// Store the reader's page for its folder; written once the pages stop turning
+    fn remember_reading_position(&mut self) -> Task<Action<Message>> {
+        if !self.gallery_view.reader.is_active {
+            return Task::none();
+        }
+
+        if let Some(page) = self.nav.index()
+            && let Some(folder) = self.nav.current().and_then(|path| nav::get_image_dir(path))
+            && let Some(folder) = folder.to_str()
+        {
+            self.config.set_reading_position(folder.to_string(), page);
+            return self.save_config_later();
+        }
+
+        Task::none()
+    }
+
     fn scan_and_nav(&mut self, path: PathBuf) -> Task<Action<Message>> {
         let dir = nav::get_image_dir(&path);
         let include_hidden = self.config.show_hidden_files;
+        let include_extensionless = self.config.include_extensionless_files;
         let sort_mode = self.config.sort_mode;
         let sort_order = self.config.sort_order;
         let target = path.clone();
@@ -266,18 +923,75 @@ impl ImageViewer {
         }
 
         cosmic::task::future(async move {
-            let images = if let Some(dir) = dir {
-                nav::scan_dir(&dir, include_hidden, sort_mode, sort_order).await
-            } else {
-                Vec::new()
-            };
+            let images = if let Some(dir) = dir {
+                nav::scan_dir(
+                    &dir,
+                    include_hidden,
+                    include_extensionless,
+                    sort_mode,
+                    sort_order,
+                )
+                .await
+            } else {
+                Vec::new()
+            };
+
+            Message::Nav(NavMessage::DirectoryScanned { images, target })
+        })
+    }
+
+    /// Read capture dates for the timeline; the gallery stays ungrouped until they arrive
+    fn load_timeline(&mut self) -> Task<Action<Message>> {
+        let Some(ref mut timeline) = self.gallery_view.timeline else {
+            return Task::none();
+        };
+        timeline.dates.clear();
+
+        let images = self.nav.shared_images();
+        let known = timeline.known.clone();
+        cosmic::task::future(async move {
+            let (dates, known) = image::capture_dates(images.clone(), known).await;
+            Message::View(ViewMessage::TimelineLoaded {
+                images,
+                dates,
+                known: Arc::new(known),
+            })
+        })
+    }
+
+    /// List the sidebar's places with their image counts; collapses the folder tree
+    fn load_places(&self) -> Task<Action<Message>> {
+        let include_hidden = self.config.show_hidden_files;
+        let bookmarks: Vec<PathBuf> = self.config.bookmarks.iter().map(PathBuf::from).collect();
+
+        let mut fixed = Vec::new();
+        if let Some(pictures) = dirs::picture_dir() {
+            fixed.push(Place::new(
+                pictures,
+                fl!("places-pictures"),
+                PlaceKind::Pictures,
+            ));
+        }
+        if let Some(home) = dirs::home_dir() {
+            fixed.push(Place::new(home, fl!("places-home"), PlaceKind::Home));
+        }
+
+        cosmic::task::future(async move {
+            let volumes = nav::mounted_volumes().await;
+            let places = sidebar::place_list(fixed, &volumes, &bookmarks);
+            let folders = places
+                .iter()
+                .map(|place| (place.path.clone(), place.label.clone()))
+                .collect();
+            let entries = nav::folder_entries(folders, include_hidden).await;
 
-            Message::Nav(NavMessage::DirectoryScanned { images, target })
+            Message::Sidebar(SidebarMessage::PlacesLoaded { places, entries })
         })
     }
 
     fn reload_image_list(&mut self) -> Task<Action<Message>> {
         let include_hidden = self.config.show_hidden_files;
+        let include_extensionless = self.config.include_extensionless_files;
         let sort_mode = self.config.sort_mode;
         let sort_order = self.config.sort_order;
 
@@ -293,7 +1007,14 @@ impl ImageViewer {
 
         if let Some(dir) = dir_option {
             return cosmic::task::future(async move {
-                let images = nav::scan_dir(&dir, include_hidden, sort_mode, sort_order).await;
+                let images = nav::scan_dir(
+                    &dir,
+                    include_hidden,
+                    include_extensionless,
+                    sort_mode,
+                    sort_order,
+                )
+                .await;
                 Message::Nav(NavMessage::DirectoryRefreshed { images })
             });
         }
@@ -334,9 +1055,7 @@ impl ImageViewer {
                             height,
                         })
                     }
-                    Err(err) => {
-                        Message::OpenError(Arc::new(format!("Failed to apply edits: {}", err)))
-                    }
+                    Err(err) => Message::Edit(EditMessage::ApplyFailed(Arc::new(err))),
                 },
             )
         } else {
@@ -368,7 +1087,7 @@ impl ImageViewer {
                 },
                 |result| match result {
                     Ok(path) => Message::Edit(EditMessage::SaveComplete(Ok(path))),
-                    Err(err) => Message::Edit(EditMessage::SaveComplete(Err(err.to_string()))),
+                    Err(err) => Message::Edit(EditMessage::SaveComplete(Err(Arc::new(err)))),
                 },
             )
         } else {
@@ -408,6 +1127,9 @@ impl Application for ImageViewer {
             Err(_) => (ViewerConfig::default(), None),
         };
 
+        let [r, g, b] = config.custom_background;
+        let background_input = color::hex([r, g, b, 255]);
+
         let mut app = Self {
             core,
             config,
@@ -417,18 +1139,33 @@ impl Application for ImageViewer {
             cache: ImageCache::with_defaults(),
             image_state: ImageViewState::new(),
             gallery_view: GalleryView::new(),
+            sidebar: Sidebar::default(),
             context_page: None,
             is_loading: false,
-            is_fullscreen: false,
             is_slideshow_active: false,
+            background_input,
             wallpaper_dialog: None,
             available_outputs: Vec::new(),
             delete_dialog: None,
+            duplicates: None,
+            compare: None,
             edit_state: EditState::new(),
             _save_dialog: None,
-            thumbnail_load_cursor: 0,
+            nav_direction: None,
+            prefetching: HashMap::new(),
+            page_counts: HashMap::new(),
+            formats: HashMap::new(),
+            layer_infos: HashMap::new(),
+            vector_render: None,
+            vector_generation: 0,
+            vector_waiting: false,
+            pending_config_save: None,
+            config_save_generation: 0,
+            notifications: Notifications::default(),
         };
 
+        app.gallery_view.reader = Reader::new(&app.config);
+
         let startup_path = if let Some(path) = flags {
             Some(path)
         } else if app.config.remember_last_dir {
@@ -448,6 +1185,9 @@ impl Application for ImageViewer {
         if let Some(path) = startup_path {
             tasks.push(app.scan_and_nav(path));
         }
+        if app.config.show_sidebar {
+            tasks.push(app.load_places());
+        }
 
         (app, Task::batch(tasks))
     }
@@ -457,74 +1197,20 @@ impl Application for ImageViewer {
             &self.core,
             &self.key_binds,
             self.is_slideshow_active,
+            self.gallery_view.timeline.is_some(),
+            self.config.show_sidebar,
+            &self.gallery_view.reader,
             &self.config.recent_folders,
         )]
     }
 
     fn view(&self) -> Element<'_, Self::Message> {
-        let gallery = self.gallery_view.view(
-            &self.nav,
-            &self.cache,
-            self.config.thumbnail_size.pixels(),
-            &self.image_state,
-            &self.edit_state,
-        );
-
-        // Overlay crop dialog if active (takes priority over other dialogs)
-        if self.edit_state.is_cropping
-            && let Some(path) = self.nav.current()
-            && let Some(cached) = self.cache.get_full(path)
-        {
-            let dialog = self.crop_dialog_view(&cached);
-
-            // Backdrop that doesn't close - crop requires explicit Apply/Cancel
-            let backdrop = cosmic::widget::mouse_area(
-                cosmic::widget::container(cosmic::widget::Space::new(
-                    cosmic::iced::Length::Fill,
-                    cosmic::iced::Length::Fill,
-                ))
-                .width(cosmic::iced::Length::Fill)
-                .height(cosmic::iced::Length::Fill)
-                .class(cosmic::theme::Container::Transparent),
-            )
-            .on_press(Message::View(ViewMessage::ImageEditEvent)); // No-op, just captures clicks
-
-            return cosmic::iced_widget::stack![gallery, backdrop, dialog].into();
-        }
-
-        // Overlay wallpaper dialog if active
-        if let Some(path) = &self.wallpaper_dialog {
-            let dialog = self.wallpaper_dialog_view(path);
-
-            let backdrop = cosmic::widget::mouse_area(
-                cosmic::widget::container(cosmic::widget::Space::new(
-                    cosmic::iced::Length::Fill,
-                    cosmic::iced::Length::Fill,
-                ))
-                .width(cosmic::iced::Length::Fill)
-                .height(cosmic::iced::Length::Fill)
-                .class(cosmic::theme::Container::Transparent),
-            )
-            .on_press(Message::CloseWallpaperDialog);
-
-            cosmic::iced_widget::stack![gallery, backdrop, dialog].into()
-        } else if let Some(path) = &self.delete_dialog {
-            let dialog = self.delete_dialog_view(path);
-
-            let backdrop = cosmic::widget::mouse_area(
-                cosmic::widget::container(cosmic::widget::Space::new(
-                    cosmic::iced::Length::Fill,
-                    cosmic::iced::Length::Fill,
-                ))
-                .width(cosmic::iced::Length::Fill)
-                .height(cosmic::iced::Length::Fill)
-                .class(cosmic::theme::Container::Transparent),
-            )
-            .on_press(Message::CloseDeleteDialog);
+        let content = self.content_view();
 
-            cosmic::iced_widget::stack![gallery, backdrop, dialog].into()
+        if self.notifications.is_empty() {
+            content
         } else {
-            gallery
+            cosmic::iced_widget::stack![content, self.notifications.view()].into()
         }
     }
 
@@ -542,8 +1228,22 @@ impl Application for ImageViewer {
                     handle,
                     width,
                     height,
+                    page_count,
+                    partial,
+                    pyramid,
                 } => {
                     self.is_loading = false;
+                    self.prefetching.remove(&path);
+                    if page_count > 1 {
+                        self.page_counts.insert(path.clone(), page_count);
+                    }
+                    if partial {
+                        self.cache.set_damaged(path.clone());
+                    }
+                    // Built from the same decode as the preview, `load_pyramid` skips it
+                    if let Some(pyramid) = pyramid {
+                        self.cache.insert_pyramid(path.clone(), pyramid);
+                    }
                     self.cache.insert_full(
                         path.clone(),
                         CachedImage {
@@ -553,7 +1253,14 @@ impl Application for ImageViewer {
                         },
                     );
                     if self.nav.current() == Some(&path) {
+                        tasks.push(self.load_pyramid(path.clone()));
+                        tasks.push(self.load_pages());
                         self.image_state.calculate_fit_zoom(width, height);
+                        tasks.push(
+                            self.image_state
+                                .restore_viewport(width, height)
+                                .map(Action::from),
+                        );
                     }
                     tasks.push(self.update_title());
                 }
@@ -574,18 +1281,165 @@ impl Application for ImageViewer {
                     self.image_state.calculate_fit_zoom(width, height);
                     tasks.push(self.update_title());
                 }
-                ImageMessage::LoadFailed { path, error } => {
+                ImageMessage::PrefetchSkipped { path, cancelled } => {
+                    // Cancelled decodes were already cleaned up when they left the window
+                    if !cancelled {
+                        self.prefetching.remove(&path);
+                        self.cache.clear_pending(&path);
+                        tracing::debug!("Skipped prefetching {}: over budget", path.display());
+                    }
+
+                    // Navigated to while it was waiting, the budget doesn't apply to the current image
+                    if self.nav.current() == Some(&path) {
+                        tasks.push(self.load_image(path));
+                    }
+                }
+                ImageMessage::PreviewReady {
+                    path,
+                    handle,
+                    width,
+                    height,
+                } => {
+                    // Too late if the full image won the race
+                    if self.cache.get_full(&path).is_none() {
+                        self.cache.insert_preview(
+                            path,
+                            CachedImage {
+                                handle,
+                                width,
+                                height,
+                            },
+                        );
+                    }
+                }
+                ImageMessage::PageLoaded {
+                    path,
+                    page,
+                    handle,
+                    width,
+                    height,
+                } => {
+                    // Dropped if another page or file was opened meanwhile
+                    if let Some(pages) = self.image_state.pages.as_mut()
+                        && pages.is_for(&path)
+                        && pages.current == page
+                    {
+                        pages.image = Some(CachedImage {
+                            handle,
+                            width,
+                            height,
+                        });
+                        self.image_state.calculate_fit_zoom(width, height);
+                    }
+                }
+                ImageMessage::RenderVector(generation) => {
+                    if generation == self.vector_generation {
+                        if self.vector_render.is_some() {
+                            // Only one render at a time, this one follows when it is done
+                            self.vector_waiting = true;
+                        } else {
+                            tasks.push(self.start_vector_render());
+                        }
+                    }
+                }
+                ImageMessage::VectorRendered {
+                    generation,
+                    path,
+                    scale,
+                    handle,
+                    width,
+                    height,
+                } => {
+                    if self.finish_vector_render(generation) {
+                        // Renders for an earlier zoom are still sharper than what is shown
+                        if let Some(vector) = self.image_state.vector.as_mut()
+                            && vector.path == path
+                        {
+                            vector.image = Some(CachedImage {
+                                handle,
+                                width,
+                                height,
+                            });
+                        }
+                        tracing::debug!("Rendered {} at {scale}x", path.display());
+
+                        if std::mem::take(&mut self.vector_waiting) {
+                            tasks.push(self.start_vector_render());
+                        }
+                    }
+                }
+                ImageMessage::VectorFailed {
+                    generation,
+                    path,
+                    error,
+                } => {
+                    if self.finish_vector_render(generation) {
+                        tracing::error!("Failed to render {}: {error}", path.display());
+                        if std::mem::take(&mut self.vector_waiting) {
+                            tasks.push(self.start_vector_render());
+                        }
+                    }
+                }
+                ImageMessage::FormatDetected { path, format } => {
+                    if let Some(format) = format {
+                        self.formats.insert(path.clone(), format);
+                        tasks.push(self.load_layer_info(path));
+                    }
+                }
+                ImageMessage::LayerInfoReady { path, info } => {
+                    self.layer_infos.insert(path, info);
+                }
+                ImageMessage::PageThumbnailsReady { path, thumbnails } => {
+                    if let Some(pages) = self.image_state.pages.as_mut()
+                        && pages.is_for(&path)
+                    {
+                        for thumbnail in thumbnails {
+                            if let Some(slot) = pages.thumbnails.get_mut(thumbnail.page) {
+                                *slot = Some(CachedImage {
+                                    handle: thumbnail.handle,
+                                    width: thumbnail.width,
+                                    height: thumbnail.height,
+                                });
+                            }
+                        }
+                    }
+                }
+                ImageMessage::PyramidLoaded { path, pyramid } => {
+                    self.cache.insert_pyramid(path, pyramid);
+                }
+                ImageMessage::LoadFailed {
+                    path,
+                    error,
+                    report,
+                } => {
                     self.is_loading = false;
+                    let prefetched = self.prefetching.remove(&path).is_some();
                     self.cache.clear_pending(&path);
                     self.cache.clear_pending_thumbnail(&path);
+                    self.cache.clear_pending_pyramid(&path);
                     tracing::error!("Failed to load {}: {error}", path.display());
+
+                    // A failed prefetch of the image that is shown by now counts as its load
+                    if (report || prefetched)
+                        && self.nav.current() == Some(&path)
+                        && !matches!(*error, LoadError::Cancelled | LoadError::OverBudget)
+                    {
+                        let (summary, body) = load_error_text(&path, &error);
+                        self.notifications
+                            .push(summary, body, Some(error.to_string()));
+                    }
                 }
                 ImageMessage::ThumbnailReady {
                     path,
                     handle,
                     width,
                     height,
+                    decoded_at,
+                    partial,
                 } => {
+                    if partial {
+                        self.cache.set_damaged(path.clone());
+                    }
                     self.cache.insert_thumbnail(
                         path,
                         CachedImage {
@@ -593,26 +1447,62 @@ impl Application for ImageViewer {
                             width,
                             height,
                         },
+                        decoded_at,
                     );
-
-                    // Load next batch of thumbnails
-                    tasks.push(self.load_thumbnails());
+                }
+                ImageMessage::ThumbnailFailed { path, error } => {
+                    tracing::warn!("Thumbnail failed to load: {error}");
+                    self.cache.set_thumbnail_failed(path);
                 }
                 ImageMessage::Clear => {
+                    self.cancel_prefetch();
+                    self.cancel_thumbnails();
                     self.nav = NavState::new();
                     self.cache.clear();
+                    self.page_counts.clear();
+                    self.formats.clear();
+                    self.layer_infos.clear();
+                    self.image_state.pages = None;
                     tasks.push(self.update_title());
                 }
             },
             Message::Nav(nav_msg) => match nav_msg {
+                // Reader mode turns whole spreads and stops at either end
+                NavMessage::Next
+                    if self.nav.is_selected() && self.gallery_view.reader.is_active =>
+                {
+                    let total = self.nav.total();
+                    if let Some(page) = self
+                        .nav
+                        .index()
+                        .and_then(|page| self.gallery_view.reader.next_page(page, total))
+                    {
+                        tasks.push(self.turn_to_page(page, NavDirection::Forward));
+                    }
+                }
+                NavMessage::Prev
+                    if self.nav.is_selected() && self.gallery_view.reader.is_active =>
+                {
+                    let total = self.nav.total();
+                    if let Some(page) = self
+                        .nav
+                        .index()
+                        .and_then(|page| self.gallery_view.reader.prev_page(page, total))
+                    {
+                        tasks.push(self.turn_to_page(page, NavDirection::Backward));
+                    }
+                }
                 NavMessage::Next => {
                     self.is_slideshow_active = false;
                     if self.nav.is_selected() {
                         // Modal open: navigate images
                         self.edit_state.reset();
                         self.nav.go_next();
-                        self.image_state.zoom_fit(); // Reset to fit mode for new image
+                        self.nav_direction = Some(NavDirection::Forward);
+                        // Keep zoom and position if the next image has the same size
+                        self.image_state.preserve_viewport();
                         self.update_fit_zoom();
+                        tasks.push(self.restore_viewport());
                         tasks.push(self.load_current_image());
                         tasks.push(self.preload_images());
                         tasks.push(self.update_title().map(Action::from));
@@ -635,8 +1525,11 @@ impl Application for ImageViewer {
                     if self.nav.is_selected() {
                         self.edit_state.reset();
                         self.nav.go_prev();
-                        self.image_state.zoom_fit(); // Reset to fit mode for new image
+                        self.nav_direction = Some(NavDirection::Backward);
+                        // Keep zoom and position if the next image has the same size
+                        self.image_state.preserve_viewport();
                         self.update_fit_zoom();
+                        tasks.push(self.restore_viewport());
                         tasks.push(self.load_current_image());
                         tasks.push(self.preload_images());
                         tasks.push(self.update_title().map(Action::from));
@@ -686,14 +1579,26 @@ impl Application for ImageViewer {
                 }
                 NavMessage::GallerySelect(idx) => {
                     self.nav.select(idx);
+                    self.nav_direction = None;
                     self.image_state.zoom_fit();
                     self.update_fit_zoom();
+                    tasks.push(self.remember_reading_position());
                     tasks.push(self.load_current_image());
+                    tasks.push(self.load_reader_pages());
+                    tasks.push(self.scroll_reader());
                     tasks.push(self.preload_images());
                 }
+                NavMessage::GalleryToggleSelect(idx) => {
+                    self.gallery_view.toggle_selection(idx);
+                }
                 NavMessage::DirectoryScanned { images, target } => {
+                    self.cancel_prefetch();
                     self.nav.set_images(images, Some(&target));
-                    self.thumbnail_load_cursor = 0;
+                    self.sidebar.current = nav::get_image_dir(&target);
+                    self.nav_direction = None;
+                    self.gallery_view.clear_selection();
+                    tasks.push(self.load_timeline());
+                    self.cancel_thumbnails();
                     self.cache.resize_thumbnails(self.nav.total());
                     // Save last directory if enabled
                     if self.config.remember_last_dir {
@@ -709,8 +1614,26 @@ impl Application for ImageViewer {
                         }
                     }
 
+                    // Comic books are read, not browsed
+                    if nav::is_comic_book(&target) {
+                        self.gallery_view.reader.is_active = true;
+                    }
+
+                    // In reader mode a folder or archive opens where reading stopped
+                    let resume = self
+                        .gallery_view
+                        .reader
+                        .is_active
+                        .then(|| nav::get_image_dir(&target))
+                        .flatten()
+                        .filter(|_| self.nav.index().is_none())
+                        .and_then(|dir| self.config.reading_position(&dir.to_string_lossy()))
+                        .filter(|&page| page < self.nav.total());
+
                     // Open modal only if a specific image file was requested
-                    if target.is_file() && self.nav.total() > 0 {
+                    if let Some(page) = resume {
+                        self.nav.select(page);
+                    } else if target.is_file() && self.nav.total() > 0 {
                         self.nav.select(self.nav.index().unwrap_or(0));
                     } else if self.nav.total() > 0 {
                         // Focus first image in gallery - FlexGrid handles scrolling
@@ -718,19 +1641,23 @@ impl Application for ImageViewer {
                     }
 
                     tasks.push(self.load_thumbnails());
-                    if target.is_file() {
+                    if self.nav.is_selected() {
+                        self.update_fit_zoom();
                         tasks.push(self.load_current_image());
+                        tasks.push(self.load_reader_pages());
+                        tasks.push(self.scroll_reader());
                         tasks.push(self.preload_images());
                     }
                 }
                 NavMessage::DirectoryRefreshed { images } => {
-                    self.thumbnail_load_cursor = 0;
                     let was_selected = self.nav.is_selected();
                     let prev_path = self.nav.current().cloned();
                     let prev_idx = self.nav.index().unwrap_or(0);
 
                     // Update image list; clearing the selection
                     self.nav.set_images(images.clone(), None);
+                    self.gallery_view.clear_selection();
+                    tasks.push(self.load_timeline());
                     self.cache.resize_thumbnails(self.nav.total());
 
                     if was_selected {
@@ -767,16 +1694,129 @@ impl Application for ImageViewer {
                 }
             },
             Message::View(view_msg) => match view_msg {
+                // In the gallery, zoom keys resize the thumbnails
+                ViewMessage::ZoomIn if self.is_gallery_shown() => {
+                    return self.update(Message::View(ViewMessage::ZoomThumbnails(1.0)));
+                }
+                ViewMessage::ZoomOut if self.is_gallery_shown() => {
+                    return self.update(Message::View(ViewMessage::ZoomThumbnails(-1.0)));
+                }
                 ViewMessage::ZoomIn => tasks.push(self.image_state.zoom_in().map(Action::from)),
                 ViewMessage::ZoomOut => tasks.push(self.image_state.zoom_out().map(Action::from)),
                 ViewMessage::ZoomReset => {
                     tasks.push(self.image_state.zoom_reset().map(Action::from))
                 }
                 ViewMessage::ZoomFit => self.image_state.zoom_fit(),
+                ViewMessage::NextPage => {
+                    if self.nav.is_selected()
+                        && let Some(page) = self.image_state.pages.as_ref().and_then(Pages::next)
+                    {
+                        tasks.push(self.show_page(page));
+                    }
+                }
+                ViewMessage::PrevPage => {
+                    if self.nav.is_selected()
+                        && let Some(page) = self.image_state.pages.as_ref().and_then(Pages::prev)
+                    {
+                        tasks.push(self.show_page(page));
+                    }
+                }
+                ViewMessage::GoToPage(page) => tasks.push(self.show_page(page)),
+                ViewMessage::ZoomAt { factor, x, y } => {
+                    tasks.push(self.image_state.zoom_at(factor, (x, y)).map(Action::from))
+                }
+                ViewMessage::ToggleZoomAt { x, y } => {
+                    tasks.push(self.image_state.toggle_zoom_at((x, y)).map(Action::from))
+                }
+                ViewMessage::PanStart => self.image_state.pan_start(),
+                ViewMessage::Pan { dx, dy } => {
+                    tasks.push(self.image_state.pan(dx, dy).map(Action::from))
+                }
+                ViewMessage::PanEnd => self.image_state.pan_end(),
+                ViewMessage::KineticTick => {
+                    tasks.push(self.image_state.kinetic_tick().map(Action::from))
+                }
+                ViewMessage::ImageScrolled { x, y } => self.image_state.set_scroll_offset(x, y),
+                ViewMessage::ToggleInspector => {
+                    self.image_state.inspector = match self.image_state.inspector.take() {
+                        Some(_) => None,
+                        None => Some(Inspector::default()),
+                    };
+                    // The panel takes its width from the image
+                    self.update_fit_zoom();
+                }
+                ViewMessage::InspectAt { x, y } => {
+                    if let Some((x, y)) = self.image_state.image_point(x, y)
+                        && let Some(inspector) = self.image_state.inspector.as_mut()
+                    {
+                        // Edits and other pages aren't in the pyramid
+                        let pyramid = self
+                            .nav
+                            .current()
+                            .filter(|path| {
+                                self.cache
+                                    .get_full(path)
+                                    .is_some_and(|cached| inspector.is_for(&cached.handle))
+                            })
+                            .and_then(|path| self.cache.get_pyramid(path));
+                        inspector.sample_at(x, y, pyramid.as_deref());
+                    }
+                }
+                ViewMessage::HistogramReady { source, histogram } => {
+                    if let Some(inspector) = self.image_state.inspector.as_mut()
+                        && inspector.source().map(Handle::id) == Some(source)
+                    {
+                        inspector.set_histogram(histogram);
+                    }
+                }
+                ViewMessage::ToggleClipping => {
+                    if let Some(inspector) = self.image_state.inspector.as_mut()
+                        && inspector.toggle_clipping()
+                        && let Some(source) = inspector.source()
+                    {
+                        tasks.push(Self::compute_clipping_mask(source.clone()));
+                    }
+                }
+                ViewMessage::ClippingMaskReady { source, mask } => {
+                    if let Some(inspector) = self.image_state.inspector.as_mut()
+                        && inspector.source().map(Handle::id) == Some(source)
+                    {
+                        inspector.set_clipping_mask(mask);
+                    }
+                }
+                ViewMessage::CopyColor => {
+                    if let Some(sample) = self
+                        .image_state
+                        .inspector
+                        .as_ref()
+                        .and_then(Inspector::sample)
+                    {
+                        tasks.push(cosmic::iced::clipboard::write(sample.hex()));
+                    }
+                }
+                ViewMessage::CycleBackground => {
+                    // Only meaningful with an image open
+                    if self.nav.index().is_some() {
+                        self.config.viewer_background = self.config.viewer_background.next();
+                        if let Some(ref handler) = self.config_handler {
+                            let _ = self.config.write_entry(handler);
+                        }
+                    }
+                }
+                ViewMessage::PointerMoved => self.image_state.pointer_moved(),
+                ViewMessage::ControlsTick => self.image_state.controls_tick(),
+                ViewMessage::MinimapPan { x, y } => {
+                    tasks.push(self.image_state.center_on(x, y).map(Action::from))
+                }
+                ViewMessage::ToggleMinimapPin => {
+                    self.image_state.minimap_pinned = !self.image_state.minimap_pinned;
+                }
                 ViewMessage::ToggleFullScreen => {
-                    self.is_fullscreen = !self.is_fullscreen;
+                    self.image_state.toggle_fullscreen();
+                    // Fullscreen fits the image to the whole window, not the modal
+                    self.update_fit_zoom();
 
-                    let mode = if self.is_fullscreen {
+                    let mode = if self.image_state.fullscreen {
                         window::Mode::Fullscreen
                     } else {
                         window::Mode::Windowed
@@ -789,7 +1829,15 @@ impl Application for ImageViewer {
                     return window::change_mode::<Message>(window_id, mode).map(Action::from);
                 }
                 ViewMessage::CloseModal => {
-                    // Close the modal
+                    if self.duplicates.take().is_some() || self.compare.take().is_some() {
+                        return Task::none();
+                    }
+
+                    // Close the modal, the reading position is saved right away
+                    if self.gallery_view.reader.is_active {
+                        let _ = self.remember_reading_position();
+                        self.save_config_now();
+                    }
                     self.nav.deselect();
                     // Clear preview image so it doesn't persist
                     self.image_state.preview_image = None;
@@ -803,6 +1851,21 @@ impl Application for ImageViewer {
                     if self.is_slideshow_active {
                         self.is_slideshow_active = false;
                     }
+
+                    // The gallery isn't shown fullscreen, closing the image leaves it
+                    if self.image_state.fullscreen {
+                        self.image_state.toggle_fullscreen();
+                        self.update_fit_zoom();
+
+                        let window_id = self
+                            .core
+                            .main_window_id()
+                            .expect("Main window ID should be present");
+                        tasks.push(
+                            window::change_mode::<Message>(window_id, window::Mode::Windowed)
+                                .map(Action::from),
+                        );
+                    }
                 }
                 ViewMessage::FocusUp => {
                     // When modal is open, up arrow = prev image
@@ -856,23 +1919,167 @@ impl Application for ImageViewer {
                         tasks.push(self.load_current_image());
                     }
                 }
-                ViewMessage::StartSlideshow => {
-                    if self.nav.total() > 0 {
-                        self.is_slideshow_active = true;
-                        if !self.nav.is_selected()
-                            && let Some(path) = self.nav.go_to(0).cloned()
-                        {
-                            self.update_fit_zoom();
-                            tasks.push(self.load_image(path.clone()));
-                        }
+                ViewMessage::StartSlideshow => {
+                    if self.nav.total() > 0 {
+                        self.is_slideshow_active = true;
+                        if !self.nav.is_selected()
+                            && let Some(path) = self.nav.go_to(0).cloned()
+                        {
+                            self.update_fit_zoom();
+                            tasks.push(self.load_image(path.clone()));
+                        }
+                    }
+                }
+                ViewMessage::StopSlideshow => self.is_slideshow_active = false,
+                ViewMessage::ToggleSlideshow => {
+                    if self.is_slideshow_active {
+                        tasks.push(self.update(Message::View(ViewMessage::StopSlideshow)));
+                    } else {
+                        tasks.push(self.update(Message::View(ViewMessage::StartSlideshow)));
+                    }
+                }
+                ViewMessage::GalleryScrolled(viewport) => {
+                    self.gallery_view.viewport = Some(viewport);
+                    self.gallery_view.reveal = None;
+                    self.reprioritize_thumbnails();
+                }
+                ViewMessage::ZoomThumbnails(steps) => {
+                    let current = self.config.thumbnail_size.pixels();
+                    let size = thumbnail_zoom::zoom_thumbnail_size(current, steps);
+                    // Saved once the wheel stops instead of on every notch
+                    if size != current {
+                        tasks.push(self.set_thumbnail_size(ThumbnailSize::from_pixels(size)));
+                        tasks.push(self.save_config_later());
+                    }
+                }
+                ViewMessage::ToggleTimeline => {
+                    if self.gallery_view.timeline.take().is_some() {
+                        // Back to the configured sort order
+                        self.gallery_view.reveal = None;
+                        tasks.push(self.reload_image_list());
+                    } else {
+                        self.gallery_view.timeline = Some(Timeline::default());
+                        tasks.push(self.load_timeline());
+                    }
+                }
+                ViewMessage::TimelineGrouping(grouping) => {
+                    if let Some(ref mut timeline) = self.gallery_view.timeline {
+                        timeline.set_grouping(grouping);
+                    } else {
+                        self.gallery_view.timeline = Some(Timeline::new(grouping));
+                        tasks.push(self.load_timeline());
+                    }
+                }
+                ViewMessage::TimelineLoaded {
+                    images,
+                    dates,
+                    known,
+                } => {
+                    // Dates of a list that was replaced in the meantime are dropped
+                    if Arc::ptr_eq(&images, &self.nav.shared_images())
+                        && let Some(ref mut timeline) = self.gallery_view.timeline
+                    {
+                        let order = timeline::timeline_order(&dates);
+                        let current = self.nav.current().cloned();
+                        let focused = self
+                            .gallery_view
+                            .focused_index
+                            .and_then(|idx| images.get(idx))
+                            .cloned();
+
+                        timeline.dates = order.iter().map(|&idx| dates[idx]).collect();
+                        timeline.known = known;
+                        self.nav.set_images(
+                            order.iter().map(|&idx| images[idx].clone()).collect(),
+                            current.as_deref(),
+                        );
+                        self.gallery_view.clear_selection();
+                        self.gallery_view.focused_index = focused
+                            .and_then(|path| self.nav.images().iter().position(|pos| *pos == path));
+                        self.reprioritize_thumbnails();
+                    }
+                }
+                ViewMessage::ToggleTimelineSection(idx) => {
+                    if let Some(ref mut timeline) = self.gallery_view.timeline
+                        && let Some(section) = timeline.sections().get(idx).copied()
+                        && timeline.toggle(&section)
+                        && let Some(focused) = self.gallery_view.focused_index
+                        && (section.start..section.start + section.len).contains(&focused)
+                    {
+                        // Hidden thumbnails can't keep focus, the header takes it
+                        self.gallery_view.focused_index = Some(section.start);
+                    }
+                }
+                ViewMessage::TimelineJump(idx) => {
+                    if let Some(ref timeline) = self.gallery_view.timeline
+                        && let Some(section) = timeline.sections().get(idx)
+                    {
+                        self.gallery_view.focused_index = Some(section.start);
+                        self.gallery_view.reveal = Some(section.start);
+                    }
+                }
+                ViewMessage::ToggleReader => {
+                    let reader = &mut self.gallery_view.reader;
+                    reader.is_active = !reader.is_active;
+                    self.image_state.zoom_fit();
+                    tasks.push(self.remember_reading_position());
+                    tasks.push(self.load_reader_pages());
+                    tasks.push(self.scroll_reader());
+                }
+                ViewMessage::ReaderLayout(layout) => {
+                    // Picking a layout also enters reader mode
+                    let reader = &mut self.gallery_view.reader;
+                    reader.layout = layout;
+                    reader.is_active = true;
+                    self.config.reader_layout = layout;
+                    if let Some(ref handler) = self.config_handler {
+                        let _ = self.config.write_entry(handler);
+                    }
+                    self.image_state.zoom_fit();
+                    tasks.push(self.load_reader_pages());
+                    tasks.push(self.scroll_reader());
+                }
+                ViewMessage::ToggleReadingDirection => {
+                    let direction = self.gallery_view.reader.direction.toggle();
+                    self.gallery_view.reader.direction = direction;
+                    self.config.reading_direction = direction;
+                    if let Some(ref handler) = self.config_handler {
+                        let _ = self.config.write_entry(handler);
+                    }
+                }
+                ViewMessage::ToggleCoverAlone => {
+                    let cover_alone = !self.gallery_view.reader.cover_alone;
+                    self.gallery_view.reader.cover_alone = cover_alone;
+                    self.config.reader_cover_alone = cover_alone;
+                    if let Some(ref handler) = self.config_handler {
+                        let _ = self.config.write_entry(handler);
                     }
+                    tasks.push(self.load_reader_pages());
                 }
-                ViewMessage::StopSlideshow => self.is_slideshow_active = false,
-                ViewMessage::ToggleSlideshow => {
-                    if self.is_slideshow_active {
-                        tasks.push(self.update(Message::View(ViewMessage::StopSlideshow)));
-                    } else {
-                        tasks.push(self.update(Message::View(ViewMessage::StartSlideshow)));
+                ViewMessage::ReaderScrolled(viewport) => {
+                    let bounds = viewport.bounds();
+                    let reader = &mut self.gallery_view.reader;
+                    reader.strip_width = bounds.width;
+
+                    let page = reader.page_at(
+                        &self.nav,
+                        &self.cache,
+                        viewport.absolute_offset().y,
+                        bounds.height,
+                    );
+                    if let Some(current) = self.nav.index()
+                        && current != page
+                    {
+                        self.nav_direction = Some(if page > current {
+                            NavDirection::Forward
+                        } else {
+                            NavDirection::Backward
+                        });
+                        self.nav.go_to(page);
+                        tasks.push(self.remember_reading_position());
+                        tasks.push(self.load_current_image());
+                        tasks.push(self.preload_images());
+                        tasks.push(self.update_title());
                     }
                 }
                 ViewMessage::GalleryScrollTo(offset_y) => {
@@ -1011,9 +2218,18 @@ impl Application for ImageViewer {
                         }
                         Err(err) => {
                             tracing::error!("Save failed: {err}");
+                            let (summary, body) = edit_error_text(true, &err);
+                            self.notifications
+                                .push(summary, body, Some(err.to_string()));
                         }
                     }
                 }
+                EditMessage::ApplyFailed(err) => {
+                    tracing::error!("Failed to apply edits: {err}");
+                    let (summary, body) = edit_error_text(false, &err);
+                    self.notifications
+                        .push(summary, body, Some(err.to_string()));
+                }
                 EditMessage::Undo => {
                     if self.edit_state.undo() {
                         tasks.push(self.reload_with_edits().map(Action::from));
@@ -1079,17 +2295,28 @@ impl Application for ImageViewer {
                     SettingsMessage::FitToWindow(fit) => self.config.fit_to_window = fit,
                     SettingsMessage::SmoothScaling(smooth) => self.config.smooth_scaling = smooth,
                     SettingsMessage::ThumbnailSize(size) => {
-                        self.config.thumbnail_size = size;
-                        // Clear thumbnail cache and for regeneration
-                        self.cache.clear_thumbnails();
-                        self.thumbnail_load_cursor = 0;
-                        tasks.push(self.load_thumbnails());
+                        tasks.push(self.set_thumbnail_size(size));
+                    }
+                    SettingsMessage::GalleryLayout(layout) => self.config.gallery_layout = layout,
+                    SettingsMessage::ViewerBackground(background) => {
+                        self.config.viewer_background = background
+                    }
+                    SettingsMessage::CustomBackground(text) => {
+                        // Partly typed colors are kept in the field until they parse
+                        if let Some(rgb) = color::parse_hex(&text) {
+                            self.config.custom_background = rgb;
+                        }
+                        self.background_input = text;
                     }
                     SettingsMessage::ShowHiddenFiles(show) => {
                         self.config.show_hidden_files = show;
                         // Reload the current directory with the setting
                         tasks.push(self.reload_image_list());
                     }
+                    SettingsMessage::IncludeExtensionless(include) => {
+                        self.config.include_extensionless_files = include;
+                        tasks.push(self.reload_image_list());
+                    }
                     SettingsMessage::SlideshowInterval(interval) => {
                         self.config.slideshow_interval = interval
                     }
@@ -1120,7 +2347,172 @@ impl Application for ImageViewer {
                     let _ = self.config.write_entry(handler);
                 }
             }
-            Message::KeyBind(action) => tasks.push(self.update(action.message())),
+            Message::Compare(msg) => match msg {
+                CompareMessage::Open => {
+                    let images = self.nav.images();
+                    let mut selected = self.gallery_view.selected.clone();
+                    selected.sort_unstable();
+
+                    let compare = if selected.len() >= 2 {
+                        // Multi-selection from the gallery
+                        CompareView::new(
+                            selected
+                                .iter()
+                                .filter_map(|&idx| images.get(idx).cloned())
+                                .collect(),
+                        )
+                    } else if let Some(path) = self.nav.current().cloned()
+                        && self.image_state.preview_image.is_some()
+                    {
+                        // Edited preview against the original
+                        CompareView::with_preview(path)
+                    } else {
+                        // Current or focused image and the one after it
+                        let start = self
+                            .nav
+                            .index()
+                            .or(self.gallery_view.focused_index)
+                            .unwrap_or(0);
+                        CompareView::new(images.iter().skip(start).take(2).cloned().collect())
+                    };
+
+                    if compare.paths.is_empty() {
+                        return Task::none();
+                    }
+
+                    for path in compare.paths.iter().cloned() {
+                        tasks.push(self.load_image(path));
+                    }
+                    // Zoom is shared by the panes, swapping the images keeps it
+                    if self.compare.is_none() {
+                        self.image_state.zoom_fit();
+                    }
+                    self.compare = Some(compare);
+                }
+                CompareMessage::SetMode(mode) => {
+                    if let Some(compare) = self.compare.as_mut() {
+                        compare.mode = mode;
+                    }
+                }
+                CompareMessage::Pan(x, y) => {
+                    if let Some(compare) = self.compare.as_mut() {
+                        compare.center = (x, y);
+                    }
+                }
+                CompareMessage::Swipe(position) => {
+                    if let Some(compare) = self.compare.as_mut() {
+                        compare.swipe = position;
+                    }
+                }
+                CompareMessage::Opacity(opacity) => {
+                    if let Some(compare) = self.compare.as_mut() {
+                        compare.opacity = opacity;
+                    }
+                }
+                CompareMessage::Close => self.compare = None,
+            },
+            Message::Duplicates(msg) => match msg {
+                DuplicatesMessage::Open => {
+                    self.duplicates = Some(DuplicatesView::new());
+                    tasks.push(self.update(Message::Duplicates(DuplicatesMessage::Scan)));
+                }
+                DuplicatesMessage::Scan => {
+                    if let Some(duplicates) = self.duplicates.as_mut() {
+                        let generation = duplicates.start_scan();
+                        let paths = self.nav.images().to_vec();
+
+                        return cosmic::task::future(async move {
+                            let entries = image::fingerprint_images(paths).await;
+                            Message::Duplicates(DuplicatesMessage::ScanComplete {
+                                generation,
+                                entries: Arc::new(entries),
+                            })
+                        });
+                    }
+                }
+                DuplicatesMessage::ScanComplete {
+                    generation,
+                    entries,
+                } => {
+                    if let Some(duplicates) = self.duplicates.as_mut()
+                        && duplicates.is_current_scan(generation)
+                    {
+                        duplicates.entries = entries;
+                        let generation = duplicates.start_grouping();
+                        tasks.push(Self::group_duplicates(
+                            generation,
+                            duplicates.entries.clone(),
+                            duplicates.threshold,
+                        ));
+                    }
+                }
+                DuplicatesMessage::SetThreshold(threshold) => {
+                    if let Some(duplicates) = self.duplicates.as_mut() {
+                        duplicates.threshold = threshold;
+                        // Regroup once the spin button settles, the fingerprints stay
+                        let generation = duplicates.start_grouping();
+                        tasks.push(delayed(
+                            REGROUP_DELAY,
+                            Message::Duplicates(DuplicatesMessage::Regroup(generation)),
+                        ));
+                    }
+                }
+                DuplicatesMessage::Regroup(generation) => {
+                    // A running scan groups with the latest threshold when it completes
+                    if let Some(duplicates) = self.duplicates.as_ref()
+                        && duplicates.is_current_grouping(generation)
+                        && !duplicates.is_scanning
+                    {
+                        tasks.push(Self::group_duplicates(
+                            generation,
+                            duplicates.entries.clone(),
+                            duplicates.threshold,
+                        ));
+                    }
+                }
+                DuplicatesMessage::Grouped { generation, groups } => {
+                    if let Some(duplicates) = self.duplicates.as_mut()
+                        && duplicates.is_current_grouping(generation)
+                    {
+                        duplicates.groups = groups;
+                        duplicates.is_scanning = false;
+                    }
+                }
+                DuplicatesMessage::Trash(path) => {
+                    return cosmic::task::future(async move {
+                        let result = trash::delete(&path)
+                            .map_err(|e| format!("Failed to move to trash: {}", e));
+                        Message::Duplicates(DuplicatesMessage::Trashed(path, result))
+                    });
+                }
+                DuplicatesMessage::Trashed(path, result) => match result {
+                    Ok(()) => {
+                        if let Some(duplicates) = self.duplicates.as_mut() {
+                            duplicates.remove_path(&path);
+                        }
+                        // The file watcher will handle updating the gallery
+                    }
+                    Err(err) => {
+                        tracing::error!("Delete failed: {}", err);
+                        self.notifications.push(
+                            fl!("error-delete-failed"),
+                            fl!("error-trash-hint"),
+                            Some(err),
+                        );
+                    }
+                },
+                DuplicatesMessage::Close => self.duplicates = None,
+            },
+            Message::KeyBind(action) => {
+                // Arrow keys turn towards the page on that side when reading right to left
+                let swap = self.nav.is_selected() && self.gallery_view.reader.is_right_to_left();
+                let action = match action {
+                    MenuAction::Next if swap => MenuAction::Prev,
+                    MenuAction::Prev if swap => MenuAction::Next,
+                    action => action,
+                };
+                tasks.push(self.update(action.message()));
+            }
             Message::Surface(action) => {
                 return cosmic::task::message(Action::Cosmic(cosmic::app::Action::Surface(action)));
             }
@@ -1157,6 +2549,69 @@ impl Application for ImageViewer {
                     }
                 });
             }
+            Message::Sidebar(msg) => match msg {
+                SidebarMessage::Toggle => {
+                    self.config.show_sidebar = !self.config.show_sidebar;
+                    if self.config.show_sidebar {
+                        // Volumes may have been mounted while it was hidden
+                        tasks.push(self.load_places());
+                    }
+                    if let Some(ref handler) = self.config_handler {
+                        let _ = self.config.write_entry(handler);
+                    }
+                }
+                SidebarMessage::PlacesLoaded { places, entries } => {
+                    self.sidebar.set_places(places, entries);
+                }
+                SidebarMessage::Expand(index) => {
+                    if let Some(node) = self.sidebar.tree.nodes().get(index) {
+                        let path = node.entry.path.clone();
+                        let include_hidden = self.config.show_hidden_files;
+                        tasks.push(cosmic::task::future(async move {
+                            let folders = nav::list_folders(&path, include_hidden).await;
+                            Message::Sidebar(SidebarMessage::FolderListed {
+                                index,
+                                path,
+                                folders,
+                            })
+                        }));
+                    }
+                }
+                SidebarMessage::Collapse(index) => self.sidebar.tree.collapse(index),
+                SidebarMessage::FolderListed {
+                    index,
+                    path,
+                    folders,
+                } => {
+                    if self.sidebar.tree.is_node(index, &path) {
+                        self.sidebar.tree.expand(index, folders);
+                    }
+                }
+                SidebarMessage::Open(path) => {
+                    if path.is_dir() {
+                        tasks.push(self.scan_and_nav(path));
+                    }
+                }
+                SidebarMessage::AddBookmark => {
+                    if let Some(folder) = self.sidebar.current.as_ref().and_then(|dir| dir.to_str())
+                    {
+                        self.config.add_bookmark(folder.to_string());
+                        if let Some(ref handler) = self.config_handler {
+                            let _ = self.config.write_entry(handler);
+                        }
+                        tasks.push(self.load_places());
+                    }
+                }
+                SidebarMessage::RemoveBookmark(path) => {
+                    if let Some(folder) = path.to_str() {
+                        self.config.remove_bookmark(folder);
+                        if let Some(ref handler) = self.config_handler {
+                            let _ = self.config.write_entry(handler);
+                        }
+                        tasks.push(self.load_places());
+                    }
+                }
+            },
             Message::OpenRecentFolder(idx) => {
                 if let Some(folder) = self.config.recent_folders.get(idx).cloned() {
                     let path = PathBuf::from(folder);
@@ -1172,7 +2627,16 @@ impl Application for ImageViewer {
                 }
             }
             Message::Cancelled => {}
-            Message::OpenError(why) => eprintln!("{why}"),
+            Message::SaveConfig(generation) => {
+                if self.pending_config_save == Some(generation) {
+                    self.save_config_now();
+                }
+            }
+            Message::Notification(msg) => match msg {
+                NotificationMessage::Dismiss(id) => self.notifications.dismiss(id),
+                NotificationMessage::ToggleDetails(id) => self.notifications.toggle_details(id),
+                NotificationMessage::Tick => self.notifications.expire(Instant::now()),
+            },
             Message::FilesSelected(paths) => {
                 if let Some(path) = paths.first() {
                     tasks.push(self.scan_and_nav(path.clone()));
@@ -1223,6 +2687,7 @@ impl Application for ImageViewer {
             }
             Message::WindowResized { width, height } => {
                 self.image_state.set_window_size(width, height);
+                self.gallery_view.reader.strip_width = 0.0;
 
                 // Update fit_zoom for current image
                 // Use preview image dimensions if available, otherwise use cached
@@ -1241,8 +2706,10 @@ impl Application for ImageViewer {
                     && !self.nav.is_empty()
                     && let Some(path) = self.nav.go_next().cloned()
                 {
+                    self.nav_direction = Some(NavDirection::Forward);
                     self.update_fit_zoom();
                     tasks.push(self.load_image(path.clone()));
+                    tasks.push(self.preload_images());
                 }
             }
             Message::SetWallpaper => {
@@ -1253,7 +2720,8 @@ impl Application for ImageViewer {
                         .and_then(|idx| self.nav.images().get(idx).cloned())
                 });
 
-                if let Some(path) = path {
+                // Members of an archive have no file of their own to point at
+                if let Some(path) = path.filter(|path| !nav::is_archive_member(path)) {
                     // On COSMIC, check the wallpaper behavior setting
                     if is_cosmic_desktop() {
                         match self.config.wallpaper_behavior {
@@ -1310,6 +2778,11 @@ impl Application for ImageViewer {
             Message::WallpaperResult(result) => {
                 if let Err(err) = result {
                     tracing::error!("Failed to set wallpaper: {}", err);
+                    self.notifications.push(
+                        fl!("error-wallpaper-failed"),
+                        fl!("error-wallpaper-hint"),
+                        Some(err),
+                    );
                 }
             }
             Message::DeleteImage => {
@@ -1320,7 +2793,8 @@ impl Application for ImageViewer {
                         .and_then(|idx| self.nav.images().get(idx).cloned())
                 });
 
-                if let Some(path) = path {
+                // Deleting a single page would mean rewriting the archive
+                if let Some(path) = path.filter(|path| !nav::is_archive_member(path)) {
                     self.delete_dialog = Some(path);
                 }
             }
@@ -1336,23 +2810,37 @@ impl Application for ImageViewer {
                         DeleteAction::Permanent => std::fs::remove_file(&path)
                             .map_err(|e| format!("Failed to delete file: {}", e)),
                     };
-                    Message::DeleteResult(result)
+                    Message::DeleteResult(action, result)
                 });
             }
             Message::CloseDeleteDialog => {
                 self.delete_dialog = None;
             }
-            Message::DeleteResult(result) => {
+            Message::DeleteResult(action, result) => {
                 if let Err(err) = result {
                     tracing::error!("Delete failed: {}", err);
+                    let body = match action {
+                        DeleteAction::Trash => fl!("error-trash-hint"),
+                        DeleteAction::Permanent => fl!("error-delete-hint"),
+                    };
+                    self.notifications
+                        .push(fl!("error-delete-failed"), body, Some(err));
                 }
                 // The file watcher will handle updating the gallery
             }
             Message::Quit => {
+                // Don't lose a change that is still waiting to be written
+                if self.pending_config_save.is_some() {
+                    self.save_config_now();
+                }
                 std::process::exit(0);
             }
         }
 
+        // Any message may have changed the zoom of a vector image
+        tasks.push(self.render_vector());
+        tasks.push(self.refresh_inspector());
+
         if tasks.is_empty() {
             Task::none()
         } else {
@@ -1388,6 +2876,30 @@ impl Application for ImageViewer {
             cosmic::iced::Subscription::none()
         };
 
+        // Kinetic panning timer, only while the image is coasting
+        let kinetic_sub = if self.image_state.is_kinetic() {
+            cosmic::iced::time::every(Duration::from_millis(KINETIC_TICK_MS))
+                .map(|_| Message::View(ViewMessage::KineticTick))
+        } else {
+            cosmic::iced::Subscription::none()
+        };
+
+        // Fades the fullscreen controls out after the pointer stops moving
+        let controls_sub = if self.image_state.controls_visible() {
+            cosmic::iced::time::every(Duration::from_millis(CONTROLS_TICK_MS))
+                .map(|_| Message::View(ViewMessage::ControlsTick))
+        } else {
+            cosmic::iced::Subscription::none()
+        };
+
+        // Expires notifications while any are shown
+        let notification_sub = if self.notifications.is_empty() {
+            cosmic::iced::Subscription::none()
+        } else {
+            cosmic::iced::time::every(Duration::from_secs(1))
+                .map(|_| Message::Notification(NotificationMessage::Tick))
+        };
+
         cosmic::iced::Subscription::batch([
             cosmic::iced::keyboard::on_key_press(key_press_handler),
             cosmic::iced::window::events().map(|(_, event)| {
@@ -1402,6 +2914,9 @@ impl Application for ImageViewer {
             }),
             watcher_sub,
             slideshow_sub,
+            kinetic_sub,
+            controls_sub,
+            notification_sub,
         ])
     }
 
@@ -1415,6 +2930,118 @@ impl Application for ImageViewer {
 }
 
 impl ImageViewer {
+    /// The window content below the notifications
+    fn content_view(&self) -> Element<'_, Message> {
+        let gallery = self.gallery_view.view(
+            &self.nav,
+            &self.cache,
+            &self.config,
+            &self.image_state,
+            &self.edit_state,
+            self.current_format(),
+        );
+
+        let gallery: Element<'_, Message> =
+            if self.config.show_sidebar && !self.image_state.fullscreen {
+                cosmic::widget::row()
+                    .push(self.sidebar.view(&self.config.bookmarks))
+                    .push(gallery)
+                    .into()
+            } else {
+                gallery
+            };
+
+        // Overlay crop dialog if active (takes priority over other dialogs)
+        if self.edit_state.is_cropping
+            && let Some(path) = self.nav.current()
+            && let Some(cached) = self.cache.get_full(path)
+        {
+            let dialog = self.crop_dialog_view(&cached);
+
+            // Backdrop that doesn't close - crop requires explicit Apply/Cancel
+            let backdrop = cosmic::widget::mouse_area(
+                cosmic::widget::container(cosmic::widget::Space::new(
+                    cosmic::iced::Length::Fill,
+                    cosmic::iced::Length::Fill,
+                ))
+                .width(cosmic::iced::Length::Fill)
+                .height(cosmic::iced::Length::Fill)
+                .class(cosmic::theme::Container::Transparent),
+            )
+            .on_press(Message::View(ViewMessage::ImageEditEvent)); // No-op, just captures clicks
+
+            return cosmic::iced_widget::stack![gallery, backdrop, dialog].into();
+        }
+
+        if let Some(compare) = &self.compare {
+            let backdrop = cosmic::widget::mouse_area(
+                cosmic::widget::container(cosmic::widget::Space::new(
+                    cosmic::iced::Length::Fill,
+                    cosmic::iced::Length::Fill,
+                ))
+                .width(cosmic::iced::Length::Fill)
+                .height(cosmic::iced::Length::Fill)
+                .class(cosmic::theme::Container::Transparent),
+            )
+            .on_press(Message::Compare(CompareMessage::Close));
+
+            let content = compare.view(&self.cache, &self.image_state);
+            return cosmic::iced_widget::stack![gallery, backdrop, content].into();
+        }
+
+        // Duplicate finder covers the gallery like the image modal
+        if let Some(duplicates) = &self.duplicates {
+            let backdrop = cosmic::widget::mouse_area(
+                cosmic::widget::container(cosmic::widget::Space::new(
+                    cosmic::iced::Length::Fill,
+                    cosmic::iced::Length::Fill,
+                ))
+                .width(cosmic::iced::Length::Fill)
+                .height(cosmic::iced::Length::Fill)
+                .class(cosmic::theme::Container::Transparent),
+            )
+            .on_press(Message::Duplicates(DuplicatesMessage::Close));
+
+            return cosmic::iced_widget::stack![gallery, backdrop, duplicates.view(&self.cache)]
+                .into();
+        }
+
+        // Overlay wallpaper dialog if active
+        if let Some(path) = &self.wallpaper_dialog {
+            let dialog = self.wallpaper_dialog_view(path);
+
+            let backdrop = cosmic::widget::mouse_area(
+                cosmic::widget::container(cosmic::widget::Space::new(
+                    cosmic::iced::Length::Fill,
+                    cosmic::iced::Length::Fill,
+                ))
+                .width(cosmic::iced::Length::Fill)
+                .height(cosmic::iced::Length::Fill)
+                .class(cosmic::theme::Container::Transparent),
+            )
+            .on_press(Message::CloseWallpaperDialog);
+
+            cosmic::iced_widget::stack![gallery, backdrop, dialog].into()
+        } else if let Some(path) = &self.delete_dialog {
+            let dialog = self.delete_dialog_view(path);
+
+            let backdrop = cosmic::widget::mouse_area(
+                cosmic::widget::container(cosmic::widget::Space::new(
+                    cosmic::iced::Length::Fill,
+                    cosmic::iced::Length::Fill,
+                ))
+                .width(cosmic::iced::Length::Fill)
+                .height(cosmic::iced::Length::Fill)
+                .class(cosmic::theme::Container::Transparent),
+            )
+            .on_press(Message::CloseDeleteDialog);
+
+            cosmic::iced_widget::stack![gallery, backdrop, dialog].into()
+        } else {
+            gallery
+        }
+    }
+
     fn about_page(&self) -> Element<'_, Message> {
         column()
             .push(text::title3(fl!("app-title")))
@@ -1539,7 +3166,7 @@ impl ImageViewer {
     }
 
     fn crop_dialog_view(&self, cached: &viewer_image::CachedImage) -> Element<'_, Message> {
-        use crate::widgets::crop_widget;
+        use crate::widgets::{checkerboard, crop_widget};
         use cosmic::iced::Length;
         use cosmic::widget::icon;
 
@@ -1567,6 +3194,15 @@ impl ImageViewer {
         // Self-contained crop widget that handles image rendering and all crop UI
         let crop = crop_widget(handle, width, height, &self.edit_state.crop_selection);
 
+        // Same background as the modal so transparent edges stay visible while cropping
+        let crop: Element<'static, Message> =
+            if self.config.viewer_background == ViewerBackground::Checkerboard {
+                cosmic::iced_widget::stack![checkerboard(), crop].into()
+            } else {
+                crop.into()
+            };
+        let crop = gallery::backdrop(crop, self.config.background_color());
+
         // Footer with Apply/Cancel buttons
         let cancel_btn =
             button::standard(fl!("crop-cancel")).on_press(Message::Edit(EditMessage::CancelCrop));
@@ -1590,7 +3226,7 @@ impl ImageViewer {
         cosmic::widget::container(
             column()
                 .push(header)
-                .push(cosmic::Element::from(crop))
+                .push(crop)
                 .push(footer)
                 .width(Length::Fill)
                 .height(Length::Fill),
@@ -1604,6 +3240,52 @@ impl ImageViewer {
     fn settings_page(&self) -> Element<'_, Message> {
         let spacing = cosmic::theme::active().cosmic().spacing;
 
+        // View settings section, with the custom color field only while it is picked
+        let mut view_section = settings::section()
+            .title(fl!("settings-view"))
+            .add(settings::item(
+                fl!("settings-default-zoom"),
+                slider(0.1..=5.0, self.config.default_zoom, |zoom| {
+                    Message::Settings(SettingsMessage::DefaultZoom(zoom))
+                })
+                .step(0.1),
+            ))
+            .add(settings::item(
+                fl!("settings-fit-to-window"),
+                toggler(self.config.fit_to_window)
+                    .on_toggle(|fit| Message::Settings(SettingsMessage::FitToWindow(fit))),
+            ))
+            .add(settings::item(
+                fl!("settings-smooth-scaling"),
+                toggler(self.config.smooth_scaling)
+                    .on_toggle(|smooth| Message::Settings(SettingsMessage::SmoothScaling(smooth))),
+            ))
+            .add(settings::item(
+                fl!("settings-background"),
+                dropdown(
+                    ViewerBackground::ALL
+                        .iter()
+                        .map(|b| background_label(*b))
+                        .collect::<Vec<_>>(),
+                    ViewerBackground::ALL
+                        .iter()
+                        .position(|b| *b == self.config.viewer_background),
+                    |idx| {
+                        Message::Settings(SettingsMessage::ViewerBackground(
+                            ViewerBackground::ALL[idx],
+                        ))
+                    },
+                ),
+            ));
+        if self.config.viewer_background == ViewerBackground::Custom {
+            view_section = view_section.add(settings::item(
+                fl!("settings-background-color"),
+                text_input("#303038", &self.background_input)
+                    .on_input(|text| Message::Settings(SettingsMessage::CustomBackground(text)))
+                    .width(cosmic::iced::Length::Fixed(120.0)),
+            ));
+        }
+
         let mut sections = vec![
             // Appearance section
             settings::section()
@@ -1622,28 +3304,7 @@ impl ImageViewer {
                     ),
                 ))
                 .into(),
-            // View settings section
-            settings::section()
-                .title(fl!("settings-view"))
-                .add(settings::item(
-                    fl!("settings-default-zoom"),
-                    slider(0.1..=5.0, self.config.default_zoom, |zoom| {
-                        Message::Settings(SettingsMessage::DefaultZoom(zoom))
-                    })
-                    .step(0.1),
-                ))
-                .add(settings::item(
-                    fl!("settings-fit-to-window"),
-                    toggler(self.config.fit_to_window)
-                        .on_toggle(|fit| Message::Settings(SettingsMessage::FitToWindow(fit))),
-                ))
-                .add(settings::item(
-                    fl!("settings-smooth-scaling"),
-                    toggler(self.config.smooth_scaling).on_toggle(|smooth| {
-                        Message::Settings(SettingsMessage::SmoothScaling(smooth))
-                    }),
-                ))
-                .into(),
+            view_section.into(),
             // Gallery settings section
             settings::section()
                 .title(fl!("settings-gallery"))
@@ -1676,12 +3337,35 @@ impl ImageViewer {
                         ))
                         .spacing(spacing.space_xxs),
                 ))
+                .add(settings::item(
+                    fl!("settings-gallery-layout"),
+                    column()
+                        .push(radio(
+                            text::body(fl!("settings-layout-grid")),
+                            GalleryLayout::Grid,
+                            Some(self.config.gallery_layout),
+                            |layout| Message::Settings(SettingsMessage::GalleryLayout(layout)),
+                        ))
+                        .push(radio(
+                            text::body(fl!("settings-layout-justified")),
+                            GalleryLayout::Justified,
+                            Some(self.config.gallery_layout),
+                            |layout| Message::Settings(SettingsMessage::GalleryLayout(layout)),
+                        ))
+                        .spacing(spacing.space_xxs),
+                ))
                 .add(settings::item(
                     fl!("settings-show-hidden"),
                     toggler(self.config.show_hidden_files).on_toggle(|show| {
                         Message::Settings(SettingsMessage::ShowHiddenFiles(show))
                     }),
                 ))
+                .add(settings::item(
+                    fl!("settings-include-extensionless"),
+                    toggler(self.config.include_extensionless_files).on_toggle(|include| {
+                        Message::Settings(SettingsMessage::IncludeExtensionless(include))
+                    }),
+                ))
                 .into(),
             // Slideshow settings section
             settings::section()
@@ -1768,6 +3452,22 @@ impl ImageViewer {
 
             content = content.push(text::body(format!("Path: {}", path.display())));
 
+            if let Some(&format) = self.formats.get(path) {
+                content = content.push(text::body(format!("Format: {}", format.name())));
+
+                if ImageFormat::extension_mismatch(path, format) {
+                    let extension = path
+                        .extension()
+                        .map(|ext| ext.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    content = content.push(text::body(fl!(
+                        "info-extension-mismatch",
+                        format = format.name(),
+                        extension = extension
+                    )));
+                }
+            }
+
             // Use preview image dimensions if available, otherwise use cached
             if let Some(ref preview) = self.image_state.preview_image {
                 content = content.push(text::body(format!(
@@ -1780,6 +3480,16 @@ impl ImageViewer {
                     cached.width, cached.height
                 )));
             }
+
+            if let Some(info) = self.layer_infos.get(path) {
+                content = content
+                    .push(text::body(fl!(
+                        "info-canvas",
+                        width = info.canvas_width,
+                        height = info.canvas_height
+                    )))
+                    .push(text::body(fl!("info-layers", count = info.layer_count)));
+            }
         } else {
             content = content.push(text::body("No image loaded"));
         }
@@ -1852,6 +3562,26 @@ async fn set_wallpaper_cosmic(path: &std::path::Path) -> Result<(), String> {
     Ok(())
 }
 
+/// Deliver `message` once `delay` has passed
+fn delayed(delay: Duration, message: Message) -> Task<Action<Message>> {
+    cosmic::task::future(async move {
+        tokio::time::sleep(delay).await;
+        message
+    })
+}
+
+/// Name of a viewer background in the settings dropdown
+fn background_label(background: ViewerBackground) -> String {
+    match background {
+        ViewerBackground::Theme => fl!("settings-background-theme"),
+        ViewerBackground::Black => fl!("settings-background-black"),
+        ViewerBackground::White => fl!("settings-background-white"),
+        ViewerBackground::Gray => fl!("settings-background-gray"),
+        ViewerBackground::Custom => fl!("settings-background-custom"),
+        ViewerBackground::Checkerboard => fl!("settings-background-checkerboard"),
+    }
+}
+
 fn is_cosmic_desktop() -> bool {
     std::env::var("XDG_CURRENT_DESKTOP")
         .map(|d| d.to_uppercase().contains("COSMIC"))
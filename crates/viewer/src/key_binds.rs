@@ -1,4 +1,7 @@
-use crate::message::{ContextPage, EditMessage, Message, NavMessage, SettingsMessage, ViewMessage};
+use crate::message::{
+    CompareMessage, ContextPage, DuplicatesMessage, EditMessage, Message, NavMessage,
+    SettingsMessage, SidebarMessage, ViewMessage,
+};
 use cosmic::{
     iced::keyboard::{Key, key::Named},
     widget::menu::{
@@ -6,8 +9,9 @@ use cosmic::{
         key_bind::{KeyBind, Modifier},
     },
 };
+use shared::timeline::TimelineGrouping;
 use std::collections::HashMap;
-use viewer_config::{SortMode, SortOrder};
+use viewer_config::{ReaderLayout, SortMode, SortOrder};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MenuAction {
@@ -24,6 +28,8 @@ pub enum MenuAction {
     Prev,
     First,
     Last,
+    NextPage,
+    PrevPage,
     CloseModal,
     About,
     Settings,
@@ -32,8 +38,10 @@ pub enum MenuAction {
     FocusDown,
     SelectFocused,
     ToggleSlideshow,
+    Compare,
     SetWallpaper,
     DeleteImage,
+    FindDuplicates,
     Rotate90,
     Rotate180,
     FlipHorizontal,
@@ -47,6 +55,16 @@ pub enum MenuAction {
     SortBySize,
     SortAscending,
     SortDescending,
+    ToggleTimeline,
+    TimelineGrouping(TimelineGrouping),
+    ToggleSidebar,
+    AddBookmark,
+    ToggleReader,
+    ToggleInspector,
+    CycleBackground,
+    ReaderLayout(ReaderLayout),
+    ToggleReadingDirection,
+    ToggleCoverAlone,
     OpenRecentFolder(usize),
     ClearRecentFolders,
 }
@@ -66,6 +84,8 @@ impl MenuAction {
             MenuAction::Prev => Message::Nav(NavMessage::Prev),
             MenuAction::First => Message::Nav(NavMessage::First),
             MenuAction::Last => Message::Nav(NavMessage::Last),
+            MenuAction::NextPage => Message::View(ViewMessage::NextPage),
+            MenuAction::PrevPage => Message::View(ViewMessage::PrevPage),
             MenuAction::CloseModal => Message::View(ViewMessage::CloseModal),
             MenuAction::About => Message::ToggleContextPage(ContextPage::About),
             MenuAction::Settings => Message::ToggleContextPage(ContextPage::Settings),
@@ -74,8 +94,10 @@ impl MenuAction {
             MenuAction::FocusDown => Message::View(ViewMessage::FocusDown),
             MenuAction::SelectFocused => Message::View(ViewMessage::SelectFocused),
             MenuAction::ToggleSlideshow => Message::View(ViewMessage::ToggleSlideshow),
+            MenuAction::Compare => Message::Compare(CompareMessage::Open),
             MenuAction::SetWallpaper => Message::SetWallpaper,
             MenuAction::DeleteImage => Message::DeleteImage,
+            MenuAction::FindDuplicates => Message::Duplicates(DuplicatesMessage::Open),
             MenuAction::Rotate90 => Message::Edit(EditMessage::Rotate90),
             MenuAction::Rotate180 => Message::Edit(EditMessage::Rotate180),
             MenuAction::FlipHorizontal => Message::Edit(EditMessage::FlipHorizontal),
@@ -93,6 +115,20 @@ impl MenuAction {
             MenuAction::SortDescending => {
                 Message::Settings(SettingsMessage::SortOrder(SortOrder::Descending))
             }
+            MenuAction::ToggleTimeline => Message::View(ViewMessage::ToggleTimeline),
+            MenuAction::TimelineGrouping(grouping) => {
+                Message::View(ViewMessage::TimelineGrouping(grouping))
+            }
+            MenuAction::ToggleSidebar => Message::Sidebar(SidebarMessage::Toggle),
+            MenuAction::AddBookmark => Message::Sidebar(SidebarMessage::AddBookmark),
+            MenuAction::ToggleReader => Message::View(ViewMessage::ToggleReader),
+            MenuAction::ToggleInspector => Message::View(ViewMessage::ToggleInspector),
+            MenuAction::CycleBackground => Message::View(ViewMessage::CycleBackground),
+            MenuAction::ReaderLayout(layout) => Message::View(ViewMessage::ReaderLayout(layout)),
+            MenuAction::ToggleReadingDirection => {
+                Message::View(ViewMessage::ToggleReadingDirection)
+            }
+            MenuAction::ToggleCoverAlone => Message::View(ViewMessage::ToggleCoverAlone),
             MenuAction::OpenRecentFolder(idx) => Message::OpenRecentFolder(idx),
             MenuAction::ClearRecentFolders => Message::ClearRecentFolders,
         }
@@ -240,6 +276,23 @@ pub fn init_key_binds() -> HashMap<KeyBind, MenuAction> {
         MenuAction::Last,
     );
 
+    // Pages of multi-page files
+    binds.insert(
+        KeyBind {
+            modifiers: vec![],
+            key: Key::Named(Named::PageDown),
+        },
+        MenuAction::NextPage,
+    );
+
+    binds.insert(
+        KeyBind {
+            modifiers: vec![],
+            key: Key::Named(Named::PageUp),
+        },
+        MenuAction::PrevPage,
+    );
+
     binds.insert(
         KeyBind {
             modifiers: vec![],
@@ -256,6 +309,62 @@ pub fn init_key_binds() -> HashMap<KeyBind, MenuAction> {
         MenuAction::ToggleSlideshow,
     );
 
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl, Modifier::Shift],
+            key: Key::Character("c".into()),
+        },
+        MenuAction::Compare,
+    );
+
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl],
+            key: Key::Character("t".into()),
+        },
+        MenuAction::ToggleTimeline,
+    );
+
+    binds.insert(
+        KeyBind {
+            modifiers: vec![],
+            key: Key::Named(Named::F9),
+        },
+        MenuAction::ToggleSidebar,
+    );
+
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl],
+            key: Key::Character("d".into()),
+        },
+        MenuAction::AddBookmark,
+    );
+
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl],
+            key: Key::Character("m".into()),
+        },
+        MenuAction::ToggleReader,
+    );
+
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl],
+            key: Key::Character("h".into()),
+        },
+        MenuAction::ToggleInspector,
+    );
+
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl],
+            key: Key::Character("b".into()),
+        },
+        MenuAction::CycleBackground,
+    );
+
     // Info
     binds.insert(
         KeyBind {
@@ -283,6 +392,14 @@ pub fn init_key_binds() -> HashMap<KeyBind, MenuAction> {
         MenuAction::DeleteImage,
     );
 
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl, Modifier::Shift],
+            key: Key::Character("d".into()),
+        },
+        MenuAction::FindDuplicates,
+    );
+
     binds.insert(
         KeyBind {
             modifiers: vec![Modifier::Ctrl],
@@ -1,4 +1,4 @@
-use crate::{fl, key_binds::MenuAction, message::Message};
+use crate::{fl, key_binds::MenuAction, message::Message, views::Reader};
 use cosmic::{
     Core, Element,
     widget::{
@@ -6,7 +6,9 @@ use cosmic::{
         responsive_menu_bar,
     },
 };
+use shared::timeline::TimelineGrouping;
 use std::{collections::HashMap, path::Path, sync::LazyLock};
+use viewer_config::{ReaderLayout, ReadingDirection};
 
 static MENU_ID: LazyLock<cosmic::widget::Id> =
     LazyLock::new(|| cosmic::widget::Id::new("responsive-menu"));
@@ -42,6 +44,12 @@ fn build_file_menu(recent_folders: &[String]) -> Vec<menu::Item<MenuAction, Stri
         items.push(menu::Item::folder(fl!("menu-recent-folders"), folder_items).width(280));
     }
 
+    items.push(menu::Item::button(
+        fl!("menu-add-bookmark"),
+        None,
+        MenuAction::AddBookmark,
+    ));
+
     items.push(menu::Item::divider());
     items.push(menu::Item::button(fl!("menu-save"), None, MenuAction::Save));
     items.push(menu::Item::button(
@@ -60,6 +68,11 @@ fn build_file_menu(recent_folders: &[String]) -> Vec<menu::Item<MenuAction, Stri
         None,
         MenuAction::DeleteImage,
     ));
+    items.push(menu::Item::button(
+        fl!("menu-find-duplicates"),
+        None,
+        MenuAction::FindDuplicates,
+    ));
     items.push(menu::Item::divider());
     items.push(menu::Item::button(
         fl!("menu-settings"),
@@ -76,6 +89,9 @@ pub fn menu_bar<'a>(
     core: &Core,
     key_binds: &HashMap<KeyBind, MenuAction>,
     is_slideshow_active: bool,
+    is_timeline_shown: bool,
+    is_sidebar_shown: bool,
+    reader: &Reader,
     recent_folders: &[String],
 ) -> Element<'a, Message> {
     let file_menu = build_file_menu(recent_folders);
@@ -121,7 +137,19 @@ pub fn menu_bar<'a>(
                         menu::Item::button(fl!("menu-zoom-reset"), None, MenuAction::ZoomReset),
                         menu::Item::button(fl!("menu-zoom-fit"), None, MenuAction::ZoomFit),
                         menu::Item::divider(),
+                        menu::Item::button(fl!("menu-next-page"), None, MenuAction::NextPage),
+                        menu::Item::button(fl!("menu-prev-page"), None, MenuAction::PrevPage),
+                        menu::Item::divider(),
                         menu::Item::button(fl!("menu-fullscreen"), None, MenuAction::Fullscreen),
+                        menu::Item::button(
+                            if is_sidebar_shown {
+                                fl!("menu-sidebar-hide")
+                            } else {
+                                fl!("menu-sidebar-show")
+                            },
+                            None,
+                            MenuAction::ToggleSidebar,
+                        ),
                         menu::Item::button(
                             if is_slideshow_active {
                                 fl!("menu-slideshow-stop")
@@ -131,6 +159,17 @@ pub fn menu_bar<'a>(
                             None,
                             MenuAction::ToggleSlideshow,
                         ),
+                        menu::Item::button(fl!("menu-compare"), None, MenuAction::Compare),
+                        menu::Item::button(
+                            fl!("menu-inspector"),
+                            None,
+                            MenuAction::ToggleInspector,
+                        ),
+                        menu::Item::button(
+                            fl!("menu-background"),
+                            None,
+                            MenuAction::CycleBackground,
+                        ),
                         menu::Item::divider(),
                         menu::Item::button(fl!("menu-sort-name"), None, MenuAction::SortByName),
                         menu::Item::button(fl!("menu-sort-date"), None, MenuAction::SortByDate),
@@ -146,6 +185,73 @@ pub fn menu_bar<'a>(
                             None,
                             MenuAction::SortDescending,
                         ),
+                        menu::Item::divider(),
+                        menu::Item::button(
+                            if is_timeline_shown {
+                                fl!("menu-timeline-hide")
+                            } else {
+                                fl!("menu-timeline-show")
+                            },
+                            None,
+                            MenuAction::ToggleTimeline,
+                        ),
+                        menu::Item::button(
+                            fl!("menu-timeline-day"),
+                            None,
+                            MenuAction::TimelineGrouping(TimelineGrouping::Day),
+                        ),
+                        menu::Item::button(
+                            fl!("menu-timeline-month"),
+                            None,
+                            MenuAction::TimelineGrouping(TimelineGrouping::Month),
+                        ),
+                        menu::Item::button(
+                            fl!("menu-timeline-year"),
+                            None,
+                            MenuAction::TimelineGrouping(TimelineGrouping::Year),
+                        ),
+                        menu::Item::divider(),
+                        menu::Item::button(
+                            if reader.is_active {
+                                fl!("menu-reader-exit")
+                            } else {
+                                fl!("menu-reader-enter")
+                            },
+                            None,
+                            MenuAction::ToggleReader,
+                        ),
+                        menu::Item::button(
+                            fl!("menu-reader-single"),
+                            None,
+                            MenuAction::ReaderLayout(ReaderLayout::Single),
+                        ),
+                        menu::Item::button(
+                            fl!("menu-reader-spread"),
+                            None,
+                            MenuAction::ReaderLayout(ReaderLayout::Spread),
+                        ),
+                        menu::Item::button(
+                            fl!("menu-reader-continuous"),
+                            None,
+                            MenuAction::ReaderLayout(ReaderLayout::Continuous),
+                        ),
+                        menu::Item::button(
+                            match reader.direction {
+                                ReadingDirection::LeftToRight => fl!("menu-reader-rtl"),
+                                ReadingDirection::RightToLeft => fl!("menu-reader-ltr"),
+                            },
+                            None,
+                            MenuAction::ToggleReadingDirection,
+                        ),
+                        menu::Item::button(
+                            if reader.cover_alone {
+                                fl!("menu-reader-cover-paired")
+                            } else {
+                                fl!("menu-reader-cover-alone")
+                            },
+                            None,
+                            MenuAction::ToggleCoverAlone,
+                        ),
                     ],
                 ),
                 (
@@ -1,8 +1,19 @@
-use cosmic::widget::image::Handle;
+use cosmic::{iced::advanced::image, iced_widget::scrollable::Viewport, widget::image::Handle};
+use shared::{
+    date::CalendarDate, formats::ImageFormat, histogram::Histogram, places::FolderEntry,
+    timeline::TimelineGrouping,
+};
 use std::{path::PathBuf, sync::Arc};
-use viewer_config::{AppTheme, SortMode, SortOrder, ThumbnailSize, WallpaperBehavior};
+use viewer_config::{
+    AppTheme, GalleryLayout, ReaderLayout, SortMode, SortOrder, ThumbnailSize, ViewerBackground,
+    WallpaperBehavior,
+};
+use viewer_image::{
+    DuplicateEntry, DuplicateGroup, ImagePyramid, KnownDates, LayerInfo, LoadError, LoadedImage,
+    edit::EditError,
+};
 
-pub use crate::{key_binds::MenuAction, widgets::DragHandle};
+pub use crate::{key_binds::MenuAction, views::sidebar::Place, widgets::DragHandle};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WallpaperTarget {
@@ -30,6 +41,9 @@ pub enum Message {
     View(ViewMessage),
     Edit(EditMessage),
     Settings(SettingsMessage),
+    Duplicates(DuplicatesMessage),
+    Compare(CompareMessage),
+    Sidebar(SidebarMessage),
     KeyBind(MenuAction),
     ToggleContextPage(ContextPage),
     OpenFileDialog,
@@ -37,13 +51,18 @@ pub enum Message {
     OpenRecentFolder(usize),
     ClearRecentFolders,
     Cancelled,
-    OpenError(Arc<String>),
+    /// Write the config unless a later change rescheduled the write
+    SaveConfig(u64),
+    Notification(NotificationMessage),
     FilesSelected(Vec<PathBuf>),
     OpenPath(PathBuf),
     SystemThemeChanged,
     ConfigChanged,
     WatcherEvent(crate::watcher::WatcherEvent),
-    WindowResized { width: f32, height: f32 },
+    WindowResized {
+        width: f32,
+        height: f32,
+    },
     SlideshowTick,
     SetWallpaper,
     ShowWallpaperDialog(std::path::PathBuf),
@@ -54,7 +73,7 @@ pub enum Message {
     ShowDeleteDialog(std::path::PathBuf),
     ConfirmDelete(std::path::PathBuf, DeleteAction),
     CloseDeleteDialog,
-    DeleteResult(Result<(), String>),
+    DeleteResult(DeleteAction, Result<(), String>),
     Quit,
     Surface(cosmic::surface::Action),
 }
@@ -67,6 +86,51 @@ pub enum ImageMessage {
         handle: Handle,
         width: u32,
         height: u32,
+        /// Pages in the file, more than one for multi-page TIFFs, icons and HEIF collections
+        page_count: usize,
+        /// Only part of a truncated or corrupt file could be decoded
+        partial: bool,
+        /// Tiles of a very large image, decoded together with its preview
+        pyramid: Option<Arc<ImagePyramid>>,
+    },
+    /// A page other than the first of a multi-page file
+    PageLoaded {
+        path: PathBuf,
+        page: usize,
+        handle: Handle,
+        width: u32,
+        height: u32,
+    },
+    /// The zoom stopped changing, render the current vector image if `generation` is the latest
+    RenderVector(u64),
+    /// A vector image rasterized at `scale` times its intrinsic size
+    VectorRendered {
+        generation: u64,
+        path: PathBuf,
+        scale: f32,
+        handle: Handle,
+        width: u32,
+        height: u32,
+    },
+    VectorFailed {
+        generation: u64,
+        path: PathBuf,
+        error: Arc<LoadError>,
+    },
+    /// Format of a file as detected from its content, `None` when unrecognised
+    FormatDetected {
+        path: PathBuf,
+        format: Option<ImageFormat>,
+    },
+    /// Canvas size and layer count of a PSD, XCF or Krita document
+    LayerInfoReady {
+        path: PathBuf,
+        info: LayerInfo,
+    },
+    /// Thumbnails for the page strip of a multi-page file
+    PageThumbnailsReady {
+        path: PathBuf,
+        thumbnails: Vec<LoadedImage>,
     },
     /// Preview of edited image - should not be cached
     EditedPreview {
@@ -75,15 +139,44 @@ pub enum ImageMessage {
         width: u32,
         height: u32,
     },
+    /// A prefetch left the window or would exceed the memory budget
+    PrefetchSkipped {
+        path: PathBuf,
+        cancelled: bool,
+    },
+    /// Reduced-resolution decode shown until `Loaded` arrives
+    PreviewReady {
+        path: PathBuf,
+        handle: Handle,
+        width: u32,
+        height: u32,
+    },
+    /// Full resolution tiles of a very large image
+    PyramidLoaded {
+        path: PathBuf,
+        pyramid: Arc<ImagePyramid>,
+    },
     LoadFailed {
         path: PathBuf,
-        error: String,
+        error: Arc<LoadError>,
+        /// Tell the user; set for decodes of the image on screen, not for
+        /// previews, thumbnails and prefetches
+        report: bool,
     },
     ThumbnailReady {
         path: PathBuf,
         handle: Handle,
         width: u32,
         height: u32,
+        /// Size the thumbnail was requested at
+        decoded_at: u32,
+        /// Only part of a truncated or corrupt file could be decoded
+        partial: bool,
+    },
+    /// No thumbnail could be decoded, the tile shows a placeholder instead
+    ThumbnailFailed {
+        path: PathBuf,
+        error: Arc<LoadError>,
     },
     Clear,
 }
@@ -104,6 +197,8 @@ pub enum NavMessage {
     },
     GalleryFocus(usize),
     GallerySelect(usize),
+    /// Ctrl+click on a thumbnail, adds or removes it from the multi-selection
+    GalleryToggleSelect(usize),
 }
 
 #[derive(Debug, Clone)]
@@ -122,6 +217,110 @@ pub enum ViewMessage {
     ToggleSlideshow,
     ImageEditEvent,
     GalleryScrollTo(f32),
+    /// The gallery scrollable moved; visible thumbnails are decoded first
+    GalleryScrolled(Viewport),
+    /// Resize gallery thumbnails by a number of zoom steps, positive to enlarge
+    ZoomThumbnails(f32),
+    /// Show or hide the gallery grouped by capture date
+    ToggleTimeline,
+    TimelineGrouping(TimelineGrouping),
+    /// Capture dates of the image list they were read for
+    TimelineLoaded {
+        images: Arc<[PathBuf]>,
+        dates: Vec<CalendarDate>,
+        known: Arc<KnownDates>,
+    },
+    /// Collapse or expand a timeline section, by section index
+    ToggleTimelineSection(usize),
+    /// Scroll to a timeline section from the year scrubber
+    TimelineJump(usize),
+    /// Enter or leave comic reading mode
+    ToggleReader,
+    ReaderLayout(ReaderLayout),
+    ToggleReadingDirection,
+    ToggleCoverAlone,
+    /// The continuous reader strip moved; the page at the reading line becomes current
+    ReaderScrolled(Viewport),
+    /// Show another page of a multi-page file
+    NextPage,
+    PrevPage,
+    GoToPage(usize),
+    /// Zoom by a factor anchored at a point of the image area
+    ZoomAt {
+        factor: f32,
+        x: f32,
+        y: f32,
+    },
+    /// Double-click: toggle between 100% and fit
+    ToggleZoomAt {
+        x: f32,
+        y: f32,
+    },
+    PanStart,
+    Pan {
+        dx: f32,
+        dy: f32,
+    },
+    PanEnd,
+    KineticTick,
+    ImageScrolled {
+        x: f32,
+        y: f32,
+    },
+    /// Show or hide the histogram and pixel inspector
+    ToggleInspector,
+    /// The pointer moved over a point of the image area while inspecting
+    InspectAt {
+        x: f32,
+        y: f32,
+    },
+    /// Histogram of the image decoded into the handle with id `source`
+    HistogramReady {
+        source: image::Id,
+        histogram: Arc<Histogram>,
+    },
+    /// Show or hide the clipped pixels overlay
+    ToggleClipping,
+    ClippingMaskReady {
+        source: image::Id,
+        mask: Handle,
+    },
+    /// Copy the inspected pixel's color to the clipboard
+    CopyColor,
+    /// Center the zoomed image on a point picked in the navigator, as fractions of its size
+    MinimapPan {
+        x: f32,
+        y: f32,
+    },
+    /// Keep the navigator shown in fullscreen
+    ToggleMinimapPin,
+    /// Switch to the next viewer background
+    CycleBackground,
+    /// The pointer moved over the fullscreen view
+    PointerMoved,
+    /// Fade the fullscreen controls
+    ControlsTick,
+}
+
+#[derive(Debug, Clone)]
+pub enum SidebarMessage {
+    Toggle,
+    PlacesLoaded {
+        places: Vec<Place>,
+        entries: Vec<FolderEntry>,
+    },
+    Expand(usize),
+    Collapse(usize),
+    /// Subfolders of the tree node at `index`, dropped if the tree changed meanwhile
+    FolderListed {
+        index: usize,
+        path: PathBuf,
+        folders: Vec<FolderEntry>,
+    },
+    Open(PathBuf),
+    /// Bookmark the folder shown in the gallery
+    AddBookmark,
+    RemoveBookmark(PathBuf),
 }
 
 #[derive(Debug, Clone)]
@@ -131,7 +330,12 @@ pub enum SettingsMessage {
     FitToWindow(bool),
     SmoothScaling(bool),
     ThumbnailSize(ThumbnailSize),
+    GalleryLayout(GalleryLayout),
+    ViewerBackground(ViewerBackground),
+    /// Text typed in the custom background color field
+    CustomBackground(String),
     ShowHiddenFiles(bool),
+    IncludeExtensionless(bool),
     SlideshowInterval(u32),
     CacheSize(usize),
     RememberLastDir(bool),
@@ -140,6 +344,14 @@ pub enum SettingsMessage {
     SortOrder(SortOrder),
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum NotificationMessage {
+    Dismiss(u64),
+    ToggleDetails(u64),
+    /// Drop the notifications that timed out
+    Tick,
+}
+
 #[derive(Debug, Clone)]
 pub enum EditMessage {
     Rotate90,
@@ -149,12 +361,62 @@ pub enum EditMessage {
     StartCrop,
     CancelCrop,
     ApplyCrop,
-    CropDragStart { x: f32, y: f32, handle: DragHandle },
-    CropDragMove { x: f32, y: f32 },
+    CropDragStart {
+        x: f32,
+        y: f32,
+        handle: DragHandle,
+    },
+    CropDragMove {
+        x: f32,
+        y: f32,
+    },
     CropDragEnd,
     Save,
     SaveAs,
     SaveAsPathSelected(PathBuf),
-    SaveComplete(Result<PathBuf, String>),
+    SaveComplete(Result<PathBuf, Arc<EditError>>),
+    /// Edits couldn't be applied to the preview
+    ApplyFailed(Arc<EditError>),
     Undo,
 }
+
+#[derive(Debug, Clone)]
+pub enum DuplicatesMessage {
+    Open,
+    Scan,
+    ScanComplete {
+        generation: u64,
+        entries: Arc<Vec<DuplicateEntry>>,
+    },
+    SetThreshold(u32),
+    /// The threshold stopped changing, regroup if `generation` is still the latest
+    Regroup(u64),
+    Grouped {
+        generation: u64,
+        groups: Vec<DuplicateGroup>,
+    },
+    Trash(PathBuf),
+    Trashed(PathBuf, Result<(), String>),
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareMode {
+    /// Images side by side (or in a 2x2 grid)
+    Split,
+    /// Two images stacked with a draggable divider
+    Swipe,
+    /// Two images stacked with adjustable opacity
+    Onion,
+}
+
+#[derive(Debug, Clone)]
+pub enum CompareMessage {
+    Open,
+    SetMode(CompareMode),
+    /// New linked view center in normalized image coordinates
+    Pan(f32, f32),
+    Swipe(f32),
+    Opacity(f32),
+    Close,
+}
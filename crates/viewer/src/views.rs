@@ -1,5 +1,21 @@
+pub mod compare;
+pub mod duplicates;
 pub mod gallery;
 pub mod image_view_state;
+pub mod inspector;
+pub mod notifications;
+pub mod pages;
+pub mod reader;
+pub mod sidebar;
+pub mod timeline;
 
+pub use compare::CompareView;
+pub use duplicates::DuplicatesView;
 pub use gallery::GalleryView;
-pub use image_view_state::ImageViewState;
+pub use image_view_state::{CONTROLS_TICK_MS, ImageViewState, KINETIC_TICK_MS, VectorRender};
+pub use inspector::Inspector;
+pub use notifications::Notifications;
+pub use pages::Pages;
+pub use reader::Reader;
+pub use sidebar::Sidebar;
+pub use timeline::Timeline;
@@ -0,0 +1,209 @@
+use crate::{
+    fl,
+    message::{CompareMessage, CompareMode, Message, ViewMessage},
+    views::ImageViewState,
+    widgets::{ComparePane, compare_widget},
+};
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    theme,
+    widget::{button, column, container, horizontal_space, icon, row, slider, text},
+};
+use std::path::PathBuf;
+use viewer_image::ImageCache;
+
+/// Maximum number of images shown at once in split mode
+pub const MAX_COMPARE: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct CompareView {
+    pub paths: Vec<PathBuf>,
+    /// Compare the edited preview against the original of `paths[0]`
+    pub with_preview: bool,
+    pub mode: CompareMode,
+    pub center: (f32, f32),
+    pub swipe: f32,
+    pub opacity: f32,
+}
+
+impl CompareView {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self {
+            paths: paths.into_iter().take(MAX_COMPARE).collect(),
+            with_preview: false,
+            mode: CompareMode::Split,
+            center: (0.5, 0.5),
+            swipe: 0.5,
+            opacity: 0.5,
+        }
+    }
+
+    /// Before/after view of the edited preview
+    pub fn with_preview(path: PathBuf) -> Self {
+        Self {
+            with_preview: true,
+            mode: CompareMode::Swipe,
+            ..Self::new(vec![path])
+        }
+    }
+
+    fn panes(&self, cache: &ImageCache, image_state: &ImageViewState) -> Vec<ComparePane> {
+        let pane = |path: &PathBuf| match cache.get_full(path) {
+            Some(cached) => ComparePane {
+                handle: Some(cached.handle),
+                width: cached.width,
+                height: cached.height,
+            },
+            None => ComparePane {
+                handle: None,
+                width: 1,
+                height: 1,
+            },
+        };
+
+        let mut panes: Vec<ComparePane> = self.paths.iter().map(pane).collect();
+
+        if self.with_preview
+            && let Some(ref preview) = image_state.preview_image
+        {
+            panes.push(ComparePane {
+                handle: Some(preview.handle.clone()),
+                width: preview.width,
+                height: preview.height,
+            });
+        }
+
+        panes
+    }
+
+    fn labels(&self) -> Vec<String> {
+        if self.with_preview {
+            return vec![fl!("compare-original"), fl!("compare-edited")];
+        }
+
+        self.paths
+            .iter()
+            .map(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.to_string_lossy().to_string())
+            })
+            .collect()
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        cache: &ImageCache,
+        image_state: &ImageViewState,
+    ) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let panes = self.panes(cache, image_state);
+        let can_overlay = panes.len() >= 2;
+
+        let close_btn = button::icon(icon::from_name("window-close-symbolic"))
+            .on_press(Message::Compare(CompareMessage::Close))
+            .padding(spacing.space_xs)
+            .class(theme::Button::Destructive);
+
+        let mode_btn = |label: String, mode: CompareMode| {
+            let btn = if self.mode == mode {
+                button::suggested(label)
+            } else {
+                button::standard(label)
+            };
+            btn.on_press_maybe(
+                (mode == CompareMode::Split || can_overlay)
+                    .then_some(Message::Compare(CompareMessage::SetMode(mode))),
+            )
+        };
+
+        let header = row()
+            .push(text::title4(fl!("compare-title")))
+            .push(horizontal_space())
+            .push(mode_btn(fl!("compare-split"), CompareMode::Split))
+            .push(mode_btn(fl!("compare-swipe"), CompareMode::Swipe))
+            .push(mode_btn(fl!("compare-onion"), CompareMode::Onion))
+            .push(close_btn)
+            .spacing(spacing.space_xs)
+            .align_y(Alignment::Center)
+            .width(Length::Fill)
+            .padding(spacing.space_xs);
+
+        let zoom = (!image_state.fit_to_window).then_some(image_state.zoom_level);
+        let compare = compare_widget(panes, self.mode)
+            .zoom(zoom)
+            .center(self.center)
+            .swipe(self.swipe)
+            .opacity(self.opacity);
+
+        // Names in the same order as the panes
+        let mut labels = row().spacing(spacing.space_m);
+        for (idx, label) in self.labels().into_iter().enumerate() {
+            let prefix = char::from(b'A' + idx as u8);
+            labels = labels.push(text::caption(format!("{prefix}: {label}")));
+        }
+
+        let zoom_label = if image_state.fit_to_window {
+            fl!("compare-fit")
+        } else {
+            format!("{}%", (image_state.zoom_level * 100.0) as u32)
+        };
+
+        let mut footer = row()
+            .push(labels)
+            .push(horizontal_space())
+            .spacing(spacing.space_xs)
+            .align_y(Alignment::Center)
+            .width(Length::Fill)
+            .padding(spacing.space_xs);
+
+        if self.mode == CompareMode::Onion && can_overlay {
+            footer = footer.push(text::body(fl!("compare-opacity"))).push(
+                container(
+                    slider(0.0..=1.0, self.opacity, |value| {
+                        Message::Compare(CompareMessage::Opacity(value))
+                    })
+                    .step(0.05),
+                )
+                .width(Length::Fixed(160.0)),
+            );
+        }
+
+        footer = footer
+            .push(
+                button::icon(icon::from_name("zoom-out-symbolic"))
+                    .on_press(Message::View(ViewMessage::ZoomOut))
+                    .padding(spacing.space_xs),
+            )
+            .push(text::body(zoom_label))
+            .push(
+                button::icon(icon::from_name("zoom-in-symbolic"))
+                    .on_press(Message::View(ViewMessage::ZoomIn))
+                    .padding(spacing.space_xs),
+            )
+            .push(
+                button::icon(icon::from_name("zoom-fit-best-symbolic"))
+                    .on_press(Message::View(ViewMessage::ZoomFit))
+                    .padding(spacing.space_xs),
+            );
+
+        container(
+            container(
+                column()
+                    .push(header)
+                    .push(Element::from(compare))
+                    .push(footer)
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .class(theme::Container::Dialog),
+        )
+        .padding([60, 80])
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{
+    fl,
+    message::{DuplicatesMessage, Message},
+};
+use cosmic::{
+    Element,
+    iced::{Alignment, ContentFit, Length},
+    iced_widget::scrollable::{Direction, Scrollbar},
+    theme,
+    widget::{
+        button, column, container, horizontal_space, icon, image, row, scrollable, spin_button,
+        text,
+    },
+};
+use shared::{date::format_unix_date, image::format_image_size};
+use std::{path::Path, sync::Arc};
+use viewer_image::{DuplicateEntry, DuplicateGroup, ImageCache, duplicates};
+
+const PREVIEW_SIZE: f32 = 160.0;
+
+#[derive(Debug, Clone)]
+pub struct DuplicatesView {
+    pub groups: Vec<DuplicateGroup>,
+    pub is_scanning: bool,
+    pub threshold: u32,
+    /// Fingerprints of the last scan, regrouped when the threshold changes
+    pub entries: Arc<Vec<DuplicateEntry>>,
+    /// Scan whose result is awaited; results of older scans are dropped
+    scan_generation: u64,
+    /// Grouping whose result is awaited, bumped on every threshold change
+    group_generation: u64,
+}
+
+impl Default for DuplicatesView {
+    fn default() -> Self {
+        Self {
+            groups: Vec::new(),
+            is_scanning: false,
+            threshold: duplicates::DEFAULT_SIMILARITY_THRESHOLD,
+            entries: Arc::default(),
+            scan_generation: 0,
+            group_generation: 0,
+        }
+    }
+}
+
+impl DuplicatesView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a scan, returns the generation its result has to carry
+    pub fn start_scan(&mut self) -> u64 {
+        self.is_scanning = true;
+        self.scan_generation += 1;
+        // Groupings still running belong to the previous scan
+        self.group_generation += 1;
+        self.scan_generation
+    }
+
+    pub fn is_current_scan(&self, generation: u64) -> bool {
+        generation == self.scan_generation
+    }
+
+    /// Start grouping the fingerprints, returns the generation its result has to carry
+    pub fn start_grouping(&mut self) -> u64 {
+        self.group_generation += 1;
+        self.group_generation
+    }
+
+    pub fn is_current_grouping(&self, generation: u64) -> bool {
+        generation == self.group_generation
+    }
+
+    /// Drop a file from every group, discarding groups left with a single entry
+    pub fn remove_path(&mut self, path: &Path) {
+        Arc::make_mut(&mut self.entries).retain(|entry| entry.path != path);
+        for group in &mut self.groups {
+            group.entries.retain(|entry| entry.path != path);
+        }
+        self.groups.retain(|group| group.entries.len() > 1);
+    }
+
+    pub fn view<'a>(&'a self, cache: &ImageCache) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let close_btn = button::icon(icon::from_name("window-close-symbolic"))
+            .on_press(Message::Duplicates(DuplicatesMessage::Close))
+            .padding(spacing.space_xs)
+            .class(theme::Button::Destructive);
+
+        let threshold = spin_button(
+            format!("{}", self.threshold),
+            fl!("duplicates-threshold"),
+            self.threshold,
+            1,
+            0,
+            32,
+            |value| Message::Duplicates(DuplicatesMessage::SetThreshold(value)),
+        );
+
+        let header = row()
+            .push(text::title4(fl!("duplicates-title")))
+            .push(horizontal_space())
+            .push(text::body(fl!("duplicates-threshold")))
+            .push(threshold)
+            .push(close_btn)
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center)
+            .width(Length::Fill)
+            .padding(spacing.space_xs);
+
+        let body: Element<'a, Message> = if self.is_scanning {
+            container(
+                column()
+                    .push(icon::from_name("content-loading-symbolic").size(48))
+                    .push(text::body(fl!("duplicates-scanning")))
+                    .spacing(spacing.space_s)
+                    .align_x(Alignment::Center),
+            )
+            .center(Length::Fill)
+            .into()
+        } else if self.groups.is_empty() {
+            container(text::body(fl!("duplicates-none")))
+                .center(Length::Fill)
+                .into()
+        } else {
+            let mut groups = column().spacing(spacing.space_m);
+
+            for group in &self.groups {
+                let label = if group.exact {
+                    fl!("duplicates-exact", count = group.entries.len())
+                } else {
+                    fl!("duplicates-similar", count = group.entries.len())
+                };
+
+                let mut cards = row().spacing(spacing.space_s);
+                for entry in &group.entries {
+                    cards = cards.push(Self::entry_card(entry, cache));
+                }
+
+                groups = groups.push(
+                    column()
+                        .push(text::heading(label))
+                        .push(
+                            scrollable(cards)
+                                .direction(Direction::Horizontal(Scrollbar::default())),
+                        )
+                        .spacing(spacing.space_xs),
+                );
+            }
+
+            scrollable(container(groups).padding(spacing.space_s))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into()
+        };
+
+        container(
+            container(
+                column()
+                    .push(header)
+                    .push(body)
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .class(theme::Container::Dialog),
+        )
+        .padding([60, 80])
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+
+    fn entry_card<'a>(entry: &'a DuplicateEntry, cache: &ImageCache) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let fingerprint = &entry.fingerprint;
+
+        let preview: Element<'a, Message> = match cache.get_thumbnail(&entry.path) {
+            Some(cached) => image(cached.handle)
+                .content_fit(ContentFit::Contain)
+                .width(Length::Fixed(PREVIEW_SIZE))
+                .height(Length::Fixed(PREVIEW_SIZE))
+                .into(),
+            None => container(icon::from_name("image-x-generic-symbolic").size(64))
+                .center(Length::Fixed(PREVIEW_SIZE))
+                .into(),
+        };
+
+        let name = entry
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| entry.path.to_string_lossy().to_string());
+
+        let trash_btn = button::destructive(fl!("delete-trash")).on_press(Message::Duplicates(
+            DuplicatesMessage::Trash(entry.path.clone()),
+        ));
+
+        container(
+            column()
+                .push(preview)
+                .push(text::body(name))
+                .push(text::caption(format!(
+                    "{} x {}",
+                    fingerprint.width, fingerprint.height
+                )))
+                .push(text::caption(format_image_size(fingerprint.file_size)))
+                .push(text::caption(format_unix_date(fingerprint.modified)))
+                .push(trash_btn)
+                .spacing(spacing.space_xxs)
+                .width(Length::Fixed(PREVIEW_SIZE))
+                .align_x(Alignment::Center),
+        )
+        .padding(spacing.space_xs)
+        .class(theme::Container::Card)
+        .into()
+    }
+}
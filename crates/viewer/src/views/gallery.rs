@@ -1,13 +1,18 @@
 use crate::{
     fl,
     message::{Message, NavMessage, ViewMessage},
-    views::ImageViewState,
-    widgets::{GalleryItem, gallery_grid},
+    views::{ImageViewState, Inspector, Reader, Timeline},
+    widgets::{
+        GalleryItem, GridArrangement, GridSection, ItemStatus, auto_hide,
+        checkerboard as checkerboard_widget, gallery_grid_from_fn, minimap, tiled_image,
+        visible_items, zoom_pan,
+    },
 };
 use cosmic::{
     Element,
-    iced::{Alignment, ContentFit, Length},
+    iced::{Alignment, Background, Border, Color, ContentFit, Length, Padding},
     iced_widget::{
+        container::Style as ContainerStyle,
         scrollable::{Direction, Scrollbar},
         stack,
     },
@@ -17,7 +22,10 @@ use cosmic::{
         responsive, row, scrollable, text,
     },
 };
-use viewer_image::{CachedImage, ImageCache, edit::EditState};
+use shared::{formats::ImageFormat, tiling};
+use std::{ops::Range, path::Path, sync::Arc};
+use viewer_config::{GalleryLayout, ViewerConfig};
+use viewer_image::{CachedImage, ImageCache, ImagePyramid, edit::EditState};
 use viewer_nav::NavState;
 
 #[derive(Debug, Clone, Default)]
@@ -27,6 +35,11 @@ pub struct GalleryView {
     pub row_height: f32,
     pub focused_index: Option<usize>,
     pub viewport: Option<cosmic::iced::widget::scrollable::Viewport>,
+    /// Grouping by capture date, `None` for the plain gallery
+    pub timeline: Option<Timeline>,
+    /// Item to scroll to once, e.g. after a timeline jump
+    pub reveal: Option<usize>,
+    pub reader: Reader,
 }
 
 impl GalleryView {
@@ -39,6 +52,9 @@ impl GalleryView {
             row_height: 4.0,
             focused_index: None,
             viewport: None,
+            timeline: None,
+            reveal: None,
+            reader: Reader::default(),
         }
     }
 
@@ -50,6 +66,48 @@ impl GalleryView {
         }
     }
 
+    /// Indices of the thumbnails on screen, empty until the gallery has scrolled
+    pub fn visible_range(
+        &self,
+        nav: &NavState,
+        cache: &ImageCache,
+        config: &ViewerConfig,
+    ) -> Range<usize> {
+        let Some(ref viewport) = self.viewport else {
+            return 0..0;
+        };
+        let spacing = theme::active().cosmic().spacing;
+
+        // Laid out like `view` lays out the grid
+        let aspect = thumbnail_aspect(nav, cache);
+        let sections: Vec<GridSection> = self
+            .timeline
+            .as_ref()
+            .filter(|timeline| timeline.is_ready(nav.total()))
+            .map(|timeline| {
+                timeline
+                    .sections()
+                    .iter()
+                    .map(|section| timeline.grid_section(section))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let arrangement = GridArrangement {
+            aspect: (config.gallery_layout == GalleryLayout::Justified)
+                .then_some(&aspect as &dyn Fn(usize) -> f32),
+            sections: &sections,
+        };
+
+        visible_items(
+            viewport,
+            config.thumbnail_size.pixels(),
+            spacing.space_xs,
+            Padding::new(spacing.space_s as f32),
+            nav.total(),
+            arrangement,
+        )
+    }
+
     pub fn clear_selection(&mut self) {
         self.selected.clear();
     }
@@ -58,7 +116,8 @@ impl GalleryView {
         self.selected.contains(&idx)
     }
 
-    fn modal_loading(&self) -> Element<'static, Message> {
+    /// Shown while the full image loads; displays the best stand-in we already have
+    fn modal_loading(&self, placeholder: Option<CachedImage>) -> Element<'static, Message> {
         let spacing = theme::active().cosmic().spacing;
 
         let close_btn = button::icon(icon::from_name("window-close-symbolic"))
@@ -72,16 +131,39 @@ impl GalleryView {
             .width(Length::Fill)
             .padding(spacing.space_xs);
 
-        let loading = container(
-            column()
-                .push(icon::from_name("content-loading-symbolic").size(48))
-                .push(text("Loading...").size(14))
-                .spacing(spacing.space_s)
-                .align_x(Alignment::Center),
-        )
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .center(Length::Fill);
+        let loading: Element<'static, Message> = match placeholder {
+            // Thumbnail or reduced decode upscaled to fit until the full image arrives
+            Some(placeholder) => column()
+                .push(
+                    container(
+                        image(placeholder.handle)
+                            .content_fit(ContentFit::Contain)
+                            .width(Length::Fill)
+                            .height(Length::Fill),
+                    )
+                    .padding([0, spacing.space_xxl])
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+                )
+                .push(
+                    container(text::caption("Loading..."))
+                        .width(Length::Fill)
+                        .center_x(Length::Fill)
+                        .padding(spacing.space_xs),
+                )
+                .into(),
+            None => container(
+                column()
+                    .push(icon::from_name("content-loading-symbolic").size(48))
+                    .push(text("Loading...").size(14))
+                    .spacing(spacing.space_s)
+                    .align_x(Alignment::Center),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center(Length::Fill)
+            .into(),
+        };
 
         container(
             mouse_area(
@@ -107,43 +189,27 @@ impl GalleryView {
     fn modal_content(
         &self,
         cached: &CachedImage,
+        pyramid: Option<Arc<ImagePyramid>>,
         image_state: &ImageViewState,
+        checkerboard: bool,
+        background: Option<[u8; 3]>,
+        damaged: bool,
     ) -> Element<'static, Message> {
         let spacing = theme::active().cosmic().spacing;
+        // Fullscreen puts the image on black unless another color was picked
+        let background = background.or(image_state.fullscreen.then_some([0, 0, 0]));
 
         let handle = cached.handle.clone();
+        let tiled = tiling::needs_tiling(cached.width, cached.height);
         let img_width = cached.width as f32;
         let img_height = cached.height as f32;
         let fit_to_window = image_state.fit_to_window;
         let zoom_level = image_state.zoom_level;
         let scroll_id = image_state.scroll_id.clone();
-
-        let prev_btn = container(
-            button::icon(icon::from_name("go-previous-symbolic"))
-                .on_press(Message::Nav(NavMessage::Prev)),
-        )
-        .width(Length::Shrink)
-        .height(Length::Fill)
-        .center_y(Length::Fill);
-
-        let next_btn = container(
-            button::icon(icon::from_name("go-next-symbolic"))
-                .on_press(Message::Nav(NavMessage::Next)),
-        )
-        .width(Length::Shrink)
-        .height(Length::Fill)
-        .center_y(Length::Fill);
-
-        let close_btn = button::icon(icon::from_name("window-close-symbolic"))
-            .on_press(Message::View(ViewMessage::CloseModal))
-            .padding(spacing.space_xs)
-            .class(theme::Button::Destructive);
-
-        let header = row()
-            .push(horizontal_space())
-            .push(close_btn)
-            .width(Length::Fill)
-            .padding(spacing.space_xs);
+        let inspector = image_state.inspector.as_ref();
+        let clipping = inspector
+            .filter(|inspector| inspector.is_for(&cached.handle))
+            .and_then(Inspector::clipping_overlay);
 
         let image_area = responsive(move |size| {
             let available_width = size.width - (spacing.space_xs * 2) as f32;
@@ -167,10 +233,44 @@ impl GalleryView {
             let pad_x = ((available_width - scaled_width) / 2.0).max(0.0);
             let pad_y = ((available_height - scaled_height) / 2.0).max(0.0);
 
-            let image_widget = image(handle.clone())
-                .content_fit(ContentFit::Fill)
-                .width(Length::Fixed(scaled_width))
-                .height(Length::Fixed(scaled_height));
+            // Gigapixel images draw a fit-resolution preview with visible tiles on top
+            let image_widget: Element<'static, Message> = if tiled {
+                tiled_image(handle.clone(), pyramid.clone())
+                    .dimensions(scaled_width, scaled_height)
+                    .into()
+            } else {
+                image(handle.clone())
+                    .content_fit(ContentFit::Fill)
+                    .width(Length::Fixed(scaled_width))
+                    .height(Length::Fixed(scaled_height))
+                    .into()
+            };
+
+            // Transparent areas show a checkerboard for vector images or when picked
+            let image_widget = if checkerboard {
+                stack![
+                    checkerboard_widget()
+                        .width(Length::Fixed(scaled_width))
+                        .height(Length::Fixed(scaled_height)),
+                    image_widget
+                ]
+                .into()
+            } else {
+                image_widget
+            };
+
+            // Clipped highlights and shadows marked over the image
+            let image_widget = match clipping {
+                Some(ref mask) => stack![
+                    image_widget,
+                    image(mask.clone())
+                        .content_fit(ContentFit::Fill)
+                        .width(Length::Fixed(scaled_width))
+                        .height(Length::Fixed(scaled_height))
+                ]
+                .into(),
+                None => image_widget,
+            };
 
             if scaled_width > available_width || scaled_height > available_height {
                 container(
@@ -185,6 +285,13 @@ impl GalleryView {
                         vertical: Scrollbar::default(),
                         horizontal: Scrollbar::default(),
                     })
+                    .on_scroll(|viewport| {
+                        let offset = viewport.absolute_offset();
+                        Message::View(ViewMessage::ImageScrolled {
+                            x: offset.x,
+                            y: offset.y,
+                        })
+                    })
                     .width(Length::Fill)
                     .height(Length::Fill),
                 )
@@ -201,9 +308,19 @@ impl GalleryView {
             }
         });
 
+        // Damaged files say so next to the zoom controls
+        let warning = damaged.then(|| {
+            row()
+                .push(icon::from_name("dialog-warning-symbolic").size(16))
+                .push(text::caption(fl!("status-damaged")))
+                .spacing(spacing.space_xxs)
+                .align_y(Alignment::Center)
+        });
+
         // Zoom controls
         let fit_zoom_display = image_state.fit_zoom;
         let zoom_ctrls = row()
+            .push_maybe(warning)
             .push(
                 button::icon(icon::from_name("zoom-out-symbolic"))
                     .on_press(Message::View(ViewMessage::ZoomOut))
@@ -235,55 +352,100 @@ impl GalleryView {
             .spacing(spacing.space_xs)
             .align_y(Alignment::Center);
 
-        let footer = row()
-            .push(horizontal_space())
-            .push(zoom_ctrls)
-            .push(horizontal_space())
-            .width(Length::Fill)
-            .padding(spacing.space_xs);
-
-        let content_row = row()
-            .push(prev_btn)
-            .push(image_area)
-            .push(next_btn)
-            .width(Length::Fill)
-            .height(Length::Fill);
+        // Wheel and pinch zoom at the cursor, drag to pan when zoomed in
+        let image_area: Element<'static, Message> = zoom_pan(image_area)
+            .pannable(!fit_to_window)
+            .inspecting(inspector.is_some())
+            .into();
+        let image_area = backdrop(image_area, background);
 
-        // Outer padding lets gallery peek through
-        container(
-            mouse_area(
-                container(
-                    column()
-                        .push(header)
-                        .push(content_row)
-                        .push(footer)
-                        .width(Length::Fill)
-                        .height(Length::Fill),
+        // Navigator in the corner while the zoomed image overflows the area. The
+        // stack stays when it hides so the scrollable below keeps its position.
+        let navigator: Element<'static, Message> = if image_state.shows_minimap() {
+            column()
+                .push(minimap(
+                    cached.handle.clone(),
+                    (img_width, img_height),
+                    image_state.visible_region(),
+                ))
+                .push(
+                    button::icon(icon::from_name("view-pin-symbolic"))
+                        .selected(image_state.minimap_pinned)
+                        .on_press(Message::View(ViewMessage::ToggleMinimapPin))
+                        .padding(spacing.space_xxs),
                 )
+                .spacing(spacing.space_xxs)
+                .align_x(Alignment::Start)
+                .into()
+        } else {
+            Space::new(Length::Shrink, Length::Shrink).into()
+        };
+        let image_area: Element<'static, Message> = stack![
+            image_area,
+            container(navigator)
                 .width(Length::Fill)
                 .height(Length::Fill)
-                .class(theme::Container::Dialog),
+                .align_x(Alignment::Start)
+                .align_y(Alignment::End)
+                .padding(spacing.space_s)
+        ]
+        .into();
+
+        // The inspector sits beside the image so it never hides the pixels looked at
+        let image_area = match inspector {
+            Some(inspector) => row()
+                .push(image_area)
+                .push(inspector.view())
+                .spacing(spacing.space_xs)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into(),
+            None => image_area,
+        };
+
+        // Multi-page files show their pages below the image
+        let image_area = match image_state.pages.as_ref().filter(|pages| pages.count > 1) {
+            Some(pages) => column()
+                .push(image_area)
+                .push(pages.strip())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into(),
+            None => image_area,
+        };
+
+        if image_state.fullscreen {
+            immersive_frame(
+                image_area,
+                zoom_ctrls.into(),
+                Message::Nav(NavMessage::Prev),
+                Message::Nav(NavMessage::Next),
+                image_state.controls_opacity(),
             )
-            .on_press(Message::View(ViewMessage::ImageEditEvent)),
-        )
-        .padding([60, 80])
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .into()
+        } else {
+            modal_frame(
+                image_area,
+                zoom_ctrls.into(),
+                Message::Nav(NavMessage::Prev),
+                Message::Nav(NavMessage::Next),
+            )
+        }
     }
 
+    /// `format` is the content-detected format of the current image, if known yet
     pub fn view(
         &self,
         nav: &NavState,
         cache: &ImageCache,
-        thumbnail_size: u32,
+        config: &ViewerConfig,
         image_state: &ImageViewState,
         edit_state: &EditState,
+        format: Option<ImageFormat>,
     ) -> Element<'_, Message> {
         let spacing = theme::active().cosmic().spacing;
-        let images = nav.images();
+        let thumbnail_size = config.thumbnail_size.pixels();
 
-        if images.is_empty() {
+        if nav.is_empty() {
             return container(
                 column()
                     .push(icon::from_name("folder-pictures-symbolic").size(64))
@@ -296,26 +458,40 @@ impl GalleryView {
             .into();
         }
 
-        // Build gallery items with dimensions for proper aspect ratio
-        let items: Vec<GalleryItem> = images
-            .iter()
-            .map(|path| {
-                // Get thumbnail handle and dimensions from cache
-                let (handle, width, height) = if let Some(cached) = cache.get_thumbnail(path) {
-                    (Some(cached.handle), cached.width, cached.height)
-                } else {
-                    (None, thumbnail_size, thumbnail_size)
-                };
-
-                GalleryItem::new(path.clone(), handle, width, height)
-            })
-            .collect();
+        // Justified rows need every aspect ratio, read without building the item
+        let aspect = thumbnail_aspect(nav, cache);
+
+        // Gallery items are built lazily, only for the rows the grid actually draws
+        let images = nav.shared_images();
+        let cache = cache.clone();
+        let item = move |idx: usize| {
+            let path = &images[idx];
+
+            // Get thumbnail handle and dimensions from cache
+            let (handle, width, height) = if let Some(cached) = cache.get_thumbnail(path) {
+                (Some(cached.handle), cached.width, cached.height)
+            } else {
+                (None, thumbnail_size, thumbnail_size)
+            };
+
+            let status = if cache.is_thumbnail_failed(path) {
+                ItemStatus::Failed
+            } else if cache.is_damaged(path) {
+                ItemStatus::Damaged
+            } else {
+                ItemStatus::Normal
+            };
+
+            GalleryItem::new(path.clone(), handle, width, height).status(status)
+        };
 
         // Disable keyboard nav when modal is open (modal handles arrow keys)
         let modal_open = nav.index().is_some();
 
-        let content = gallery_grid(items)
+        let mut grid = gallery_grid_from_fn(nav.total(), item)
             .thumbnail_size(thumbnail_size)
+            .justified(config.gallery_layout == GalleryLayout::Justified)
+            .aspect(aspect)
             .focused(self.focused_index)
             .selected(self.selected.clone())
             .spacing(spacing.space_xs)
@@ -324,12 +500,52 @@ impl GalleryView {
             .keyboard_navigation(!modal_open)
             .on_focus(|idx| Message::Nav(NavMessage::GalleryFocus(idx)))
             .on_activate(|idx| Message::Nav(NavMessage::GallerySelect(idx)))
+            .on_toggle_select(|idx| Message::Nav(NavMessage::GalleryToggleSelect(idx)))
+            .on_scroll(|viewport| Message::View(ViewMessage::GalleryScrolled(viewport)))
+            .on_zoom(|steps| Message::View(ViewMessage::ZoomThumbnails(steps)))
             .on_scroll_request(|req| Message::View(ViewMessage::GalleryScrollTo(req.offset_y)))
-            .into_element();
+            .on_toggle_section(|idx| Message::View(ViewMessage::ToggleTimelineSection(idx)))
+            .reveal(self.reveal);
+
+        // Date headers and the year scrubber once all capture dates are known
+        let mut scrubber = None;
+        if let Some(ref timeline) = self.timeline
+            && timeline.is_ready(nav.total())
+        {
+            let sections = timeline.sections();
+            for (idx, section) in sections.iter().enumerate() {
+                grid = grid.section(
+                    timeline.grid_section(section),
+                    timeline.header(idx, section),
+                );
+            }
+            scrubber = Some(timeline.scrubber(&sections));
+        }
+
+        let content: Element<'_, Message> = match scrubber {
+            Some(scrubber) => row()
+                .push(grid.into_element())
+                .push(scrubber)
+                .spacing(spacing.space_xxs)
+                .height(Length::Fill)
+                .into(),
+            None => grid.into_element(),
+        };
 
         // Status bar
-        let status = row()
-            .push(text(format!("{} images", images.len())).size(12))
+        let mut status = row().push(text(format!("{} images", nav.total())).size(12));
+        if let Some(pages) = nav.current().and_then(|path| image_state.pages_of(path)) {
+            status = status.push(text(pages.label()).size(12));
+        }
+        if self
+            .timeline
+            .as_ref()
+            .is_some_and(|timeline| !timeline.is_ready(nav.total()))
+        {
+            status = status.push(text(fl!("timeline-loading")).size(12));
+        }
+        let status = status
+            .spacing(spacing.space_s)
             .padding([spacing.space_xxs, spacing.space_s])
             .align_y(Alignment::Center);
 
@@ -342,10 +558,10 @@ impl GalleryView {
         // If modal is open wrap with popover (but not when cropping - crop has its own overlay)
         if !edit_state.is_cropping
             && let Some(idx) = nav.index()
-            && let Some(path) = images.get(idx)
+            && let Some(path) = nav.images().get(idx)
         {
             // Use mouse-area to close the modal when the backdrop is clicked.
-            let backdrop = mouse_area(
+            let dismiss = mouse_area(
                 container(Space::new(Length::Fill, Length::Fill))
                     .width(Length::Fill)
                     .height(Length::Fill)
@@ -355,19 +571,253 @@ impl GalleryView {
 
             // Show modal with image
             // Use preview image if available (contains edits), otherwise use cached
-            let modal = if let Some(ref preview) = image_state.preview_image {
-                self.modal_content(preview, image_state)
+            let background = config.viewer_background;
+            let color = config.background_color();
+            let modal = if self.reader.shows_pages() {
+                self.reader.view(nav, cache)
+            } else if let Some(ref preview) = image_state.preview_image {
+                let checkerboard = background.shows_checkerboard(false);
+                self.modal_content(preview, None, image_state, checkerboard, color, false)
+            } else if let Some(pages) = image_state.pages_of(path)
+                && (pages.current > 0 || pages.image.is_some())
+            {
+                // Pages are decoded on demand, their thumbnail stands in. The first
+                // page uses the cached image unless it was decoded on its own.
+                match pages.image {
+                    Some(ref page) => {
+                        let checkerboard = background.shows_checkerboard(false);
+                        self.modal_content(page, None, image_state, checkerboard, color, false)
+                    }
+                    None => self.modal_loading(pages.thumbnails[pages.current].clone()),
+                }
+            } else if let Some(render) = image_state.vector_render(path) {
+                let checkerboard = background.shows_checkerboard(true);
+                self.modal_content(render, None, image_state, checkerboard, color, false)
             } else if let Some(cached) = cache.get_full(path) {
-                self.modal_content(&cached, image_state)
+                let is_vector = format.is_some_and(ImageFormat::is_vector);
+                self.modal_content(
+                    &cached,
+                    cache.get_pyramid(path),
+                    image_state,
+                    background.shows_checkerboard(is_vector),
+                    color,
+                    cache.is_damaged(path),
+                )
             } else {
-                self.modal_loading()
+                let placeholder = cache
+                    .get_preview(path)
+                    .or_else(|| cache.get_thumbnail(path));
+                self.modal_loading(placeholder)
             };
 
+            // Fullscreen leaves the gallery out, the image takes the whole window
+            if image_state.fullscreen {
+                let opacity = image_state.controls_opacity();
+                return auto_hide(stack![
+                    backdrop(modal, color.or(Some([0, 0, 0]))),
+                    immersive_header(path, opacity)
+                ])
+                .hide_cursor(!image_state.controls_visible())
+                .into();
+            }
+
             // Create a stack as a modal; this avoids the modal blocking other
             // UI elements.
-            return stack![gallery, backdrop, modal].into();
+            return stack![gallery, dismiss, modal].into();
         }
 
         gallery
     }
 }
+
+/// Width to height ratio of each gallery thumbnail, square until it is loaded
+fn thumbnail_aspect(nav: &NavState, cache: &ImageCache) -> impl Fn(usize) -> f32 + 'static {
+    let images = nav.shared_images();
+    let cache = cache.clone();
+    move |idx: usize| {
+        cache
+            .thumbnail_dimensions(&images[idx])
+            .map_or(1.0, |(width, height)| width as f32 / height.max(1) as f32)
+    }
+}
+
+/// Fill the area behind `content` with a solid color, or leave the theme's
+pub fn backdrop(
+    content: Element<'static, Message>,
+    color: Option<[u8; 3]>,
+) -> Element<'static, Message> {
+    let Some([r, g, b]) = color else {
+        return content;
+    };
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .class(theme::Container::custom(move |_| ContainerStyle {
+            background: Some(Background::Color(Color::from_rgb8(r, g, b))),
+            ..ContainerStyle::default()
+        }))
+        .into()
+}
+
+/// Fullscreen counterpart of `modal_frame`: `image_area` fills the window and the
+/// previous and next buttons and `controls` float over it, fading with `opacity`
+pub fn immersive_frame(
+    image_area: Element<'static, Message>,
+    controls: Element<'static, Message>,
+    on_prev: Message,
+    on_next: Message,
+    opacity: f32,
+) -> Element<'static, Message> {
+    let spacing = theme::active().cosmic().spacing;
+
+    // The layer stays while hidden so the image below keeps its scroll position
+    let layer: Element<'static, Message> = if opacity > 0.0 {
+        let nav = row()
+            .push(button::icon(icon::from_name("go-previous-symbolic")).on_press(on_prev))
+            .push(horizontal_space())
+            .push(button::icon(icon::from_name("go-next-symbolic")).on_press(on_next))
+            .width(Length::Fill)
+            .align_y(Alignment::Center);
+
+        column()
+            .push(container(nav).height(Length::Fill).center_y(Length::Fill))
+            .push(
+                container(overlay_bar(controls, opacity))
+                    .width(Length::Fill)
+                    .center_x(Length::Fill),
+            )
+            .padding(spacing.space_s)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    } else {
+        Space::new(Length::Shrink, Length::Shrink).into()
+    };
+
+    stack![image_area, layer].into()
+}
+
+/// File name and close button over the top of the fullscreen image
+fn immersive_header(path: &Path, opacity: f32) -> Element<'static, Message> {
+    let spacing = theme::active().cosmic().spacing;
+
+    if opacity <= 0.0 {
+        return Space::new(Length::Shrink, Length::Shrink).into();
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let bar = row()
+        .push(text::body(name).width(Length::Fill))
+        .push(
+            button::icon(icon::from_name("window-close-symbolic"))
+                .on_press(Message::View(ViewMessage::CloseModal))
+                .padding(spacing.space_xs),
+        )
+        .align_y(Alignment::Center)
+        .width(Length::Fill);
+
+    container(overlay_bar(bar.into(), opacity))
+        .width(Length::Fill)
+        .padding(spacing.space_s)
+        .into()
+}
+
+/// Translucent dark bar readable over any image, its text fading with `opacity`
+fn overlay_bar(content: Element<'static, Message>, opacity: f32) -> Element<'static, Message> {
+    let spacing = theme::active().cosmic().spacing;
+
+    container(content)
+        .padding([spacing.space_xxs, spacing.space_s])
+        .class(theme::Container::custom(move |_| ContainerStyle {
+            background: Some(Background::Color(Color::from_rgba(
+                0.0,
+                0.0,
+                0.0,
+                0.6 * opacity,
+            ))),
+            text_color: Some(Color {
+                a: opacity,
+                ..Color::WHITE
+            }),
+            border: Border {
+                radius: 8.0.into(),
+                ..Border::default()
+            },
+            ..ContainerStyle::default()
+        }))
+        .into()
+}
+
+/// Image modal around `image_area`: close button on top, previous and next buttons
+/// on the sides and `controls` centered below
+pub fn modal_frame(
+    image_area: Element<'static, Message>,
+    controls: Element<'static, Message>,
+    on_prev: Message,
+    on_next: Message,
+) -> Element<'static, Message> {
+    let spacing = theme::active().cosmic().spacing;
+
+    let prev_btn =
+        container(button::icon(icon::from_name("go-previous-symbolic")).on_press(on_prev))
+            .width(Length::Shrink)
+            .height(Length::Fill)
+            .center_y(Length::Fill);
+
+    let next_btn = container(button::icon(icon::from_name("go-next-symbolic")).on_press(on_next))
+        .width(Length::Shrink)
+        .height(Length::Fill)
+        .center_y(Length::Fill);
+
+    let close_btn = button::icon(icon::from_name("window-close-symbolic"))
+        .on_press(Message::View(ViewMessage::CloseModal))
+        .padding(spacing.space_xs)
+        .class(theme::Button::Destructive);
+
+    let header = row()
+        .push(horizontal_space())
+        .push(close_btn)
+        .width(Length::Fill)
+        .padding(spacing.space_xs);
+
+    let footer = row()
+        .push(horizontal_space())
+        .push(controls)
+        .push(horizontal_space())
+        .width(Length::Fill)
+        .padding(spacing.space_xs);
+
+    let content_row = row()
+        .push(prev_btn)
+        .push(image_area)
+        .push(next_btn)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+    // Outer padding lets gallery peek through
+    container(
+        mouse_area(
+            container(
+                column()
+                    .push(header)
+                    .push(content_row)
+                    .push(footer)
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .class(theme::Container::Dialog),
+        )
+        .on_press(Message::View(ViewMessage::ImageEditEvent)),
+    )
+    .padding([60, 80])
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .into()
+}
@@ -1,8 +1,50 @@
-use crate::message::Message;
+use crate::{
+    message::Message,
+    views::{
+        inspector::{self, Inspector},
+        pages::{PAGE_STRIP_HEIGHT, Pages},
+    },
+};
 use cosmic::{Task, iced_widget::scrollable, widget::Id};
+use shared::{
+    overlay,
+    viewport::{self, Kinetic},
+};
+use std::{
+    path::{Path, PathBuf},
+    time::Instant,
+};
 use viewer_image::CachedImage;
 
 const MODAL_SCROLL_ID: &str = "modal-image-scroll";
+const MIN_ZOOM: f32 = 0.1;
+const MAX_ZOOM: f32 = 10.0;
+const ZOOM_STEP: f32 = 1.25;
+/// Interval of the kinetic panning timer
+pub const KINETIC_TICK_MS: u64 = 16;
+/// Interval of the timer fading the fullscreen controls
+pub const CONTROLS_TICK_MS: u64 = 33;
+/// Pause after the last pan event before coasting starts (touchpads send no release)
+const KINETIC_IDLE_SECS: f32 = 0.05;
+
+/// Rasterization of the current vector image at the zoom it is shown at
+#[derive(Debug, Clone)]
+pub struct VectorRender {
+    pub path: PathBuf,
+    /// Scale the render was requested at, relative to the intrinsic size
+    pub scale: f32,
+    /// Intrinsic size with a sharper handle; the previous render until the new one arrives
+    pub image: Option<CachedImage>,
+}
+
+/// Zoom and scroll position carried over to the next image if it has the same size
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PreservedViewport {
+    width: u32,
+    height: u32,
+    zoom_level: f32,
+    scroll_offset: (f32, f32),
+}
 
 #[derive(Debug, Clone)]
 pub struct ImageViewState {
@@ -14,6 +56,29 @@ pub struct ImageViewState {
     pub window_height: f32,
     /// Preview image for editing (not cached)
     pub preview_image: Option<CachedImage>,
+    /// Page shown of a multi-page file
+    pub pages: Option<Pages>,
+    /// Sharp render of a zoomed in vector image
+    pub vector: Option<VectorRender>,
+    /// Histogram and pixel inspector overlay, while it is shown
+    pub inspector: Option<Inspector>,
+    /// Dimensions of the image currently shown
+    pub image_width: u32,
+    pub image_height: u32,
+    /// Scroll position of the zoomed image
+    pub scroll_offset: (f32, f32),
+    /// Whether the window is fullscreen, showing the image edge to edge
+    pub fullscreen: bool,
+    /// Keep the navigator shown in fullscreen
+    pub minimap_pinned: bool,
+    /// When the fullscreen controls appeared, `None` while they are hidden
+    controls_shown: Option<Instant>,
+    /// When the pointer last moved in fullscreen
+    last_activity: Option<Instant>,
+    kinetic: Kinetic,
+    last_pan: Option<Instant>,
+    is_panning: bool,
+    preserved: Option<PreservedViewport>,
 }
 
 impl Default for ImageViewState {
@@ -26,6 +91,20 @@ impl Default for ImageViewState {
             window_width: 0.0,
             window_height: 0.0,
             preview_image: None,
+            pages: None,
+            vector: None,
+            inspector: None,
+            image_width: 0,
+            image_height: 0,
+            scroll_offset: (0.0, 0.0),
+            fullscreen: false,
+            minimap_pinned: false,
+            controls_shown: None,
+            last_activity: None,
+            kinetic: Kinetic::default(),
+            last_pan: None,
+            is_panning: false,
+            preserved: None,
         }
     }
 }
@@ -35,32 +114,246 @@ impl ImageViewState {
         Self::default()
     }
 
-    pub fn zoom_in(&mut self) -> Task<Message> {
+    /// Pages of the file at `path`, if it has several
+    pub fn pages_of(&self, path: &Path) -> Option<&Pages> {
+        self.pages.as_ref().filter(|pages| pages.is_for(path))
+    }
+
+    /// Render of the vector image at `path` to draw instead of the cached one
+    pub fn vector_render(&self, path: &Path) -> Option<&CachedImage> {
+        self.vector
+            .as_ref()
+            .filter(|vector| vector.path == path)
+            .and_then(|vector| vector.image.as_ref())
+    }
+
+    /// Zoom level the image is currently drawn at
+    pub fn effective_zoom(&self) -> f32 {
         if self.fit_to_window {
-            self.zoom_level = self.fit_zoom;
+            self.fit_zoom
+        } else {
+            self.zoom_level
         }
-        self.fit_to_window = false;
-        self.zoom_level = (self.zoom_level * 1.25).min(10.);
-        self.scroll_to_center()
+    }
+
+    pub fn zoom_in(&mut self) -> Task<Message> {
+        self.zoom_at(ZOOM_STEP, self.viewport_center())
     }
 
     pub fn zoom_out(&mut self) -> Task<Message> {
-        if self.fit_to_window {
-            self.zoom_level = self.fit_zoom;
-        }
-        self.fit_to_window = false;
-        self.zoom_level = (self.zoom_level / 1.25).max(0.1);
-        self.scroll_to_center()
+        self.zoom_at(1.0 / ZOOM_STEP, self.viewport_center())
     }
 
     pub fn zoom_reset(&mut self) -> Task<Message> {
-        self.fit_to_window = false;
-        self.zoom_level = 1.0;
-        self.scroll_to_center()
+        let factor = 1.0 / self.effective_zoom();
+        self.zoom_at(factor, self.viewport_center())
     }
 
     pub fn zoom_fit(&mut self) {
         self.fit_to_window = true;
+        self.scroll_offset = (0.0, 0.0);
+        self.kinetic.stop();
+    }
+
+    /// Zoom by `factor` keeping the image point under `anchor` in place.
+    /// `anchor` is relative to the top-left corner of the image area.
+    pub fn zoom_at(&mut self, factor: f32, anchor: (f32, f32)) -> Task<Message> {
+        let old_zoom = self.effective_zoom();
+        let new_zoom = (old_zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+
+        self.kinetic.stop();
+        self.fit_to_window = false;
+        self.zoom_level = new_zoom;
+
+        self.scroll_offset = viewport::zoom_anchor_offset(
+            self.viewport_size(),
+            self.image_size(),
+            self.scroll_offset,
+            anchor,
+            old_zoom,
+            new_zoom,
+        );
+        self.scroll_to_offset()
+    }
+
+    /// Double-click behavior: toggle between 100% at the cursor and fit
+    pub fn toggle_zoom_at(&mut self, anchor: (f32, f32)) -> Task<Message> {
+        if !self.fit_to_window && (self.zoom_level - 1.0).abs() < 0.001 {
+            self.zoom_fit();
+            Task::none()
+        } else {
+            let factor = 1.0 / self.effective_zoom();
+            self.zoom_at(factor, anchor)
+        }
+    }
+
+    /// Position in the full size image under a point of the image area
+    pub fn image_point(&self, x: f32, y: f32) -> Option<(f32, f32)> {
+        viewport::image_point(
+            self.viewport_size(),
+            self.image_size(),
+            self.scroll_offset,
+            (x, y),
+            self.effective_zoom(),
+        )
+    }
+
+    /// Switch between the windowed modal and the immersive fullscreen view
+    pub fn toggle_fullscreen(&mut self) {
+        self.fullscreen = !self.fullscreen;
+        self.controls_shown = None;
+        self.last_activity = None;
+
+        // Show the controls once so it is clear how to get out again
+        if self.fullscreen {
+            self.pointer_moved();
+        }
+    }
+
+    /// Bring the fullscreen controls back, fading them in if they were hidden
+    pub fn pointer_moved(&mut self) {
+        let now = Instant::now();
+        self.controls_shown.get_or_insert(now);
+        self.last_activity = Some(now);
+    }
+
+    /// Opacity of the controls over the fullscreen image
+    pub fn controls_opacity(&self) -> f32 {
+        match (self.controls_shown, self.last_activity) {
+            (Some(shown), Some(active)) => overlay::opacity(
+                shown.elapsed().as_secs_f32(),
+                active.elapsed().as_secs_f32(),
+            ),
+            _ => 0.0,
+        }
+    }
+
+    /// Whether the fullscreen controls are shown or fading, so the timer needs to run
+    pub fn controls_visible(&self) -> bool {
+        self.fullscreen && self.controls_shown.is_some()
+    }
+
+    /// Forget the controls once they faded out, which stops the timer and hides the cursor
+    pub fn controls_tick(&mut self) {
+        if self
+            .last_activity
+            .is_none_or(|active| overlay::is_hidden(active.elapsed().as_secs_f32()))
+        {
+            self.controls_shown = None;
+        }
+    }
+
+    /// Whether the navigator is shown: the zoomed image overflows the image area,
+    /// and the window is not fullscreen unless the navigator is pinned
+    pub fn shows_minimap(&self) -> bool {
+        if self.fit_to_window || (self.fullscreen && !self.minimap_pinned) {
+            return false;
+        }
+
+        let (width, height) = self.viewport_size();
+        let zoom = self.effective_zoom();
+        self.image_width as f32 * zoom > width || self.image_height as f32 * zoom > height
+    }
+
+    /// Part of the image in view as `(x, y, width, height)` fractions of its size
+    pub fn visible_region(&self) -> (f32, f32, f32, f32) {
+        viewport::visible_fraction(
+            self.viewport_size(),
+            self.image_size(),
+            self.scroll_offset,
+            self.effective_zoom(),
+        )
+    }
+
+    /// Scroll so the point given as fractions of the image size is centered
+    pub fn center_on(&mut self, x: f32, y: f32) -> Task<Message> {
+        self.kinetic.stop();
+        self.scroll_offset = viewport::centered_offset(
+            self.viewport_size(),
+            self.image_size(),
+            self.effective_zoom(),
+            (x, y),
+        );
+        self.scroll_to_offset()
+    }
+
+    /// Track the position reported by the scrollable
+    pub fn set_scroll_offset(&mut self, x: f32, y: f32) {
+        self.scroll_offset = (x, y);
+    }
+
+    pub fn pan_start(&mut self) {
+        self.is_panning = true;
+        self.kinetic.stop();
+        self.last_pan = Some(Instant::now());
+    }
+
+    pub fn pan(&mut self, dx: f32, dy: f32) -> Task<Message> {
+        let now = Instant::now();
+        if let Some(last) = self.last_pan {
+            self.kinetic
+                .track((dx, dy), now.duration_since(last).as_secs_f32());
+        }
+        self.last_pan = Some(now);
+
+        self.pan_by(dx, dy)
+    }
+
+    pub fn pan_end(&mut self) {
+        self.is_panning = false;
+
+        // Holding still before releasing should not fling the image
+        if self
+            .last_pan
+            .is_some_and(|last| last.elapsed().as_secs_f32() > KINETIC_IDLE_SECS)
+        {
+            self.kinetic.stop();
+        }
+    }
+
+    /// Whether the kinetic panning timer needs to run
+    pub fn is_kinetic(&self) -> bool {
+        !self.is_panning && self.kinetic.is_moving()
+    }
+
+    pub fn kinetic_tick(&mut self) -> Task<Message> {
+        if self.is_panning
+            || self
+                .last_pan
+                .is_some_and(|last| last.elapsed().as_secs_f32() < KINETIC_IDLE_SECS)
+        {
+            return Task::none();
+        }
+
+        match self.kinetic.step(KINETIC_TICK_MS as f32 / 1000.0) {
+            Some((dx, dy)) => self.pan_by(dx, dy),
+            None => Task::none(),
+        }
+    }
+
+    /// Remember the viewport so the next image can reuse it if it has the same size
+    pub fn preserve_viewport(&mut self) {
+        self.kinetic.stop();
+        self.preserved = (!self.fit_to_window).then_some(PreservedViewport {
+            width: self.image_width,
+            height: self.image_height,
+            zoom_level: self.zoom_level,
+            scroll_offset: self.scroll_offset,
+        });
+        self.zoom_fit();
+    }
+
+    /// Reapply a preserved viewport if the new image has the same dimensions
+    pub fn restore_viewport(&mut self, width: u32, height: u32) -> Task<Message> {
+        match self.preserved.take() {
+            Some(preserved) if preserved.width == width && preserved.height == height => {
+                self.fit_to_window = false;
+                self.zoom_level = preserved.zoom_level;
+                self.scroll_offset = preserved.scroll_offset;
+                self.scroll_to_offset()
+            }
+            _ => Task::none(),
+        }
     }
 
     pub fn set_window_size(&mut self, width: f32, height: f32) {
@@ -69,10 +362,22 @@ impl ImageViewState {
     }
 
     pub fn calculate_fit_zoom(&mut self, img_width: u32, img_height: u32) {
-        if self.window_width <= 0.0 || self.window_height <= 0.0 {
-            return; // No valid window dimensions yet
+        self.image_width = img_width;
+        self.image_height = img_height;
+
+        let (available_width, available_height) = self.viewport_size();
+
+        if available_width <= 0.0 || available_height <= 0.0 {
+            return; // No valid window dimensions yet, or window too small
         }
 
+        let zoom_x = available_width / img_width as f32;
+        let zoom_y = available_height / img_height as f32;
+        self.fit_zoom = zoom_x.min(zoom_y).min(1.0);
+    }
+
+    /// Size of the image area inside the modal, or the whole window in fullscreen
+    pub fn viewport_size(&self) -> (f32, f32) {
         // Modal padding from window edges
         let modal_pad_x = 80.0 * 2.0;
         let modal_pad_y = 60.0 * 2.0;
@@ -83,24 +388,72 @@ impl ImageViewState {
         let nav_btn_width = 48.0 * 2.0;
         // Container padding inside modal
         let container_pad = 16.0;
+        // Inspector panel and the spacing before it
+        let inspector_width = if self.inspector.is_some() {
+            inspector::PANEL_WIDTH + 8.0
+        } else {
+            0.0
+        };
+        // Page strip of multi-page files below the image
+        let strip_height = if self.pages.as_ref().is_some_and(|pages| pages.count > 1) {
+            PAGE_STRIP_HEIGHT
+        } else {
+            0.0
+        };
+
+        // Fullscreen draws the image edge to edge with the controls over it
+        if self.fullscreen {
+            return (
+                self.window_width - inspector_width,
+                self.window_height - strip_height,
+            );
+        }
+
+        (
+            self.window_width - modal_pad_x - nav_btn_width - container_pad - inspector_width,
+            self.window_height
+                - modal_pad_y
+                - header_height
+                - footer_height
+                - container_pad
+                - strip_height,
+        )
+    }
 
-        let available_width = self.window_width - modal_pad_x - nav_btn_width - container_pad;
-        let available_height =
-            self.window_height - modal_pad_y - header_height - footer_height - container_pad;
+    fn viewport_center(&self) -> (f32, f32) {
+        let (width, height) = self.viewport_size();
+        (width / 2.0, height / 2.0)
+    }
 
-        if available_width <= 0.0 || available_height <= 0.0 {
-            return; // Window too small
+    fn image_size(&self) -> (f32, f32) {
+        (self.image_width as f32, self.image_height as f32)
+    }
+
+    fn pan_by(&mut self, dx: f32, dy: f32) -> Task<Message> {
+        let offset = (self.scroll_offset.0 + dx, self.scroll_offset.1 + dy);
+        let clamped = viewport::clamp_offset(
+            self.viewport_size(),
+            self.image_size(),
+            self.effective_zoom(),
+            offset,
+        );
+
+        // Hitting an edge ends the fling
+        if clamped != offset {
+            self.kinetic.stop();
         }
 
-        let zoom_x = available_width / img_width as f32;
-        let zoom_y = available_height / img_height as f32;
-        self.fit_zoom = zoom_x.min(zoom_y).min(1.0);
+        self.scroll_offset = clamped;
+        self.scroll_to_offset()
     }
 
-    fn scroll_to_center(&self) -> Task<Message> {
-        scrollable::snap_to(
+    fn scroll_to_offset(&self) -> Task<Message> {
+        scrollable::scroll_to(
             self.scroll_id.clone(),
-            scrollable::RelativeOffset { x: 0.5, y: 0.5 },
+            scrollable::AbsoluteOffset {
+                x: self.scroll_offset.0,
+                y: self.scroll_offset.1,
+            },
         )
     }
 }
@@ -0,0 +1,302 @@
+//! Histogram and pixel inspector shown over the modal image
+
+use crate::{
+    fl,
+    message::{Message, ViewMessage},
+    widgets::{histogram_graph, loupe},
+};
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    theme,
+    widget::{button, column, container, icon, image::Handle, row, text, toggler},
+};
+use shared::{
+    color,
+    histogram::{self, Histogram},
+};
+use std::sync::Arc;
+use viewer_image::ImagePyramid;
+
+/// Pixels across the loupe, odd so the inspected one is in the middle
+const LOUPE_SPAN: usize = 11;
+/// Width of the panel beside the image
+pub const PANEL_WIDTH: f32 = 300.0;
+
+/// The pixel last pointed at
+#[derive(Debug, Clone)]
+pub struct Sample {
+    /// Position in the full size image
+    pub x: u32,
+    pub y: u32,
+    pub rgba: [u8; 4],
+    /// `LOUPE_SPAN` squared pixels around it, `None` past the image edges
+    neighbourhood: Vec<Option<[u8; 4]>>,
+    /// Read from a downscaled preview, so blended from several pixels
+    approximate: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Inspector {
+    /// Pixels the histogram and samples come from
+    source: Option<Handle>,
+    /// Full size of the image; the source can be a smaller preview of it
+    image_size: (u32, u32),
+    histogram: Option<Arc<Histogram>>,
+    show_clipping: bool,
+    clipping_mask: Option<Handle>,
+    sample: Option<Sample>,
+}
+
+impl Inspector {
+    /// Whether the data shown was taken from `handle`
+    pub fn is_for(&self, handle: &Handle) -> bool {
+        self.source.as_ref().map(Handle::id) == Some(handle.id())
+    }
+
+    pub fn source(&self) -> Option<&Handle> {
+        self.source.as_ref()
+    }
+
+    /// Start over for another image
+    pub fn set_source(&mut self, handle: Handle, width: u32, height: u32) {
+        self.source = Some(handle);
+        self.image_size = (width, height);
+        self.histogram = None;
+        self.clipping_mask = None;
+        self.sample = None;
+    }
+
+    pub fn set_histogram(&mut self, histogram: Arc<Histogram>) {
+        self.histogram = Some(histogram);
+    }
+
+    pub fn shows_clipping(&self) -> bool {
+        self.show_clipping
+    }
+
+    /// Turn the clipping overlay on or off, returns whether its mask has to be computed
+    pub fn toggle_clipping(&mut self) -> bool {
+        self.show_clipping = !self.show_clipping;
+        self.show_clipping && self.clipping_mask.is_none()
+    }
+
+    pub fn set_clipping_mask(&mut self, mask: Handle) {
+        self.clipping_mask = Some(mask);
+    }
+
+    /// Overlay to draw over the image while clipping is shown
+    pub fn clipping_overlay(&self) -> Option<Handle> {
+        self.clipping_mask.clone().filter(|_| self.show_clipping)
+    }
+
+    pub fn sample(&self) -> Option<&Sample> {
+        self.sample.as_ref()
+    }
+
+    /// Inspect the pixel at a position of the full size image. Tiled images are
+    /// read from the full resolution level of their `pyramid` once it is built.
+    pub fn sample_at(&mut self, x: f32, y: f32, pyramid: Option<&ImagePyramid>) {
+        let (image_width, image_height) = self.image_size;
+        if image_width == 0 || image_height == 0 {
+            return;
+        }
+        let position = (
+            (x as u32).min(image_width - 1),
+            (y as u32).min(image_height - 1),
+        );
+
+        if let Some(pyramid) =
+            pyramid.filter(|pyramid| (pyramid.width, pyramid.height) == self.image_size)
+        {
+            let pixel = |px: i64, py: i64| {
+                let px = u32::try_from(px).ok()?;
+                let py = u32::try_from(py).ok()?;
+                pyramid.pixel(px, py)
+            };
+            let source = (i64::from(position.0), i64::from(position.1));
+            if let Some(sample) = Sample::new(position, source, false, pixel) {
+                self.sample = Some(sample);
+            }
+            return;
+        }
+
+        // Otherwise the handle shown is inspected, which for a tiled image is its
+        // preview until the pyramid is built
+        let Some((width, height, pixels)) = self.source.as_ref().and_then(rgba_pixels) else {
+            return;
+        };
+        let source_x = (x * width as f32 / image_width as f32) as i64;
+        let source_y = (y * height as f32 / image_height as f32) as i64;
+        let pixel = |px: i64, py: i64| {
+            if px < 0 || py < 0 || px >= width as i64 || py >= height as i64 {
+                return None;
+            }
+            let idx = (py as usize * width as usize + px as usize) * 4;
+            pixels.get(idx..idx + 4).map(|p| [p[0], p[1], p[2], p[3]])
+        };
+
+        let approximate = width < image_width || height < image_height;
+        if let Some(sample) = Sample::new(position, (source_x, source_y), approximate, pixel) {
+            self.sample = Some(sample);
+        }
+    }
+
+    pub fn view(&self) -> Element<'static, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let mut content = column()
+            .push(text::heading(fl!("inspector-title")))
+            .spacing(spacing.space_xs)
+            .width(Length::Fill);
+
+        match self.histogram {
+            Some(ref histogram) => {
+                content = content.push(histogram_graph(histogram.clone()));
+
+                if histogram.highlights_clipped() {
+                    content = content.push(clipping_warning(fl!(
+                        "inspector-highlights-clipped",
+                        percent = percent(histogram.highlight_fraction())
+                    )));
+                }
+                if histogram.shadows_clipped() {
+                    content = content.push(clipping_warning(fl!(
+                        "inspector-shadows-clipped",
+                        percent = percent(histogram.shadow_fraction())
+                    )));
+                }
+            }
+            None => content = content.push(text::caption(fl!("inspector-histogram-loading"))),
+        }
+
+        content = content.push(
+            row()
+                .push(text::body(fl!("inspector-show-clipping")).width(Length::Fill))
+                .push(
+                    toggler(self.show_clipping)
+                        .on_toggle(|_| Message::View(ViewMessage::ToggleClipping)),
+                )
+                .align_y(Alignment::Center),
+        );
+
+        content = content.push(match self.sample {
+            Some(ref sample) => sample.view(),
+            None => text::caption(fl!("inspector-hint")).into(),
+        });
+
+        container(content)
+            .width(Length::Fixed(PANEL_WIDTH))
+            .padding(spacing.space_s)
+            .class(theme::Container::Dialog)
+            .into()
+    }
+}
+
+impl Sample {
+    /// Sample for `position` in the full size image, read around `source` in the
+    /// pixels `pixel` reads from
+    fn new(
+        (x, y): (u32, u32),
+        (source_x, source_y): (i64, i64),
+        approximate: bool,
+        pixel: impl Fn(i64, i64) -> Option<[u8; 4]>,
+    ) -> Option<Self> {
+        let rgba = pixel(source_x, source_y)?;
+        let half = (LOUPE_SPAN / 2) as i64;
+        let neighbourhood = (-half..=half)
+            .flat_map(|dy| (-half..=half).map(move |dx| (dx, dy)))
+            .map(|(dx, dy)| pixel(source_x + dx, source_y + dy))
+            .collect();
+
+        Some(Self {
+            x,
+            y,
+            rgba,
+            neighbourhood,
+            approximate,
+        })
+    }
+
+    fn view(&self) -> Element<'static, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let [r, g, b, a] = self.rgba;
+        let hex = color::hex(self.rgba);
+
+        let mut values = column()
+            .push(text::body(fl!(
+                "inspector-position",
+                x = self.x,
+                y = self.y
+            )))
+            .push(text::body(fl!(
+                "inspector-rgba",
+                r = r,
+                g = g,
+                b = b,
+                a = a
+            )))
+            .push(text::body(hex))
+            .push(text::body(color::hsl_label(r, g, b)))
+            .spacing(spacing.space_xxs);
+        if self.approximate {
+            values = values.push(text::caption(fl!("inspector-approximate")));
+        }
+        let values = values.push(
+            button::text(fl!("inspector-copy-color"))
+                .on_press(Message::View(ViewMessage::CopyColor)),
+        );
+
+        row()
+            .push(loupe(self.neighbourhood.clone(), LOUPE_SPAN).size(110.0))
+            .push(values)
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center)
+            .into()
+    }
+
+    /// Hex notation, as copied to the clipboard
+    pub fn hex(&self) -> String {
+        color::hex(self.rgba)
+    }
+}
+
+fn clipping_warning(label: String) -> Element<'static, Message> {
+    let spacing = theme::active().cosmic().spacing;
+
+    row()
+        .push(icon::from_name("dialog-warning-symbolic").size(16))
+        .push(text::caption(label))
+        .spacing(spacing.space_xxs)
+        .align_y(Alignment::Center)
+        .into()
+}
+
+fn percent(fraction: f32) -> String {
+    format!("{:.1}", fraction * 100.0)
+}
+
+/// Width, height and RGBA pixels of a decoded image handle
+fn rgba_pixels(handle: &Handle) -> Option<(u32, u32, &[u8])> {
+    match handle {
+        Handle::Rgba {
+            width,
+            height,
+            pixels,
+            ..
+        } => Some((*width, *height, &pixels[..])),
+        _ => None,
+    }
+}
+
+/// Histogram of a decoded image, slow for large ones so run it off the UI thread
+pub fn compute_histogram(handle: &Handle) -> Option<Histogram> {
+    rgba_pixels(handle).map(|(_, _, pixels)| Histogram::from_rgba(pixels))
+}
+
+/// Overlay marking the clipped pixels of a decoded image, see `compute_histogram`
+pub fn compute_clipping_mask(handle: &Handle) -> Option<Handle> {
+    rgba_pixels(handle).map(|(width, height, pixels)| {
+        Handle::from_rgba(width, height, histogram::clipping_mask(pixels))
+    })
+}
@@ -0,0 +1,192 @@
+use crate::{
+    fl,
+    message::{Message, NotificationMessage},
+};
+use cosmic::{
+    Element,
+    iced::{
+        Alignment, Length,
+        alignment::{Horizontal, Vertical},
+    },
+    theme,
+    widget::{button, column, container, icon, row, text},
+};
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+use viewer_image::{LoadError, edit::EditError};
+
+/// How long a notification stays up unless its details are open
+pub const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(8);
+/// Older notifications are dropped beyond this many
+const MAX_NOTIFICATIONS: usize = 4;
+const NOTIFICATION_WIDTH: f32 = 360.0;
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub id: u64,
+    /// What failed, e.g. "Couldn't open photo.jpg"
+    pub summary: String,
+    /// What the user can do about it
+    pub body: String,
+    /// The underlying error, shown on request
+    pub details: Option<String>,
+    expanded: bool,
+    shown_at: Instant,
+}
+
+/// Failure notifications shown over the bottom right corner of the window
+#[derive(Debug, Clone, Default)]
+pub struct Notifications {
+    items: Vec<Notification>,
+    next_id: u64,
+}
+
+impl Notifications {
+    /// Show a notification; one with the same text that is still up is shown again instead
+    pub fn push(&mut self, summary: String, body: String, details: Option<String>) {
+        self.items
+            .retain(|item| item.summary != summary || item.body != body);
+        if self.items.len() >= MAX_NOTIFICATIONS {
+            self.items.remove(0);
+        }
+
+        self.items.push(Notification {
+            id: self.next_id,
+            summary,
+            body,
+            details,
+            expanded: false,
+            shown_at: Instant::now(),
+        });
+        self.next_id += 1;
+    }
+
+    pub fn dismiss(&mut self, id: u64) {
+        self.items.retain(|item| item.id != id);
+    }
+
+    pub fn toggle_details(&mut self, id: u64) {
+        if let Some(item) = self.items.iter_mut().find(|item| item.id == id) {
+            item.expanded = !item.expanded;
+            // Closing the details gives the reader the full timeout again
+            item.shown_at = Instant::now();
+        }
+    }
+
+    /// Drop timed out notifications; ones with open details stay until dismissed
+    pub fn expire(&mut self, now: Instant) {
+        self.items.retain(|item| {
+            item.expanded || now.duration_since(item.shown_at) < NOTIFICATION_TIMEOUT
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let cards = self
+            .items
+            .iter()
+            .fold(column().spacing(spacing.space_xs), |cards, item| {
+                cards.push(item.view())
+            });
+
+        container(cards)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Horizontal::Right)
+            .align_y(Vertical::Bottom)
+            .padding(spacing.space_m)
+            .into()
+    }
+}
+
+impl Notification {
+    fn view(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let header = row()
+            .push(text::heading(&self.summary).width(Length::Fill))
+            .push(
+                button::icon(icon::from_name("window-close-symbolic"))
+                    .on_press(Message::Notification(NotificationMessage::Dismiss(self.id)))
+                    .padding(spacing.space_xxxs),
+            )
+            .spacing(spacing.space_xs)
+            .align_y(Alignment::Center);
+
+        let mut content = column()
+            .push(header)
+            .push(text::body(&self.body))
+            .spacing(spacing.space_xxs);
+
+        if let Some(details) = &self.details {
+            let label = if self.expanded {
+                fl!("notification-hide-details")
+            } else {
+                fl!("notification-show-details")
+            };
+            content = content.push(
+                button::text(label)
+                    .on_press(Message::Notification(NotificationMessage::ToggleDetails(
+                        self.id,
+                    )))
+                    .class(theme::Button::Link),
+            );
+            if self.expanded {
+                content = content.push(text::caption(details));
+            }
+        }
+
+        container(content)
+            .width(Length::Fixed(NOTIFICATION_WIDTH))
+            .padding(spacing.space_s)
+            .class(theme::Container::Dialog)
+            .into()
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Summary and advice for a failed load
+pub fn load_error_text(path: &Path, error: &LoadError) -> (String, String) {
+    let summary = fl!("error-open-failed", name = file_name(path));
+    let body = match error {
+        LoadError::NotFound => fl!("error-not-found"),
+        LoadError::PermissionDenied => fl!("error-permission-denied"),
+        LoadError::Truncated => fl!("error-truncated"),
+        LoadError::UnsupportedCodec(_) => fl!("error-unsupported-codec"),
+        LoadError::UnsupportedFormat(_) => fl!("error-unsupported-format"),
+        LoadError::TooLarge => fl!("error-too-large"),
+        _ => fl!("error-corrupt"),
+    };
+    (summary, body)
+}
+
+/// Summary and advice for a failed save, or for an edit that couldn't be applied
+pub fn edit_error_text(saving: bool, error: &EditError) -> (String, String) {
+    let summary = if saving {
+        fl!("error-save-failed")
+    } else {
+        fl!("error-edit-failed")
+    };
+    let body = match error {
+        EditError::NotFound => fl!("error-not-found"),
+        EditError::PermissionDenied => fl!("error-save-permission-denied"),
+        EditError::Truncated => fl!("error-truncated"),
+        EditError::UnsupportedCodec(_) => fl!("error-save-unsupported"),
+        EditError::InvalidCrop => fl!("error-invalid-crop"),
+        _ => fl!("error-try-again"),
+    };
+    (summary, body)
+}
@@ -0,0 +1,98 @@
+use crate::{
+    fl,
+    message::{Message, ViewMessage},
+};
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    iced_widget::scrollable::{Direction, Scrollbar},
+    theme,
+    widget::{button, container, image, row, scrollable, text},
+};
+use std::path::{Path, PathBuf};
+use viewer_image::CachedImage;
+
+/// Height of the thumbnails in the page strip
+pub const PAGE_THUMBNAIL_HEIGHT: f32 = 64.0;
+/// Height of the whole strip: the thumbnails, their padding and the scrollbar
+pub const PAGE_STRIP_HEIGHT: f32 = PAGE_THUMBNAIL_HEIGHT + 32.0;
+
+/// Pages of the multi-page file open in the modal
+#[derive(Debug, Clone)]
+pub struct Pages {
+    pub path: PathBuf,
+    pub count: usize,
+    pub current: usize,
+    /// Decoded current page; the first page is the cached image itself
+    pub image: Option<CachedImage>,
+    /// Page strip thumbnails, by page
+    pub thumbnails: Vec<Option<CachedImage>>,
+}
+
+impl Pages {
+    pub fn new(path: PathBuf, count: usize) -> Self {
+        Self {
+            path,
+            count,
+            current: 0,
+            image: None,
+            thumbnails: vec![None; count],
+        }
+    }
+
+    pub fn is_for(&self, path: &Path) -> bool {
+        self.path == path
+    }
+
+    pub fn next(&self) -> Option<usize> {
+        (self.current + 1 < self.count).then_some(self.current + 1)
+    }
+
+    pub fn prev(&self) -> Option<usize> {
+        self.current.checked_sub(1)
+    }
+
+    pub fn label(&self) -> String {
+        fl!("page-of", page = self.current + 1, total = self.count)
+    }
+
+    /// Row of page thumbnails with the current page highlighted
+    pub fn strip(&self) -> Element<'static, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let strip = self.thumbnails.iter().enumerate().fold(
+            row().spacing(spacing.space_xxs).align_y(Alignment::Center),
+            |strip, (page, thumbnail)| {
+                let content: Element<'static, Message> = match thumbnail {
+                    Some(thumbnail) => image(thumbnail.handle.clone())
+                        .height(Length::Fixed(PAGE_THUMBNAIL_HEIGHT))
+                        .into(),
+                    None => container(text::caption((page + 1).to_string()))
+                        .width(Length::Fixed(PAGE_THUMBNAIL_HEIGHT * 0.75))
+                        .height(Length::Fixed(PAGE_THUMBNAIL_HEIGHT))
+                        .center(Length::Fill)
+                        .into(),
+                };
+
+                let class = if page == self.current {
+                    theme::Button::Suggested
+                } else {
+                    theme::Button::Text
+                };
+
+                strip.push(
+                    button::custom(content)
+                        .on_press(Message::View(ViewMessage::GoToPage(page)))
+                        .padding(spacing.space_xxxs)
+                        .class(class),
+                )
+            },
+        );
+
+        container(scrollable(strip).direction(Direction::Horizontal(Scrollbar::default())))
+            .center_x(Length::Fill)
+            .center_y(Length::Fixed(PAGE_STRIP_HEIGHT))
+            .padding([spacing.space_xxs, spacing.space_xs])
+            .into()
+    }
+}
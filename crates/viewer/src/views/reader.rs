@@ -0,0 +1,296 @@
+use crate::{
+    fl,
+    message::{Message, NavMessage, ViewMessage},
+    views::gallery::modal_frame,
+};
+use cosmic::{
+    Element, Task,
+    iced::{Alignment, Length},
+    iced_widget::scrollable,
+    widget::{Id, Space, column, container, image, responsive, row, text},
+};
+use shared::reader::{self, DEFAULT_PAGE_ASPECT};
+use std::{cell::RefCell, ops::Range, path::PathBuf, sync::Arc};
+use viewer_config::{ReaderLayout, ReadingDirection, ViewerConfig};
+use viewer_image::{CachedImage, ImageCache};
+use viewer_nav::NavState;
+
+/// Space between pages of the continuous strip
+const STRIP_GAP: f32 = 8.0;
+
+/// Page tops of the continuous strip and what they were computed from
+#[derive(Debug, Clone)]
+struct StripOffsets {
+    images: Arc<[PathBuf]>,
+    width: f32,
+    cache_revision: u64,
+    offsets: Vec<f32>,
+}
+
+/// Comic reading mode of the image modal
+#[derive(Debug, Clone)]
+pub struct Reader {
+    pub is_active: bool,
+    pub layout: ReaderLayout,
+    pub direction: ReadingDirection,
+    pub cover_alone: bool,
+    pub scroll_id: Id,
+    /// Width pages were last laid out at in the continuous strip
+    pub strip_width: f32,
+    /// Recomputed only when the pages, the width or the cached page sizes change
+    strip_offsets: RefCell<Option<StripOffsets>>,
+}
+
+impl Default for Reader {
+    fn default() -> Self {
+        Self::new(&ViewerConfig::default())
+    }
+}
+
+impl Reader {
+    pub const SCROLL_ID: &'static str = "reader-scroll";
+
+    pub fn new(config: &ViewerConfig) -> Self {
+        Self {
+            is_active: false,
+            layout: config.reader_layout,
+            direction: config.reading_direction,
+            cover_alone: config.reader_cover_alone,
+            scroll_id: Id::new(Self::SCROLL_ID),
+            strip_width: 0.0,
+            strip_offsets: RefCell::new(None),
+        }
+    }
+
+    /// Whether the reader draws the pages; single pages use the regular image view
+    pub fn shows_pages(&self) -> bool {
+        self.is_active && self.layout != ReaderLayout::Single
+    }
+
+    pub fn is_right_to_left(&self) -> bool {
+        self.is_active && self.direction == ReadingDirection::RightToLeft
+    }
+
+    /// Pages on screen together with `page`, in reading order
+    pub fn pages(&self, page: usize, total: usize) -> Range<usize> {
+        match self.layout {
+            ReaderLayout::Spread => reader::spread(page, total, self.cover_alone),
+            ReaderLayout::Single | ReaderLayout::Continuous => page..(page + 1).min(total),
+        }
+    }
+
+    pub fn next_page(&self, page: usize, total: usize) -> Option<usize> {
+        match self.layout {
+            ReaderLayout::Spread => reader::next_spread(page, total, self.cover_alone),
+            ReaderLayout::Single | ReaderLayout::Continuous => {
+                (page + 1 < total).then_some(page + 1)
+            }
+        }
+    }
+
+    pub fn prev_page(&self, page: usize, total: usize) -> Option<usize> {
+        match self.layout {
+            ReaderLayout::Spread => reader::prev_spread(page, total, self.cover_alone),
+            ReaderLayout::Single | ReaderLayout::Continuous => page.checked_sub(1),
+        }
+    }
+
+    /// Page at the reading line, a third down the visible part of the strip
+    pub fn page_at(&self, nav: &NavState, cache: &ImageCache, offset: f32, height: f32) -> usize {
+        self.with_strip_offsets(nav, cache, |offsets| {
+            reader::page_at(offsets, offset + height / 3.0)
+        })
+    }
+
+    /// Scroll the continuous strip so `page` starts at the top
+    pub fn scroll_to_page(&self, nav: &NavState, cache: &ImageCache, page: usize) -> Task<Message> {
+        if self.layout != ReaderLayout::Continuous {
+            return Task::none();
+        }
+
+        let y = self.with_strip_offsets(nav, cache, |offsets| {
+            offsets.get(page).copied().unwrap_or_default()
+        });
+        scrollable::scroll_to(
+            self.scroll_id.clone(),
+            scrollable::AbsoluteOffset { x: 0.0, y },
+        )
+    }
+
+    /// Run `f` on the page tops of the continuous strip, computing them if they are stale
+    fn with_strip_offsets<R>(
+        &self,
+        nav: &NavState,
+        cache: &ImageCache,
+        f: impl FnOnce(&[f32]) -> R,
+    ) -> R {
+        let images = nav.shared_images();
+        let cache_revision = cache.revision();
+        let mut cached = self.strip_offsets.borrow_mut();
+
+        let stale = cached.as_ref().is_none_or(|strip| {
+            !Arc::ptr_eq(&strip.images, &images)
+                || strip.width != self.strip_width
+                || strip.cache_revision != cache_revision
+        });
+        if stale {
+            *cached = Some(StripOffsets {
+                offsets: strip_offsets(&images, cache, self.strip_width),
+                images,
+                width: self.strip_width,
+                cache_revision,
+            });
+        }
+
+        f(&cached.as_ref().expect("Offsets were just computed").offsets)
+    }
+
+    pub fn view(&self, nav: &NavState, cache: &ImageCache) -> Element<'static, Message> {
+        let page = nav.index().unwrap_or_default();
+        let total = nav.total();
+
+        let pages = self.pages(page, total);
+        let label = if pages.len() > 1 {
+            fl!(
+                "reader-pages",
+                first = pages.start + 1,
+                last = pages.end,
+                total = total
+            )
+        } else {
+            fl!("reader-page", page = page + 1, total = total)
+        };
+
+        let page_area = match self.layout {
+            ReaderLayout::Continuous => self.strip(nav, cache),
+            ReaderLayout::Single | ReaderLayout::Spread => self.spread(nav, cache, pages),
+        };
+
+        // The side buttons turn towards the page that lies on that side
+        let (left, right) = if self.is_right_to_left() {
+            (NavMessage::Next, NavMessage::Prev)
+        } else {
+            (NavMessage::Prev, NavMessage::Next)
+        };
+
+        modal_frame(
+            page_area,
+            text::body(label).into(),
+            Message::Nav(left),
+            Message::Nav(right),
+        )
+    }
+
+    /// Pages side by side at a common height, the first page on the right when reading right to left
+    fn spread(
+        &self,
+        nav: &NavState,
+        cache: &ImageCache,
+        pages: Range<usize>,
+    ) -> Element<'static, Message> {
+        let mut images: Vec<Option<CachedImage>> = nav.images()[pages]
+            .iter()
+            .map(|path| page_image(cache, path))
+            .collect();
+        if self.is_right_to_left() {
+            images.reverse();
+        }
+
+        responsive(move |size| {
+            let sizes: Vec<(f32, f32)> = images
+                .iter()
+                .map(|image| match image {
+                    Some(image) => (image.width as f32, image.height as f32),
+                    None => (1.0, DEFAULT_PAGE_ASPECT),
+                })
+                .collect();
+            let scale = reader::spread_scale(&sizes, (size.width, size.height));
+
+            let spread = images.iter().zip(&sizes).fold(
+                row().align_y(Alignment::Center),
+                |spread, (page, &(width, height))| {
+                    let (width, height) =
+                        (Length::Fixed(width * scale), Length::Fixed(height * scale));
+                    match page {
+                        Some(page) => {
+                            spread.push(image(page.handle.clone()).width(width).height(height))
+                        }
+                        None => spread.push(Space::new(width, height)),
+                    }
+                },
+            );
+
+            container(spread)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center(Length::Fill)
+                .into()
+        })
+        .into()
+    }
+
+    /// All pages stacked at the width of the modal
+    fn strip(&self, nav: &NavState, cache: &ImageCache) -> Element<'static, Message> {
+        let images: Vec<(Option<CachedImage>, f32)> = nav
+            .images()
+            .iter()
+            .map(|path| {
+                let page = page_image(cache, path);
+                let aspect = aspect_of(page.as_ref());
+                (page, aspect)
+            })
+            .collect();
+        let scroll_id = self.scroll_id.clone();
+
+        responsive(move |size| {
+            let width = size.width;
+            let strip = images
+                .iter()
+                .fold(column(), |strip, (page, aspect)| {
+                    let height = Length::Fixed(width * aspect);
+                    match page {
+                        Some(page) => strip.push(
+                            image(page.handle.clone())
+                                .width(Length::Fixed(width))
+                                .height(height),
+                        ),
+                        None => strip.push(Space::new(Length::Fixed(width), height)),
+                    }
+                })
+                .spacing(STRIP_GAP);
+
+            cosmic::widget::scrollable(strip)
+                .id(scroll_id.clone())
+                .on_scroll(|viewport| Message::View(ViewMessage::ReaderScrolled(viewport)))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into()
+        })
+        .into()
+    }
+}
+
+/// Sharpest version of a page in the cache
+fn page_image(cache: &ImageCache, path: &PathBuf) -> Option<CachedImage> {
+    cache
+        .get_full(path)
+        .or_else(|| cache.get_preview(path))
+        .or_else(|| cache.get_thumbnail(path))
+}
+
+/// Height to width ratio of a page, assumed until any version of it is cached
+fn aspect_of(page: Option<&CachedImage>) -> f32 {
+    page.filter(|page| page.width > 0)
+        .map_or(DEFAULT_PAGE_ASPECT, |page| {
+            page.height as f32 / page.width as f32
+        })
+}
+
+/// Top of each page of the continuous strip at `width`
+fn strip_offsets(images: &[PathBuf], cache: &ImageCache, width: f32) -> Vec<f32> {
+    let aspects: Vec<f32> = images
+        .iter()
+        .map(|path| aspect_of(page_image(cache, path).as_ref()))
+        .collect();
+    reader::strip_offsets(&aspects, width, STRIP_GAP)
+}
@@ -0,0 +1,221 @@
+use crate::{
+    fl,
+    message::{Message, SidebarMessage},
+};
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    theme,
+    widget::{Space, button, column, container, horizontal_space, icon, row, scrollable, text},
+};
+use shared::places::{FolderEntry, FolderNode, FolderTree};
+use std::path::{Path, PathBuf};
+
+const SIDEBAR_WIDTH: f32 = 260.0;
+const EXPANDER_SIZE: u16 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceKind {
+    Pictures,
+    Home,
+    Volume,
+    Bookmark,
+}
+
+impl PlaceKind {
+    fn icon_name(self) -> &'static str {
+        match self {
+            PlaceKind::Pictures => "folder-pictures-symbolic",
+            PlaceKind::Home => "user-home-symbolic",
+            PlaceKind::Volume => "drive-removable-media-symbolic",
+            PlaceKind::Bookmark => "user-bookmarks-symbolic",
+        }
+    }
+}
+
+/// A top level entry of the sidebar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Place {
+    pub path: PathBuf,
+    pub label: String,
+    pub kind: PlaceKind,
+}
+
+impl Place {
+    pub fn new(path: PathBuf, label: String, kind: PlaceKind) -> Self {
+        Self { path, label, kind }
+    }
+
+    /// Place labelled with its folder name
+    pub fn named(path: PathBuf, kind: PlaceKind) -> Self {
+        let label = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string_lossy().into_owned());
+        Self::new(path, label, kind)
+    }
+}
+
+/// Places followed by mounted volumes and bookmarks, without duplicate or missing folders
+pub fn place_list(fixed: Vec<Place>, volumes: &[PathBuf], bookmarks: &[PathBuf]) -> Vec<Place> {
+    let mut places: Vec<Place> = Vec::new();
+
+    let candidates = fixed
+        .into_iter()
+        .chain(
+            volumes
+                .iter()
+                .map(|path| Place::named(path.clone(), PlaceKind::Volume)),
+        )
+        .chain(
+            bookmarks
+                .iter()
+                .map(|path| Place::named(path.clone(), PlaceKind::Bookmark)),
+        );
+
+    for place in candidates {
+        if place.path.is_dir() && !places.iter().any(|known| known.path == place.path) {
+            places.push(place);
+        }
+    }
+
+    places
+}
+
+/// Places and a lazily expanded folder tree beside the gallery
+#[derive(Debug, Clone, Default)]
+pub struct Sidebar {
+    pub tree: FolderTree,
+    pub places: Vec<Place>,
+    /// Folder shown in the gallery
+    pub current: Option<PathBuf>,
+}
+
+impl Sidebar {
+    pub fn set_places(&mut self, places: Vec<Place>, entries: Vec<FolderEntry>) {
+        self.places = places;
+        self.tree = FolderTree::new(entries);
+    }
+
+    fn place(&self, node: &FolderNode) -> Option<&Place> {
+        if node.depth > 0 {
+            return None;
+        }
+        self.places
+            .iter()
+            .find(|place| place.path == node.entry.path)
+    }
+
+    pub fn view(&self, bookmarks: &[String]) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let can_bookmark = self.current.as_deref().is_some_and(|current| {
+            !bookmarks
+                .iter()
+                .any(|bookmark| Path::new(bookmark) == current)
+        });
+
+        let mut header = row()
+            .push(text::heading(fl!("places-title")))
+            .push(horizontal_space())
+            .align_y(Alignment::Center)
+            .padding([0, spacing.space_xxs]);
+        if can_bookmark {
+            header = header.push(
+                button::icon(icon::from_name("bookmark-new-symbolic"))
+                    .on_press(Message::Sidebar(SidebarMessage::AddBookmark)),
+            );
+        }
+
+        let nodes = self
+            .tree
+            .nodes()
+            .iter()
+            .enumerate()
+            .fold(column(), |nodes, (idx, node)| {
+                nodes.push(self.node_row(idx, node))
+            })
+            .spacing(spacing.space_xxxs);
+
+        container(
+            column()
+                .push(header)
+                .push(scrollable(nodes).width(Length::Fill).height(Length::Fill))
+                .spacing(spacing.space_xs),
+        )
+        .padding(spacing.space_xs)
+        .width(Length::Fixed(SIDEBAR_WIDTH))
+        .height(Length::Fill)
+        .class(theme::Container::Card)
+        .into()
+    }
+
+    fn node_row(&self, idx: usize, node: &FolderNode) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let place = self.place(node);
+        let entry = &node.entry;
+
+        let expander: Element<'_, Message> = if entry.has_children {
+            let (icon_name, message) = if node.expanded {
+                ("go-down-symbolic", SidebarMessage::Collapse(idx))
+            } else {
+                ("go-next-symbolic", SidebarMessage::Expand(idx))
+            };
+            button::icon(icon::from_name(icon_name).size(EXPANDER_SIZE))
+                .on_press(Message::Sidebar(message))
+                .padding(spacing.space_xxxs)
+                .into()
+        } else {
+            Space::new(
+                Length::Fixed((EXPANDER_SIZE + spacing.space_xxxs * 2) as f32),
+                Length::Shrink,
+            )
+            .into()
+        };
+
+        let icon_name = place.map_or("folder-symbolic", |place| place.kind.icon_name());
+        let label = place.map_or(entry.name.clone(), |place| place.label.clone());
+        let is_current = self.current.as_deref() == Some(entry.path.as_path());
+
+        let mut content = row()
+            .push(icon::from_name(icon_name).size(16))
+            .push(if is_current {
+                text::heading(label)
+            } else {
+                text::body(label)
+            })
+            .push(horizontal_space())
+            .spacing(spacing.space_xs)
+            .align_y(Alignment::Center);
+        if entry.image_count > 0 {
+            content = content.push(text::caption(format!("{}", entry.image_count)));
+        }
+
+        let mut node_row = row()
+            .push(Space::new(
+                Length::Fixed((node.depth as u16 * spacing.space_s) as f32),
+                Length::Shrink,
+            ))
+            .push(expander)
+            .push(
+                button::custom(content)
+                    .on_press(Message::Sidebar(SidebarMessage::Open(entry.path.clone())))
+                    .padding([spacing.space_xxxs, spacing.space_xxs])
+                    .width(Length::Fill)
+                    .class(theme::Button::Text),
+            )
+            .align_y(Alignment::Center);
+
+        if place.is_some_and(|place| place.kind == PlaceKind::Bookmark) {
+            node_row = node_row.push(
+                button::icon(icon::from_name("window-close-symbolic").size(EXPANDER_SIZE))
+                    .on_press(Message::Sidebar(SidebarMessage::RemoveBookmark(
+                        entry.path.clone(),
+                    )))
+                    .padding(spacing.space_xxxs),
+            );
+        }
+
+        node_row.into()
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{
+    fl,
+    message::{Message, ViewMessage},
+    widgets::GridSection,
+};
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    theme,
+    widget::{button, column, icon, row, scrollable, text},
+};
+use shared::{
+    date::CalendarDate,
+    timeline::{self, TimelineGrouping, TimelineSection},
+};
+use std::{collections::HashSet, sync::Arc};
+use viewer_image::KnownDates;
+
+/// Gallery grouped under capture date headers
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    pub grouping: TimelineGrouping,
+    /// Capture date of each image in navigation order, empty while they are read
+    pub dates: Vec<CalendarDate>,
+    /// Section keys of collapsed headers, kept across folder refreshes
+    pub collapsed: HashSet<CalendarDate>,
+    /// Dates read so far, so a refresh only reads new and modified files
+    pub known: Arc<KnownDates>,
+}
+
+impl Timeline {
+    pub fn new(grouping: TimelineGrouping) -> Self {
+        Self {
+            grouping,
+            ..Self::default()
+        }
+    }
+
+    /// Whether dates have been read for all `total` images
+    pub fn is_ready(&self, total: usize) -> bool {
+        total > 0 && self.dates.len() == total
+    }
+
+    pub fn sections(&self) -> Vec<TimelineSection> {
+        timeline::sections(&self.dates, self.grouping)
+    }
+
+    pub fn set_grouping(&mut self, grouping: TimelineGrouping) {
+        // Keys of another grouping don't match any section anymore
+        if grouping != self.grouping {
+            self.grouping = grouping;
+            self.collapsed.clear();
+        }
+    }
+
+    /// Collapse or expand a section, returns whether it is now collapsed
+    pub fn toggle(&mut self, section: &TimelineSection) -> bool {
+        if self.collapsed.remove(&section.key) {
+            false
+        } else {
+            self.collapsed.insert(section.key);
+            true
+        }
+    }
+
+    pub fn grid_section(&self, section: &TimelineSection) -> GridSection {
+        GridSection {
+            start: section.start,
+            len: section.len,
+            collapsed: self.collapsed.contains(&section.key),
+        }
+    }
+
+    /// Clickable date header with an expand/collapse chevron and the image count
+    pub fn header(&self, idx: usize, section: &TimelineSection) -> Element<'static, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let chevron = if self.collapsed.contains(&section.key) {
+            "go-next-symbolic"
+        } else {
+            "go-down-symbolic"
+        };
+
+        button::custom(
+            row()
+                .push(icon::from_name(chevron).size(16))
+                .push(text::heading(self.grouping.label(section.key)))
+                .push(text::caption(fl!(
+                    "timeline-section-count",
+                    count = section.len
+                )))
+                .spacing(spacing.space_xs)
+                .align_y(Alignment::Center),
+        )
+        .on_press(Message::View(ViewMessage::ToggleTimelineSection(idx)))
+        .padding([spacing.space_xxs, spacing.space_xs])
+        .class(theme::Button::Text)
+        .into()
+    }
+
+    /// Column of years on the side of the gallery, each jumps to its first section
+    pub fn scrubber(&self, sections: &[TimelineSection]) -> Element<'static, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let years = timeline::years(sections)
+            .into_iter()
+            .fold(column(), |years, (year, section)| {
+                years.push(
+                    button::text(format!("{year}"))
+                        .on_press(Message::View(ViewMessage::TimelineJump(section)))
+                        .class(theme::Button::Text),
+                )
+            })
+            .spacing(spacing.space_xxxs)
+            .align_x(Alignment::End);
+
+        scrollable(years)
+            .width(Length::Shrink)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -1,10 +1,29 @@
 // Re-export widgets from viewer-widgets crate
-pub use viewer_widgets::{GalleryGrid, GalleryItem, ScrollRequest, gallery_grid};
+pub use viewer_widgets::{
+    GalleryGrid, GalleryItem, GridArrangement, GridSection, ItemStatus, ScrollRequest,
+    gallery_grid_from_fn, visible_items,
+};
 
 // Re-export types from viewer-types for convenience
 pub use viewer_types::{CropRegion, CropSelection, DragHandle};
 
+pub mod auto_hide;
+pub mod checkerboard;
+pub mod compare;
 // Keep local crop widget for now (needs refactoring to be generic)
 pub mod crop;
+pub mod histogram_graph;
+pub mod loupe;
+pub mod minimap;
+pub mod tiled_image;
+pub mod zoom_pan;
 
+pub use auto_hide::{AutoHide, auto_hide};
+pub use checkerboard::{Checkerboard, checkerboard};
+pub use compare::{ComparePane, CompareWidget, compare_widget};
 pub use crop::{CropWidget, crop_widget};
+pub use histogram_graph::{HistogramGraph, histogram_graph};
+pub use loupe::{Loupe, loupe};
+pub use minimap::{Minimap, minimap};
+pub use tiled_image::{TiledImage, tiled_image};
+pub use zoom_pan::{ZoomPan, zoom_pan};
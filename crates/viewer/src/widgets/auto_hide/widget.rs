@@ -0,0 +1,172 @@
+//! Wrapper around the fullscreen view that reports pointer movement, so the
+//! controls can come back, and hides the cursor while they are away.
+
+use crate::message::{Message, ViewMessage};
+use cosmic::{
+    Element, Renderer,
+    iced::{
+        Length, Rectangle, Size, Vector,
+        advanced::{
+            Clipboard, Layout, Shell, Widget,
+            layout::{Limits, Node},
+            overlay,
+            renderer::Style,
+            widget::{Operation, Tree},
+        },
+        event::{Event, Status},
+        mouse::{self, Cursor},
+    },
+};
+
+pub struct AutoHide<'a> {
+    content: Element<'a, Message>,
+    hide_cursor: bool,
+}
+
+impl<'a> AutoHide<'a> {
+    pub fn new(content: impl Into<Element<'a, Message>>) -> Self {
+        Self {
+            content: content.into(),
+            hide_cursor: false,
+        }
+    }
+
+    /// Hide the cursor over the content
+    pub fn hide_cursor(mut self, hide_cursor: bool) -> Self {
+        self.hide_cursor = hide_cursor;
+        self
+    }
+}
+
+impl Widget<Message, cosmic::Theme, Renderer> for AutoHide<'_> {
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&mut self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_mut(&mut self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn layout(&self, tree: &mut Tree, renderer: &Renderer, limits: &Limits) -> Node {
+        let child = self
+            .content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits);
+        Node::with_children(child.size(), vec![child])
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &cosmic::Theme,
+        style: &Style,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout.children().next().unwrap(),
+            cursor,
+            viewport,
+        );
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.content.as_widget().operate(
+            &mut tree.children[0],
+            layout.children().next().unwrap(),
+            renderer,
+            operation,
+        );
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> Status {
+        // Watched before the content sees it, panning captures the movement
+        if let Event::Mouse(mouse::Event::CursorMoved { .. }) = event {
+            shell.publish(Message::View(ViewMessage::PointerMoved));
+        }
+
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout.children().next().unwrap(),
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if self.hide_cursor && cursor.is_over(layout.bounds()) {
+            return mouse::Interaction::Hidden;
+        }
+
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout.children().next().unwrap(),
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, cosmic::Theme, Renderer>> {
+        self.content.as_widget_mut().overlay(
+            &mut tree.children[0],
+            layout.children().next().unwrap(),
+            renderer,
+            translation,
+        )
+    }
+}
+
+impl<'a> From<AutoHide<'a>> for Element<'a, Message> {
+    fn from(widget: AutoHide<'a>) -> Self {
+        Self::new(widget)
+    }
+}
+
+/// Helper function to create an AutoHide wrapper
+pub fn auto_hide<'a>(content: impl Into<Element<'a, Message>>) -> AutoHide<'a> {
+    AutoHide::new(content)
+}
@@ -0,0 +1,128 @@
+//! Checkerboard drawn behind images with transparency. Squares are aligned to
+//! the widget, so the pattern moves with the image when it is scrolled.
+
+use crate::message::Message;
+use cosmic::{
+    Element, Renderer,
+    iced::{
+        Color, Length, Point, Rectangle, Size,
+        advanced::{
+            Layout, Renderer as _, Widget,
+            layout::{Limits, Node},
+            renderer::{Quad, Style},
+            widget::Tree,
+        },
+        mouse::Cursor,
+    },
+};
+
+/// Side of one square in logical pixels
+const SQUARE_SIZE: f32 = 12.0;
+const LIGHT: Color = Color::from_rgb(0.8, 0.8, 0.8);
+const DARK: Color = Color::from_rgb(0.6, 0.6, 0.6);
+
+pub struct Checkerboard {
+    width: Length,
+    height: Length,
+}
+
+impl Checkerboard {
+    pub fn new() -> Self {
+        Self {
+            width: Length::Fill,
+            height: Length::Fill,
+        }
+    }
+
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    pub fn height(mut self, height: impl Into<Length>) -> Self {
+        self.height = height.into();
+        self
+    }
+}
+
+impl Default for Checkerboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget<Message, cosmic::Theme, Renderer> for Checkerboard {
+    fn size(&self) -> Size<Length> {
+        Size::new(self.width, self.height)
+    }
+
+    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &Limits) -> Node {
+        Node::new(limits.resolve(self.width, self.height, Size::ZERO))
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &cosmic::Theme,
+        _style: &Style,
+        layout: Layout<'_>,
+        _cursor: Cursor,
+        viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        // Only the visible part is drawn, zoomed images can be far larger than the window
+        let Some(visible) = viewport.intersection(&bounds) else {
+            return;
+        };
+
+        renderer.fill_quad(
+            Quad {
+                bounds: visible,
+                ..Quad::default()
+            },
+            LIGHT,
+        );
+
+        let first_col = ((visible.x - bounds.x) / SQUARE_SIZE).floor() as i64;
+        let first_row = ((visible.y - bounds.y) / SQUARE_SIZE).floor() as i64;
+        let last_col = ((visible.x + visible.width - bounds.x) / SQUARE_SIZE).ceil() as i64;
+        let last_row = ((visible.y + visible.height - bounds.y) / SQUARE_SIZE).ceil() as i64;
+
+        for row in first_row..last_row {
+            for col in first_col..last_col {
+                if (row + col) % 2 == 0 {
+                    continue;
+                }
+
+                let square = Rectangle::new(
+                    Point::new(
+                        bounds.x + col as f32 * SQUARE_SIZE,
+                        bounds.y + row as f32 * SQUARE_SIZE,
+                    ),
+                    Size::new(SQUARE_SIZE, SQUARE_SIZE),
+                );
+                if let Some(square) = square.intersection(&visible) {
+                    renderer.fill_quad(
+                        Quad {
+                            bounds: square,
+                            ..Quad::default()
+                        },
+                        DARK,
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl<'a> From<Checkerboard> for Element<'a, Message> {
+    fn from(widget: Checkerboard) -> Self {
+        Self::new(widget)
+    }
+}
+
+/// Helper function to create a Checkerboard
+pub fn checkerboard() -> Checkerboard {
+    Checkerboard::new()
+}
@@ -0,0 +1,3 @@
+mod widget;
+
+pub use widget::{ComparePane, CompareWidget, compare_widget};
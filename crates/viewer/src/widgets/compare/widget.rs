@@ -0,0 +1,396 @@
+//! Compare widget that renders two to four images with linked zoom and pan.
+//! In swipe and onion-skin modes the first two images are stacked on top of each other.
+
+use crate::message::{CompareMessage, CompareMode, Message, ViewMessage};
+use cosmic::{
+    Element, Renderer,
+    iced::{
+        Color, Length, Point, Rectangle, Size, Vector,
+        advanced::{
+            Clipboard, Layout, Shell, Widget,
+            image::Renderer as ImageRenderer,
+            layout::{Limits, Node},
+            renderer::{Quad, Renderer as QuadRenderer},
+            widget::{Tree, tree},
+        },
+        event::{Event, Status},
+        mouse::{self, Button, Cursor, ScrollDelta},
+    },
+    widget::image::Handle,
+};
+
+const PANE_GAP: f32 = 4.0;
+const DIVIDER_WIDTH: f32 = 2.0;
+const DIVIDER_HIT_WIDTH: f32 = 16.0;
+const DIVIDER_COLOR: Color = Color::WHITE;
+const PLACEHOLDER_COLOR: Color = Color::from_rgba(0.5, 0.5, 0.5, 0.3);
+
+/// An image shown in a compare pane; `None` while it is still loading
+#[derive(Debug, Clone)]
+pub struct ComparePane {
+    pub handle: Option<Handle>,
+    pub width: u32,
+    pub height: u32,
+}
+
+pub struct CompareWidget {
+    panes: Vec<ComparePane>,
+    mode: CompareMode,
+    /// Absolute zoom level, or `None` to fit each pane
+    zoom: Option<f32>,
+    /// Linked view center in normalized image coordinates
+    center: (f32, f32),
+    /// Swipe divider position across the widget (0.0 - 1.0)
+    swipe: f32,
+    /// Opacity of the top image in onion-skin mode
+    opacity: f32,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    drag_origin: Option<Point>,
+    dragging_divider: bool,
+}
+
+impl CompareWidget {
+    pub fn new(panes: Vec<ComparePane>, mode: CompareMode) -> Self {
+        Self {
+            panes,
+            mode,
+            zoom: None,
+            center: (0.5, 0.5),
+            swipe: 0.5,
+            opacity: 0.5,
+        }
+    }
+
+    pub fn zoom(mut self, zoom: Option<f32>) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn center(mut self, center: (f32, f32)) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn swipe(mut self, swipe: f32) -> Self {
+        self.swipe = swipe.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    fn is_overlay(&self) -> bool {
+        self.mode != CompareMode::Split && self.panes.len() >= 2
+    }
+
+    /// Split the widget bounds into one rectangle per pane
+    fn pane_bounds(&self, bounds: Rectangle) -> Vec<Rectangle> {
+        if self.is_overlay() {
+            return vec![bounds; 2];
+        }
+
+        match self.panes.len() {
+            0 => Vec::new(),
+            1 => vec![bounds],
+            2 => {
+                let width = (bounds.width - PANE_GAP) / 2.0;
+                vec![
+                    Rectangle::new(bounds.position(), Size::new(width, bounds.height)),
+                    Rectangle::new(
+                        Point::new(bounds.x + width + PANE_GAP, bounds.y),
+                        Size::new(width, bounds.height),
+                    ),
+                ]
+            }
+            count => {
+                let width = (bounds.width - PANE_GAP) / 2.0;
+                let height = (bounds.height - PANE_GAP) / 2.0;
+                (0..count.min(4))
+                    .map(|idx| {
+                        let col = (idx % 2) as f32;
+                        let row = (idx / 2) as f32;
+                        Rectangle::new(
+                            Point::new(
+                                bounds.x + col * (width + PANE_GAP),
+                                bounds.y + row * (height + PANE_GAP),
+                            ),
+                            Size::new(width, height),
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn scale_for(&self, pane: &ComparePane, bounds: Rectangle) -> f32 {
+        self.zoom.unwrap_or_else(|| {
+            let scale_x = bounds.width / pane.width.max(1) as f32;
+            let scale_y = bounds.height / pane.height.max(1) as f32;
+            scale_x.min(scale_y).min(1.0)
+        })
+    }
+
+    /// Screen rectangle of an image so that the linked center sits in the middle of the pane.
+    /// Axes where the image is smaller than the pane are simply centered.
+    fn image_rect(&self, pane: &ComparePane, bounds: Rectangle) -> Rectangle {
+        let scale = self.scale_for(pane, bounds);
+        let width = pane.width as f32 * scale;
+        let height = pane.height as f32 * scale;
+
+        let pane_center = bounds.center();
+        let x = if width <= bounds.width {
+            pane_center.x - width / 2.0
+        } else {
+            pane_center.x - self.center.0 * width
+        };
+        let y = if height <= bounds.height {
+            pane_center.y - height / 2.0
+        } else {
+            pane_center.y - self.center.1 * height
+        };
+
+        Rectangle::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    fn divider_x(&self, bounds: Rectangle) -> f32 {
+        bounds.x + bounds.width * self.swipe
+    }
+
+    fn draw_pane(
+        &self,
+        renderer: &mut Renderer,
+        pane: &ComparePane,
+        bounds: Rectangle,
+        clip: Rectangle,
+        opacity: f32,
+    ) {
+        renderer.with_layer(clip, |renderer| match &pane.handle {
+            Some(handle) => {
+                renderer.draw_image(
+                    handle.clone(),
+                    cosmic::iced::widget::image::FilterMethod::Linear,
+                    self.image_rect(pane, bounds),
+                    cosmic::iced::Radians(0.0),
+                    opacity,
+                    [0.0; 4],
+                );
+            }
+            None => {
+                let size = bounds.width.min(bounds.height) / 4.0;
+                renderer.fill_quad(
+                    Quad {
+                        bounds: Rectangle::new(
+                            bounds.center() - Vector::new(size / 2.0, size / 2.0),
+                            Size::new(size, size),
+                        ),
+                        ..Quad::default()
+                    },
+                    PLACEHOLDER_COLOR,
+                );
+            }
+        });
+    }
+}
+
+impl Widget<Message, cosmic::Theme, Renderer> for CompareWidget {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fill, Length::Fill)
+    }
+
+    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &Limits) -> Node {
+        Node::new(limits.max())
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &cosmic::Theme,
+        _style: &cosmic::iced::advanced::renderer::Style,
+        layout: Layout<'_>,
+        _cursor: Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+
+        if !self.is_overlay() {
+            for (pane, pane_bounds) in self.panes.iter().zip(self.pane_bounds(bounds)) {
+                self.draw_pane(renderer, pane, pane_bounds, pane_bounds, 1.0);
+            }
+            return;
+        }
+
+        let (bottom, top) = (&self.panes[0], &self.panes[1]);
+
+        match self.mode {
+            CompareMode::Swipe => {
+                let divider = self.divider_x(bounds);
+                let left = Rectangle::new(
+                    bounds.position(),
+                    Size::new(divider - bounds.x, bounds.height),
+                );
+                let right = Rectangle::new(
+                    Point::new(divider, bounds.y),
+                    Size::new(bounds.x + bounds.width - divider, bounds.height),
+                );
+
+                self.draw_pane(renderer, bottom, bounds, left, 1.0);
+                self.draw_pane(renderer, top, bounds, right, 1.0);
+
+                renderer.fill_quad(
+                    Quad {
+                        bounds: Rectangle::new(
+                            Point::new(divider - DIVIDER_WIDTH / 2.0, bounds.y),
+                            Size::new(DIVIDER_WIDTH, bounds.height),
+                        ),
+                        ..Quad::default()
+                    },
+                    DIVIDER_COLOR,
+                );
+            }
+            CompareMode::Onion => {
+                self.draw_pane(renderer, bottom, bounds, bounds, 1.0);
+                self.draw_pane(renderer, top, bounds, bounds, self.opacity);
+            }
+            CompareMode::Split => {}
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) -> Status {
+        let state = tree.state.downcast_mut::<State>();
+        let bounds = layout.bounds();
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(Button::Left)) => {
+                if let Some(pos) = cursor.position_over(bounds) {
+                    state.dragging_divider = self.mode == CompareMode::Swipe
+                        && self.is_overlay()
+                        && (pos.x - self.divider_x(bounds)).abs() <= DIVIDER_HIT_WIDTH / 2.0;
+                    state.drag_origin = Some(pos);
+                    return Status::Captured;
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let Some(origin) = state.drag_origin else {
+                    return Status::Ignored;
+                };
+
+                if state.dragging_divider {
+                    let swipe = (position.x - bounds.x) / bounds.width.max(1.0);
+                    shell.publish(Message::Compare(CompareMessage::Swipe(
+                        swipe.clamp(0.0, 1.0),
+                    )));
+                    return Status::Captured;
+                }
+
+                // Pan relative to the first pane so all panes stay linked
+                if let (Some(pane), Some(pane_bounds)) = (
+                    self.panes.first(),
+                    self.pane_bounds(bounds).first().copied(),
+                ) {
+                    let rect = self.image_rect(pane, pane_bounds);
+                    let dx = (origin.x - position.x) / rect.width.max(1.0);
+                    let dy = (origin.y - position.y) / rect.height.max(1.0);
+                    state.drag_origin = Some(position);
+
+                    shell.publish(Message::Compare(CompareMessage::Pan(
+                        (self.center.0 + dx).clamp(0.0, 1.0),
+                        (self.center.1 + dy).clamp(0.0, 1.0),
+                    )));
+                    return Status::Captured;
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(Button::Left)) => {
+                if state.drag_origin.take().is_some() {
+                    state.dragging_divider = false;
+                    return Status::Captured;
+                }
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                if cursor.is_over(bounds) {
+                    let y = match delta {
+                        ScrollDelta::Lines { y, .. } | ScrollDelta::Pixels { y, .. } => y,
+                    };
+
+                    if y > 0.0 {
+                        shell.publish(Message::View(ViewMessage::ZoomIn));
+                    } else if y < 0.0 {
+                        shell.publish(Message::View(ViewMessage::ZoomOut));
+                    }
+                    return Status::Captured;
+                }
+            }
+            _ => {}
+        }
+
+        Status::Ignored
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = layout.bounds();
+
+        if state.dragging_divider {
+            return mouse::Interaction::ResizingHorizontally;
+        }
+
+        if state.drag_origin.is_some() {
+            return mouse::Interaction::Grabbing;
+        }
+
+        if let Some(pos) = cursor.position()
+            && bounds.contains(pos)
+        {
+            if self.mode == CompareMode::Swipe
+                && self.is_overlay()
+                && (pos.x - self.divider_x(bounds)).abs() <= DIVIDER_HIT_WIDTH / 2.0
+            {
+                return mouse::Interaction::ResizingHorizontally;
+            }
+            return mouse::Interaction::Grab;
+        }
+
+        mouse::Interaction::default()
+    }
+}
+
+impl<'a> From<CompareWidget> for Element<'a, Message> {
+    fn from(widget: CompareWidget) -> Self {
+        Self::new(widget)
+    }
+}
+
+/// Helper function to create a CompareWidget
+pub fn compare_widget(panes: Vec<ComparePane>, mode: CompareMode) -> CompareWidget {
+    CompareWidget::new(panes, mode)
+}
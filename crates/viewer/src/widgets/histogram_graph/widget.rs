@@ -0,0 +1,128 @@
+//! Red, green, blue and luminance histograms drawn over each other
+
+use crate::message::Message;
+use cosmic::{
+    Element, Renderer,
+    iced::{
+        Border, Color, Length, Point, Rectangle, Size,
+        advanced::{
+            Layout, Renderer as _, Widget,
+            layout::{Limits, Node},
+            renderer::{Quad, Style},
+            widget::Tree,
+        },
+        mouse::Cursor,
+    },
+};
+use shared::histogram::Histogram;
+use std::sync::Arc;
+
+const BACKGROUND: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.55);
+const LUMA: Color = Color::from_rgba(1.0, 1.0, 1.0, 0.35);
+const RED: Color = Color::from_rgba(1.0, 0.25, 0.25, 0.5);
+const GREEN: Color = Color::from_rgba(0.25, 1.0, 0.25, 0.5);
+const BLUE: Color = Color::from_rgba(0.3, 0.45, 1.0, 0.5);
+
+pub struct HistogramGraph {
+    histogram: Arc<Histogram>,
+    width: Length,
+    height: Length,
+}
+
+impl HistogramGraph {
+    pub fn new(histogram: Arc<Histogram>) -> Self {
+        Self {
+            histogram,
+            width: Length::Fill,
+            height: Length::Fixed(96.0),
+        }
+    }
+
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    pub fn height(mut self, height: impl Into<Length>) -> Self {
+        self.height = height.into();
+        self
+    }
+}
+
+impl Widget<Message, cosmic::Theme, Renderer> for HistogramGraph {
+    fn size(&self) -> Size<Length> {
+        Size::new(self.width, self.height)
+    }
+
+    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &Limits) -> Node {
+        Node::new(limits.resolve(self.width, self.height, Size::ZERO))
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &cosmic::Theme,
+        _style: &Style,
+        layout: Layout<'_>,
+        _cursor: Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+
+        renderer.fill_quad(
+            Quad {
+                bounds,
+                border: Border {
+                    radius: 4.0.into(),
+                    ..Border::default()
+                },
+                ..Quad::default()
+            },
+            BACKGROUND,
+        );
+
+        let peak = self.histogram.peak() as f32;
+        let bin_width = bounds.width / 256.0;
+
+        for (channel, color) in [
+            (&self.histogram.luma, LUMA),
+            (&self.histogram.red, RED),
+            (&self.histogram.green, GREEN),
+            (&self.histogram.blue, BLUE),
+        ] {
+            for (bin, &count) in channel.iter().enumerate() {
+                if count == 0 {
+                    continue;
+                }
+
+                // Clipped end bins can exceed the peak, they are cut off at the top
+                let height = (count as f32 / peak).min(1.0) * bounds.height;
+                renderer.fill_quad(
+                    Quad {
+                        bounds: Rectangle::new(
+                            Point::new(
+                                bounds.x + bin as f32 * bin_width,
+                                bounds.y + bounds.height - height,
+                            ),
+                            Size::new(bin_width.max(1.0), height),
+                        ),
+                        ..Quad::default()
+                    },
+                    color,
+                );
+            }
+        }
+    }
+}
+
+impl<'a> From<HistogramGraph> for Element<'a, Message> {
+    fn from(widget: HistogramGraph) -> Self {
+        Self::new(widget)
+    }
+}
+
+/// Helper function to create a HistogramGraph
+pub fn histogram_graph(histogram: Arc<Histogram>) -> HistogramGraph {
+    HistogramGraph::new(histogram)
+}
@@ -0,0 +1,135 @@
+//! Magnified square of pixels around the inspected one, drawn as blocks so
+//! they stay crisp at any magnification
+
+use crate::message::Message;
+use cosmic::{
+    Element, Renderer,
+    iced::{
+        Border, Color, Length, Point, Rectangle, Size,
+        advanced::{
+            Layout, Renderer as _, Widget,
+            layout::{Limits, Node},
+            renderer::{Quad, Style},
+            widget::Tree,
+        },
+        mouse::Cursor,
+    },
+};
+
+/// Pixels outside the image
+const OUTSIDE: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.0);
+
+pub struct Loupe {
+    /// Row-major RGBA of a `span` by `span` square, `None` outside the image
+    pixels: Vec<Option<[u8; 4]>>,
+    span: usize,
+    size: f32,
+}
+
+impl Loupe {
+    pub fn new(pixels: Vec<Option<[u8; 4]>>, span: usize) -> Self {
+        Self {
+            pixels,
+            span,
+            size: 132.0,
+        }
+    }
+
+    /// Side of the whole loupe in logical pixels
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+}
+
+impl Widget<Message, cosmic::Theme, Renderer> for Loupe {
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fixed(self.size), Length::Fixed(self.size))
+    }
+
+    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &Limits) -> Node {
+        Node::new(limits.resolve(
+            Length::Fixed(self.size),
+            Length::Fixed(self.size),
+            Size::ZERO,
+        ))
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &cosmic::Theme,
+        _style: &Style,
+        layout: Layout<'_>,
+        _cursor: Cursor,
+        _viewport: &Rectangle,
+    ) {
+        if self.span == 0 {
+            return;
+        }
+
+        let bounds = layout.bounds();
+        let cell = bounds.width.min(bounds.height) / self.span as f32;
+        let cell_bounds = |col: usize, row: usize| {
+            Rectangle::new(
+                Point::new(bounds.x + col as f32 * cell, bounds.y + row as f32 * cell),
+                Size::new(cell, cell),
+            )
+        };
+
+        for (idx, pixel) in self.pixels.iter().enumerate() {
+            let color = pixel.map_or(OUTSIDE, |[r, g, b, a]| {
+                Color::from_rgba8(r, g, b, a as f32 / 255.0)
+            });
+            renderer.fill_quad(
+                Quad {
+                    bounds: cell_bounds(idx % self.span, idx / self.span),
+                    ..Quad::default()
+                },
+                color,
+            );
+        }
+
+        // Outline the inspected pixel in both black and white so it shows on any color
+        let center = cell_bounds(self.span / 2, self.span / 2);
+        for (inset, color) in [(0.0, Color::BLACK), (1.0, Color::WHITE)] {
+            renderer.fill_quad(
+                Quad {
+                    bounds: center.shrink(inset),
+                    border: Border {
+                        width: 1.0,
+                        color,
+                        ..Border::default()
+                    },
+                    ..Quad::default()
+                },
+                Color::TRANSPARENT,
+            );
+        }
+
+        renderer.fill_quad(
+            Quad {
+                bounds,
+                border: Border {
+                    width: 1.0,
+                    color: Color::from_rgba(0.5, 0.5, 0.5, 0.8),
+                    ..Border::default()
+                },
+                ..Quad::default()
+            },
+            Color::TRANSPARENT,
+        );
+    }
+}
+
+impl<'a> From<Loupe> for Element<'a, Message> {
+    fn from(widget: Loupe) -> Self {
+        Self::new(widget)
+    }
+}
+
+/// Helper function to create a Loupe
+pub fn loupe(pixels: Vec<Option<[u8; 4]>>, span: usize) -> Loupe {
+    Loupe::new(pixels, span)
+}
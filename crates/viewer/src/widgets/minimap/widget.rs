@@ -0,0 +1,290 @@
+//! Navigator showing the whole zoomed image with the part in view outlined.
+//! Pressing or dragging in it centers the view on that point.
+
+use crate::message::{Message, ViewMessage};
+use cosmic::{
+    Element, Renderer,
+    iced::{
+        Border, Color, Length, Point, Rectangle, Size,
+        advanced::{
+            Clipboard, Layout, Shell, Widget,
+            image::Renderer as ImageRenderer,
+            layout::{Limits, Node},
+            renderer::{Quad, Renderer as QuadRenderer, Style},
+            widget::{Tree, tree},
+        },
+        event::{Event, Status},
+        mouse::{self, Button, Cursor},
+    },
+    widget::image::Handle,
+};
+
+const BACKGROUND: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.6);
+/// Dims the parts of the image out of view
+const SHADE: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.45);
+const FRAME_WIDTH: f32 = 2.0;
+const PADDING: f32 = 4.0;
+
+pub struct Minimap {
+    handle: Handle,
+    /// Full size of the image, the handle can be a smaller preview of it
+    image_size: (f32, f32),
+    /// Part in view as `(x, y, width, height)` fractions of the image
+    visible: (f32, f32, f32, f32),
+    size: f32,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Offset from the cursor to the center of the view rectangle while dragging
+    grab: Option<(f32, f32)>,
+}
+
+impl Minimap {
+    pub fn new(handle: Handle, image_size: (f32, f32), visible: (f32, f32, f32, f32)) -> Self {
+        Self {
+            handle,
+            image_size,
+            visible,
+            size: 160.0,
+        }
+    }
+
+    /// Longest side of the image in the navigator, in logical pixels
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    fn outer_size(&self) -> Size {
+        let (width, height) = self.image_size;
+        let scale = if width > 0.0 && height > 0.0 {
+            self.size / width.max(height)
+        } else {
+            0.0
+        };
+        Size::new(
+            (width * scale).max(1.0) + PADDING * 2.0,
+            (height * scale).max(1.0) + PADDING * 2.0,
+        )
+    }
+
+    /// Where the image is drawn inside the widget bounds
+    fn image_bounds(bounds: Rectangle) -> Rectangle {
+        bounds.shrink(PADDING)
+    }
+
+    fn visible_bounds(&self, image: Rectangle) -> Rectangle {
+        let (x, y, width, height) = self.visible;
+        Rectangle::new(
+            Point::new(image.x + x * image.width, image.y + y * image.height),
+            Size::new(width * image.width, height * image.height),
+        )
+    }
+
+    /// Cursor position as fractions of the image
+    fn fraction_at(image: Rectangle, position: Point) -> (f32, f32) {
+        (
+            ((position.x - image.x) / image.width).clamp(0.0, 1.0),
+            ((position.y - image.y) / image.height).clamp(0.0, 1.0),
+        )
+    }
+}
+
+impl Widget<Message, cosmic::Theme, Renderer> for Minimap {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        let size = self.outer_size();
+        Size::new(Length::Fixed(size.width), Length::Fixed(size.height))
+    }
+
+    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &Limits) -> Node {
+        let size = self.outer_size();
+        Node::new(limits.resolve(
+            Length::Fixed(size.width),
+            Length::Fixed(size.height),
+            Size::ZERO,
+        ))
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &cosmic::Theme,
+        _style: &Style,
+        layout: Layout<'_>,
+        _cursor: Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let image = Self::image_bounds(bounds);
+
+        renderer.fill_quad(
+            Quad {
+                bounds,
+                border: Border {
+                    radius: 4.0.into(),
+                    ..Border::default()
+                },
+                ..Quad::default()
+            },
+            BACKGROUND,
+        );
+
+        renderer.draw_image(
+            self.handle.clone(),
+            cosmic::iced::widget::image::FilterMethod::Linear,
+            image,
+            cosmic::iced::Radians(0.0),
+            1.0,
+            [0.0; 4],
+        );
+
+        // Shade around the view rectangle: above, below, left and right of it
+        let visible = self.visible_bounds(image);
+        let shades = [
+            Rectangle::new(
+                image.position(),
+                Size::new(image.width, visible.y - image.y),
+            ),
+            Rectangle::new(
+                Point::new(image.x, visible.y + visible.height),
+                Size::new(
+                    image.width,
+                    image.y + image.height - visible.y - visible.height,
+                ),
+            ),
+            Rectangle::new(
+                Point::new(image.x, visible.y),
+                Size::new(visible.x - image.x, visible.height),
+            ),
+            Rectangle::new(
+                Point::new(visible.x + visible.width, visible.y),
+                Size::new(
+                    image.x + image.width - visible.x - visible.width,
+                    visible.height,
+                ),
+            ),
+        ];
+        renderer.with_layer(image, |renderer| {
+            for bounds in shades {
+                if bounds.width > 0.0 && bounds.height > 0.0 {
+                    renderer.fill_quad(
+                        Quad {
+                            bounds,
+                            ..Quad::default()
+                        },
+                        SHADE,
+                    );
+                }
+            }
+
+            renderer.fill_quad(
+                Quad {
+                    bounds: visible,
+                    border: Border {
+                        width: FRAME_WIDTH,
+                        color: Color::WHITE,
+                        ..Border::default()
+                    },
+                    ..Quad::default()
+                },
+                Color::TRANSPARENT,
+            );
+        });
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) -> Status {
+        let state = tree.state.downcast_mut::<State>();
+        let image = Self::image_bounds(layout.bounds());
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(Button::Left)) => {
+                let Some(position) = cursor.position_over(layout.bounds()) else {
+                    return Status::Ignored;
+                };
+
+                // Grabbing the rectangle keeps it under the cursor, elsewhere it jumps there
+                let (x, y, width, height) = self.visible;
+                let (fx, fy) = Self::fraction_at(image, position);
+                let grab = if self.visible_bounds(image).contains(position) {
+                    (fx - (x + width / 2.0), fy - (y + height / 2.0))
+                } else {
+                    (0.0, 0.0)
+                };
+                state.grab = Some(grab);
+
+                shell.publish(Message::View(ViewMessage::MinimapPan {
+                    x: fx - grab.0,
+                    y: fy - grab.1,
+                }));
+                Status::Captured
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let Some((grab_x, grab_y)) = state.grab else {
+                    return Status::Ignored;
+                };
+
+                let (fx, fy) = Self::fraction_at(image, position);
+                shell.publish(Message::View(ViewMessage::MinimapPan {
+                    x: fx - grab_x,
+                    y: fy - grab_y,
+                }));
+                Status::Captured
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(Button::Left)) if state.grab.is_some() => {
+                state.grab = None;
+                Status::Captured
+            }
+            _ => Status::Ignored,
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+
+        if state.grab.is_some() {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(layout.bounds()) {
+            mouse::Interaction::Grab
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+impl<'a> From<Minimap> for Element<'a, Message> {
+    fn from(widget: Minimap) -> Self {
+        Self::new(widget)
+    }
+}
+
+/// Helper function to create a Minimap
+pub fn minimap(handle: Handle, image_size: (f32, f32), visible: (f32, f32, f32, f32)) -> Minimap {
+    Minimap::new(handle, image_size, visible)
+}
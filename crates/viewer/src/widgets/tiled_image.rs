@@ -0,0 +1,3 @@
+mod widget;
+
+pub use widget::{TiledImage, tiled_image};
@@ -0,0 +1,158 @@
+//! Image widget for very large images. The fit-resolution preview is stretched over the
+//! whole image and the tiles of the matching pyramid level are drawn on top where visible.
+
+use crate::message::Message;
+use cosmic::{
+    Element, Renderer,
+    iced::{
+        Length, Point, Rectangle, Size,
+        advanced::{
+            Layout, Widget,
+            image::Renderer as ImageRenderer,
+            layout::{Limits, Node},
+            renderer::Style,
+            widget::Tree,
+        },
+        mouse::Cursor,
+        widget::image::FilterMethod,
+    },
+    widget::image::Handle,
+};
+use shared::tiling::{self, TILED_PREVIEW_SIZE};
+use std::sync::Arc;
+use viewer_image::ImagePyramid;
+
+pub struct TiledImage {
+    preview: Handle,
+    pyramid: Option<Arc<ImagePyramid>>,
+    width: f32,
+    height: f32,
+}
+
+impl TiledImage {
+    pub fn new(preview: Handle, pyramid: Option<Arc<ImagePyramid>>) -> Self {
+        Self {
+            preview,
+            pyramid,
+            width: 0.0,
+            height: 0.0,
+        }
+    }
+
+    /// Size of the scaled image on screen
+    pub fn dimensions(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    fn draw_tiles(
+        &self,
+        renderer: &mut Renderer,
+        pyramid: &ImagePyramid,
+        bounds: Rectangle,
+        viewport: &Rectangle,
+    ) {
+        let zoom = bounds.width / pyramid.width.max(1) as f32;
+        let level = tiling::level_for_zoom(zoom, pyramid.level_count());
+        let scale = tiling::level_scale(level);
+
+        // The preview is already as sharp as this level
+        let preview_scale = TILED_PREVIEW_SIZE as f32 / pyramid.width.max(pyramid.height) as f32;
+        if scale <= preview_scale {
+            return;
+        }
+
+        let Some(visible) = viewport.intersection(&bounds) else {
+            return;
+        };
+
+        let (level_width, level_height) = tiling::level_size(pyramid.width, pyramid.height, level);
+        let tiles = tiling::visible_tiles(
+            pyramid.width,
+            pyramid.height,
+            level,
+            (
+                (visible.x - bounds.x) / zoom,
+                (visible.y - bounds.y) / zoom,
+                visible.width / zoom,
+                visible.height / zoom,
+            ),
+        );
+
+        // Level pixels to screen pixels
+        let factor = zoom / scale;
+        for id in tiles {
+            let Some(handle) = pyramid.tile(id) else {
+                continue;
+            };
+
+            let (x, y, width, height) =
+                tiling::tile_rect(level_width, level_height, id.col, id.row);
+            let rect = Rectangle::new(
+                Point::new(bounds.x + x as f32 * factor, bounds.y + y as f32 * factor),
+                Size::new(width as f32 * factor, height as f32 * factor),
+            );
+
+            renderer.draw_image(
+                handle,
+                FilterMethod::Linear,
+                rect,
+                cosmic::iced::Radians(0.0),
+                1.0,
+                [0.0; 4],
+            );
+        }
+    }
+}
+
+impl Widget<Message, cosmic::Theme, Renderer> for TiledImage {
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fixed(self.width), Length::Fixed(self.height))
+    }
+
+    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &Limits) -> Node {
+        Node::new(limits.resolve(
+            Length::Fixed(self.width),
+            Length::Fixed(self.height),
+            Size::ZERO,
+        ))
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &cosmic::Theme,
+        _style: &Style,
+        layout: Layout<'_>,
+        _cursor: Cursor,
+        viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+
+        renderer.draw_image(
+            self.preview.clone(),
+            FilterMethod::Linear,
+            bounds,
+            cosmic::iced::Radians(0.0),
+            1.0,
+            [0.0; 4],
+        );
+
+        if let Some(ref pyramid) = self.pyramid {
+            self.draw_tiles(renderer, pyramid, bounds, viewport);
+        }
+    }
+}
+
+impl<'a> From<TiledImage> for Element<'a, Message> {
+    fn from(widget: TiledImage) -> Self {
+        Self::new(widget)
+    }
+}
+
+/// Helper function to create a TiledImage
+pub fn tiled_image(preview: Handle, pyramid: Option<Arc<ImagePyramid>>) -> TiledImage {
+    TiledImage::new(preview, pyramid)
+}
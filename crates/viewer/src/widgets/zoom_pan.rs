@@ -0,0 +1,3 @@
+mod widget;
+
+pub use widget::{ZoomPan, zoom_pan};
@@ -0,0 +1,297 @@
+//! Wrapper around the modal image area that turns pointer input into zoom and pan messages.
+//! Scrolling itself is still done by the wrapped scrollable; this widget only decides where to.
+
+use crate::message::{Message, ViewMessage};
+use cosmic::{
+    Element, Renderer,
+    iced::{
+        Length, Point, Rectangle, Size, Vector,
+        advanced::{
+            Clipboard, Layout, Shell, Widget,
+            layout::{Limits, Node},
+            overlay,
+            renderer::Style,
+            widget::{Operation, Tree, tree},
+        },
+        event::{Event, Status},
+        keyboard,
+        mouse::{self, Button, Cursor, ScrollDelta, click},
+    },
+};
+
+/// Zoom factor per wheel notch
+const WHEEL_ZOOM_STEP: f32 = 1.25;
+/// Zoom factor per pixel of a pinch (reported as Ctrl + smooth scroll)
+const PINCH_ZOOM_STEP: f32 = 1.01;
+
+pub struct ZoomPan<'a> {
+    content: Element<'a, Message>,
+    pannable: bool,
+    inspecting: bool,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    modifiers: keyboard::Modifiers,
+    drag_last: Option<Point>,
+    last_click: Option<click::Click>,
+}
+
+impl<'a> ZoomPan<'a> {
+    pub fn new(content: impl Into<Element<'a, Message>>) -> Self {
+        Self {
+            content: content.into(),
+            pannable: false,
+            inspecting: false,
+        }
+    }
+
+    /// Whether the image overflows the area and can be dragged around
+    pub fn pannable(mut self, pannable: bool) -> Self {
+        self.pannable = pannable;
+        self
+    }
+
+    /// Report the pointer position for the pixel inspector
+    pub fn inspecting(mut self, inspecting: bool) -> Self {
+        self.inspecting = inspecting;
+        self
+    }
+}
+
+impl Widget<Message, cosmic::Theme, Renderer> for ZoomPan<'_> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&mut self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_mut(&mut self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn layout(&self, tree: &mut Tree, renderer: &Renderer, limits: &Limits) -> Node {
+        let child = self
+            .content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits);
+        Node::with_children(child.size(), vec![child])
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &cosmic::Theme,
+        style: &Style,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout.children().next().unwrap(),
+            cursor,
+            viewport,
+        );
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.content.as_widget().operate(
+            &mut tree.children[0],
+            layout.children().next().unwrap(),
+            renderer,
+            operation,
+        );
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> Status {
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_mut::<State>();
+
+        match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = modifiers;
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                if let Some(pos) = cursor.position_in(bounds) {
+                    let message = match delta {
+                        // Mouse wheel notches zoom at the cursor
+                        ScrollDelta::Lines { y, .. } if y != 0.0 => ViewMessage::ZoomAt {
+                            factor: WHEEL_ZOOM_STEP.powf(y.clamp(-3.0, 3.0)),
+                            x: pos.x,
+                            y: pos.y,
+                        },
+                        // Touchpad pinch arrives as Ctrl + smooth scroll
+                        ScrollDelta::Pixels { y, .. } if state.modifiers.control() => {
+                            ViewMessage::ZoomAt {
+                                factor: PINCH_ZOOM_STEP.powf(y),
+                                x: pos.x,
+                                y: pos.y,
+                            }
+                        }
+                        // Two-finger touchpad scrolling pans with inertia
+                        ScrollDelta::Pixels { x, y } if self.pannable => {
+                            ViewMessage::Pan { dx: -x, dy: -y }
+                        }
+                        _ => return Status::Ignored,
+                    };
+
+                    shell.publish(Message::View(message));
+                    return Status::Captured;
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                if let Some(last) = state.drag_last {
+                    state.drag_last = Some(position);
+                    shell.publish(Message::View(ViewMessage::Pan {
+                        dx: last.x - position.x,
+                        dy: last.y - position.y,
+                    }));
+                    return Status::Captured;
+                }
+
+                // Not captured, the scrollable still tracks its scrollbars
+                if self.inspecting
+                    && let Some(pos) = cursor.position_in(bounds)
+                {
+                    shell.publish(Message::View(ViewMessage::InspectAt { x: pos.x, y: pos.y }));
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(Button::Left)) => {
+                if state.drag_last.take().is_some() {
+                    shell.publish(Message::View(ViewMessage::PanEnd));
+                    return Status::Captured;
+                }
+            }
+            _ => {}
+        }
+
+        // Let the scrollable handle its scrollbars first
+        let status = self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event.clone(),
+            layout.children().next().unwrap(),
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        );
+
+        if status == Status::Captured {
+            return status;
+        }
+
+        if let Event::Mouse(mouse::Event::ButtonPressed(Button::Left)) = event
+            && let Some(pos) = cursor.position_over(bounds)
+        {
+            let click = click::Click::new(pos, Button::Left, state.last_click);
+            state.last_click = Some(click);
+
+            if click.kind() == click::Kind::Double {
+                let local = pos - Vector::new(bounds.x, bounds.y);
+                shell.publish(Message::View(ViewMessage::ToggleZoomAt {
+                    x: local.x,
+                    y: local.y,
+                }));
+                return Status::Captured;
+            }
+
+            if self.pannable {
+                state.drag_last = Some(pos);
+                shell.publish(Message::View(ViewMessage::PanStart));
+                return Status::Captured;
+            }
+        }
+
+        Status::Ignored
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+
+        if state.drag_last.is_some() {
+            return mouse::Interaction::Grabbing;
+        }
+
+        let interaction = self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout.children().next().unwrap(),
+            cursor,
+            viewport,
+            renderer,
+        );
+
+        if interaction == mouse::Interaction::default()
+            && self.pannable
+            && cursor.is_over(layout.bounds())
+        {
+            return mouse::Interaction::Grab;
+        }
+
+        interaction
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, cosmic::Theme, Renderer>> {
+        self.content.as_widget_mut().overlay(
+            &mut tree.children[0],
+            layout.children().next().unwrap(),
+            renderer,
+            translation,
+        )
+    }
+}
+
+impl<'a> From<ZoomPan<'a>> for Element<'a, Message> {
+    fn from(widget: ZoomPan<'a>) -> Self {
+        Self::new(widget)
+    }
+}
+
+/// Helper function to create a ZoomPan wrapper
+pub fn zoom_pan<'a>(content: impl Into<Element<'a, Message>>) -> ZoomPan<'a> {
+    ZoomPan::new(content)
+}
@@ -10,10 +10,12 @@ menu-open-folder = Open Folder
 menu-recent-folders = Recent Folders
 menu-no-recent-folders = No recent folders
 menu-clear-recent = Clear Recent
+menu-add-bookmark = Bookmark Folder
 menu-set-wallpaper = Set as Wallpaper
 menu-save = Save
 menu-save-as = Save As...
 menu-delete = Delete
+menu-find-duplicates = Find Duplicates...
 menu-settings = Settings
 menu-quit = Quit
 
@@ -23,9 +25,14 @@ menu-zoom-in = Zoom In
 menu-zoom-out = Zoom Out
 menu-zoom-reset = Reset Zoom
 menu-zoom-fit = Fit to Window
+menu-next-page = Next Page
+menu-prev-page = Previous Page
 menu-fullscreen = Fullscreen
 menu-slideshow-start = Start Slideshow
 menu-slideshow-stop = Stop Slideshow
+menu-compare = Compare
+menu-inspector = Histogram and Pixel Inspector
+menu-background = Next Viewer Background
 menu-gallery = Gallery View
 menu-sort = Sort
 menu-sort-name = By Name
@@ -33,6 +40,22 @@ menu-sort-date = By Date
 menu-sort-size = By Size
 menu-sort-ascending = Ascending
 menu-sort-descending = Descending
+menu-sidebar-show = Show Sidebar
+menu-sidebar-hide = Hide Sidebar
+menu-timeline-show = Show Timeline
+menu-timeline-hide = Hide Timeline
+menu-timeline-day = Group by Day
+menu-timeline-month = Group by Month
+menu-timeline-year = Group by Year
+menu-reader-enter = Enter Reader Mode
+menu-reader-exit = Leave Reader Mode
+menu-reader-single = Single Page
+menu-reader-spread = Two-Page Spread
+menu-reader-continuous = Continuous Scroll
+menu-reader-rtl = Read Right to Left
+menu-reader-ltr = Read Left to Right
+menu-reader-cover-alone = Show Cover Alone
+menu-reader-cover-paired = Pair Cover With Next Page
 
 # Menu - Edit
 menu-edit = Edit
@@ -79,13 +102,25 @@ settings-view = View Settings
 settings-default-zoom = Default Zoom Level
 settings-fit-to-window = Fit Images to Window
 settings-smooth-scaling = Smooth Image Scaling
+settings-background = Viewer Background
+settings-background-theme = Theme
+settings-background-black = Black
+settings-background-white = White
+settings-background-gray = Gray
+settings-background-custom = Custom color
+settings-background-checkerboard = Checkerboard
+settings-background-color = Background Color
 settings-gallery = Gallery Settings
 settings-thumbnail-size = Thumbnail Size
 settings-thumbnail-small = Small (64px)
 settings-thumbnail-medium = Medium (128px)
 settings-thumbnail-large = Large (192px)
 settings-thumbnail-xlarge = Extra Large (256px)
+settings-gallery-layout = Layout
+settings-layout-grid = Grid
+settings-layout-justified = Justified rows
 settings-show-hidden = Show Hidden Files
+settings-include-extensionless = Include Images Without Extension
 settings-slideshow = Slideshow Settings
 settings-slideshow-interval = Slideshow Interval (seconds)
 settings-performance = Performance
@@ -100,6 +135,7 @@ status-loading = Loading...
 status-no-image = No image loaded
 status-image-count = Image { $current } of { $total }
 status-zoom-level = Zoom: { $percent }%
+status-damaged = Damaged file, only part of the image could be read
 
 # Edit Status
 edit-modified = Modified
@@ -129,6 +165,83 @@ delete-trash = Move to Trash
 delete-permanent = Delete Permanently
 delete-cancel = Cancel
 
+# Duplicates
+duplicates-title = Duplicate Images
+duplicates-threshold = Similarity threshold
+duplicates-scanning = Comparing images...
+duplicates-none = No duplicates found
+duplicates-exact = Identical files ({ $count })
+duplicates-similar = Similar images ({ $count })
+
+# Places Sidebar
+places-title = Places
+places-pictures = Pictures
+places-home = Home
+
+# Timeline
+timeline-section-count = { $count ->
+    [one] 1 image
+   *[other] { $count } images
+}
+timeline-loading = Reading capture dates...
+
+# Reader
+reader-page = Page { $page } of { $total }
+reader-pages = Pages { $first }–{ $last } of { $total }
+
+# Multi-page files
+page-of = Page { $page } of { $total }
+
+# Compare
+compare-title = Compare
+compare-split = Side by Side
+compare-swipe = Swipe
+compare-onion = Onion Skin
+compare-opacity = Opacity
+compare-original = Original
+compare-edited = Edited
+compare-fit = Fit
+
+# Inspector
+inspector-title = Inspector
+inspector-histogram-loading = Computing histogram...
+inspector-highlights-clipped = Highlights clipped: { $percent }%
+inspector-shadows-clipped = Shadows clipped: { $percent }%
+inspector-show-clipping = Show clipped pixels
+inspector-hint = Point at the image to inspect a pixel
+inspector-position = X { $x }, Y { $y }
+inspector-rgba = RGBA { $r }, { $g }, { $b }, { $a }
+inspector-approximate = Approximate, read from a preview
+inspector-copy-color = Copy Color
+
+# Image Information
+info-canvas = Canvas: { $width } x { $height }
+info-layers = Layers: { $count }
+info-extension-mismatch = Extension mismatch: the content is { $format }, not .{ $extension }
+
+# Notifications
+notification-show-details = Show details
+notification-hide-details = Hide details
+error-open-failed = Couldn't open { $name }
+error-not-found = The file no longer exists. It may have been moved, renamed or deleted.
+error-permission-denied = You don't have permission to read this file. Check its permissions or ask its owner for access.
+error-truncated = The file is incomplete. If it is still downloading or copying, wait for it to finish and try again.
+error-unsupported-codec = The file uses a compression or color mode that isn't supported. Try converting it in an image editor.
+error-unsupported-format = This file type isn't supported.
+error-corrupt = The file appears to be damaged and couldn't be decoded.
+error-too-large = The image is too large to open.
+error-save-failed = Couldn't save the image
+error-edit-failed = Couldn't apply the edit
+error-save-permission-denied = You don't have permission to write here. Use Save As to save a copy somewhere else.
+error-save-unsupported = Images can't be saved in this format. Use Save As with another file type.
+error-invalid-crop = The crop area lies outside the image. Adjust the crop and try again.
+error-try-again = Something went wrong. Try again, and see the details if it keeps happening.
+error-wallpaper-failed = Couldn't set the wallpaper
+error-wallpaper-hint = Your desktop didn't accept the request. Check that its settings service is running and try again.
+error-delete-failed = Couldn't delete the file
+error-trash-hint = The drive may not have a trash folder. You can delete the file permanently instead.
+error-delete-hint = Check that you have permission to change the folder the file is in.
+
 # Save Dialog
 save-dialog-title = Save Image As
 save-dialog-filter = Images